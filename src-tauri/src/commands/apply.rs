@@ -1,146 +1,122 @@
-use crate::models::{Album, AppError};
-use crate::services::{AudioService, CleanerService, RenamerService, ValidatorService};
-use regex::Regex;
-use std::fs;
-use std::path::Path;
-
-pub async fn apply_auto_correct_logic(mut album: Album) -> Result<Album, AppError> {
+use crate::db::Database;
+use crate::models::{Album, AppError, ChangeSet};
+use crate::services::journal::Transaction;
+use crate::services::{AudioService, CleanerService, PlannerService, ValidatorService};
+use std::path::{Path, PathBuf};
+
+pub async fn apply_auto_correct_logic(
+    db: &Database,
+    mut album: Album,
+    plan: ChangeSet,
+) -> Result<Album, AppError> {
     // Sanitize album path (remove trailing slash)
-    if album.path.ends_with('/') || album.path.ends_with('\\') {
-        album.path.pop();
-    }
-
-    let renamer = RenamerService::new();
-    let cleaner = CleanerService::new();
-
-    // 2. Rename Folder Logic
-    // Calculate Year Range
-    let years: Vec<u32> = album
-        .tracks
-        .iter()
-        .filter_map(|t| t.year)
-        .filter(|&y| y > 0)
-        .collect();
-
-    let year_min = years.iter().min().copied();
-    let year_max = years.iter().max().copied();
+    let album_path = album.path.trim_end_matches(['/', '\\']).to_string();
 
-    // Use the first track to determine Album Artist (heuristic)
-    if let Some(first_track) = album.tracks.first() {
-        let artist = &first_track.artist;
-
-        // Use album.title as the source of truth for the folder name,
-        // but strip the year prefix if present (to avoid double year like "(2023) (2023) Title")
-        let title_clean = if album.title.starts_with('(') {
-            let re_year_prefix = Regex::new(r"^\(\d{4}(?:-\d{2})?\)\s*").unwrap();
-            re_year_prefix.replace(&album.title, "").to_string()
-        } else {
-            album.title.clone()
-        };
+    // 1. Vérifier que le plan correspond toujours au disque
+    if album_path != plan.album_path {
+        return Err(AppError::Validation(
+            "Le plan ne correspond pas à cet album".to_string(),
+        ));
+    }
+    if PlannerService::empreinte_dossier(Path::new(&album_path))? != plan.fingerprint {
+        return Err(AppError::Validation(
+            "Le dossier a été modifié depuis la planification, relancez l'analyse".to_string(),
+        ));
+    }
+    // Le plan vient du client : il doit être celui que le serveur calcule, sans chemin hors album
+    PlannerService::verifier_plan(&album, &plan)?;
+
+    // 2. Exécuter le plan dans une transaction journalisée (rollback automatique en cas d'échec)
+    let mut tx = Transaction::demarrer(db, "apply_auto_correct", &album_path)?;
+    let root = match executer_plan(&mut tx, &album_path, &plan) {
+        Ok(root) => {
+            tx.valider()?;
+            root
+        }
+        Err(e) => return Err(tx.annuler_apres(e)),
+    };
 
-        // Fallback to track album if album title is empty
-        let title = if title_clean.trim().is_empty() {
-            &first_track.album
-        } else {
-            &title_clean
+    // 3. Refresh album from disk
+    let audio_service = AudioService::new();
+    album.path = root.to_string_lossy().to_string();
+    for track in &mut album.tracks {
+        let Ok(relative) = Path::new(&track.path).strip_prefix(&album_path) else {
+            continue;
         };
+        let relative = relative.to_string_lossy().to_string();
+        let new_relative = plan
+            .file_renames
+            .iter()
+            .find(|r| r.from == relative)
+            .map(|r| r.to.clone())
+            .unwrap_or(relative);
+        let new_path = root.join(new_relative).to_string_lossy().to_string();
 
-        // If range, we might want to format manually, but let's use processor for consistency
-        let new_folder_name = renamer.format_folder_name(artist, title, year_min, year_max);
-
-        let current_path_buf = Path::new(&album.path).to_path_buf();
-        let current_path = current_path_buf.as_path();
-
-        if let Some(parent) = current_path.parent() {
-            let new_path = parent.join(&new_folder_name);
-
-            // Only rename if different and target doesn't exist
-            if new_path != current_path && !new_path.exists() {
-                match fs::rename(current_path, &new_path) {
-                    Ok(_) => {
-                        // Update Album path
-                        let new_path_str = new_path.to_string_lossy().to_string();
-                        let old_path_str = album.path.clone();
-
-                        album.path = new_path_str.clone();
-
-                        // Update all tracks paths (assuming they are inside the folder)
-                        for track in &mut album.tracks {
-                            let track_path_obj = Path::new(&track.path);
-                            let old_path_obj = Path::new(&old_path_str);
-                            if let Ok(relative) = track_path_obj.strip_prefix(old_path_obj) {
-                                let new_track_path = Path::new(&new_path_str).join(relative);
-                                track.path = new_track_path.to_string_lossy().to_string();
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to rename folder: {}", e);
-                        return Err(AppError::Io(format!("Failed to rename folder: {}", e)));
-                    }
-                }
-            }
+        // Reset modification state since we just saved it
+        match audio_service.lire_metadonnees(&new_path) {
+            Ok(fresh) => *track = fresh,
+            Err(_) => track.path = new_path,
         }
     }
 
-    // 3. Flatten & Rename Files
-    let album_path = Path::new(&album.path);
-    cleaner.rename_track_files(&mut album.tracks, album_path, &renamer);
+    // Update cover_path in the struct to reflect the new location
+    let target_cover = root.join("cover.jpg");
+    if target_cover.exists() {
+        album.cover_path = Some(target_cover.to_string_lossy().to_string());
+    } else {
+        album.cover_path = None;
+    }
+
+    // 4. Update Status (Re-validate instead of forcing Clean to check for missing playlist)
+    ValidatorService::evaluate_album_status(&mut album);
+
+    Ok(album)
+}
 
-    // 3b. Write Metadata (Tags) to reflect changes
+/// Exécute le plan et renvoie le chemin final du dossier de l'album
+fn executer_plan(
+    tx: &mut Transaction,
+    album_path: &str,
+    plan: &ChangeSet,
+) -> Result<PathBuf, AppError> {
+    let cleaner = CleanerService::new();
     let audio_service = AudioService::new();
-    for track in &mut album.tracks {
-        let path_obj = Path::new(&track.path);
-        // Ensure file exists before writing
-        if !path_obj.exists() {
-            let mut debug_info = String::new();
-            if let Some(parent) = path_obj.parent() {
-                debug_info.push_str(&format!(" (Parent: {}, Files: ", parent.display()));
-                if let Ok(entries) = std::fs::read_dir(parent) {
-                    let files: Vec<String> = entries
-                        .filter_map(|e| e.ok().map(|e| e.file_name().to_string_lossy().to_string()))
-                        .collect();
-                    debug_info.push_str(&files.join(", "));
-                } else {
-                    debug_info.push_str("Cannot read parent");
-                }
-                debug_info.push(')');
-            }
 
+    // Rename Folder
+    let mut root = PathBuf::from(album_path);
+    if let Some(folder) = &plan.folder_rename {
+        let new_path = PathBuf::from(&folder.to);
+        if new_path.exists() {
             return Err(AppError::Io(format!(
-                "Fichier introuvable pour écriture tags: {}{}",
-                track.path, debug_info
+                "Le dossier cible existe déjà: {}",
+                folder.to
             )));
         }
+        tx.renommer(&root, &new_path)?;
+        root = new_path;
+    }
 
-        if let Err(e) = audio_service.ecrire_metadonnees(track) {
+    // Flatten & Rename Files
+    cleaner.executer_renommages(tx, &root, &plan.file_renames)?;
+
+    // Write Metadata (Tags), only the planned fields
+    for diff in &plan.tag_diffs {
+        let path = root.join(&diff.file);
+        let mut track = audio_service.lire_metadonnees(&path.to_string_lossy())?;
+        PlannerService::appliquer_changements(&mut track, &diff.changes);
+
+        if let Err(e) = tx.ecrire_tags(&audio_service, &track) {
             return Err(AppError::Audio(format!(
                 "Erreur écriture tags {}: {}",
                 track.path, e
             )));
         }
-
-        // Reset modification state since we just saved it
-        track.is_modified = false;
-        track.original_metadata = Some(Box::new(track.clone()));
-    }
-
-    // 4. Handle Cover Image (Recursive Search)
-    cleaner.handle_cover_image(album_path);
-
-    // Update cover_path in the struct to reflect the new location
-    let target_cover = album_path.join("cover.jpg");
-    if target_cover.exists() {
-        album.cover_path = Some(target_cover.to_string_lossy().to_string());
-    } else {
-        album.cover_path = None;
     }
 
-    // 5. Clean Directory (Delete junk, empty folders)
-    cleaner.clean_directory(album_path);
+    // Cover Image & Clean Directory (junk, empty folders)
+    cleaner.executer_deplacements(tx, &root, &plan.cover_moves)?;
+    cleaner.executer_suppressions(tx, &root, &plan.deletions)?;
+    cleaner.supprimer_dossiers_vides(&root);
 
-    // 6. Update Status (Re-validate instead of forcing Clean to check for missing playlist)
-    ValidatorService::evaluate_album_status(&mut album);
-
-    Ok(album)
+    Ok(root)
 }
@@ -0,0 +1,22 @@
+use crate::db::Database;
+use crate::models::{AppError, CleaningRule};
+use crate::services::CleaningRuleService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn add_cleaning_rule(
+    db: State<'_, Database>,
+    rule: CleaningRule,
+) -> Result<CleaningRule, AppError> {
+    CleaningRuleService::create(&db, rule)
+}
+
+#[tauri::command]
+pub async fn get_cleaning_rules(db: State<'_, Database>) -> Result<Vec<CleaningRule>, AppError> {
+    CleaningRuleService::get_all(&db)
+}
+
+#[tauri::command]
+pub async fn delete_cleaning_rule(db: State<'_, Database>, id: i64) -> Result<(), AppError> {
+    CleaningRuleService::delete(&db, id)
+}
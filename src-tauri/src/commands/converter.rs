@@ -1,25 +1,88 @@
+use crate::models::{ConvertOptions, ConvertResult, FfmpegInfo, TrimProposal};
 use crate::services::converter::ConverterService;
+use crate::services::FfmpegService;
 use std::path::Path;
 
+/// Convertit un fichier ; sans `options`, MP3 au débit `bitrate` ("192k") comme auparavant.
+/// La sortie est écrite à côté de la source, suffixée " (converted)" si l'extension ne change pas.
 #[tauri::command]
-pub async fn convert_file(input_path: String, bitrate: String) -> Result<String, String> {
+pub async fn convert_file(
+    input_path: String,
+    bitrate: Option<String>,
+    options: Option<ConvertOptions>,
+    output_path: Option<String>,
+) -> Result<ConvertResult, String> {
     let service = ConverterService::new();
 
     let input = Path::new(&input_path);
     if !input.exists() {
         return Err("Input file does not exist".to_string());
     }
+    let options = options.unwrap_or_else(|| ConvertOptions {
+        bitrate_kbps: bitrate.and_then(|b| b.trim_end_matches(['k', 'K']).parse().ok()),
+        ..ConvertOptions::default()
+    });
 
-    let output_path = input.with_extension("mp3");
-    let output_str = output_path.to_str().ok_or("Invalid path")?.to_string();
-    let output_str_clone = output_str.clone();
+    let output_str = match output_path {
+        Some(path) => path,
+        None => {
+            let extension = options.format.extension();
+            let mut output = input.with_extension(extension);
+            if output == input {
+                let stem = input.file_stem().ok_or("Invalid path")?.to_string_lossy();
+                output = input.with_file_name(format!("{} (converted).{}", stem, extension));
+            }
+            output.to_str().ok_or("Invalid path")?.to_string()
+        }
+    };
+    if Path::new(&output_str) == input {
+        return Err("La conversion ne peut pas remplacer le fichier source".to_string());
+    }
 
     // Run in blocking thread to avoid blocking async runtime
     tauri::async_runtime::spawn_blocking(move || {
-        service.convert_to_mp3(&input_path, &output_str_clone, &bitrate)
+        service.convertir(&input_path, &output_str, &options)
     })
     .await
-    .map_err(|e| e.to_string())??;
+    .map_err(|e| e.to_string())?
+}
+
+/// Présence et version de ffmpeg, requis pour MP3, Opus et AAC
+#[tauri::command]
+pub async fn get_ffmpeg_info() -> Result<FfmpegInfo, String> {
+    tauri::async_runtime::spawn_blocking(FfmpegService::detecter)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Écrit la piste rognée à côté de l'originale ("<nom> (trimmed).<ext>") sauf chemin explicite
+#[tauri::command]
+pub async fn apply_trim(
+    proposal: TrimProposal,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let service = ConverterService::new();
+
+    let input = Path::new(&proposal.path);
+    let output_str = match output_path {
+        Some(path) => path,
+        None => {
+            let stem = input.file_stem().ok_or("Invalid path")?.to_string_lossy();
+            let ext = input.extension().ok_or("Invalid path")?.to_string_lossy();
+            input
+                .with_file_name(format!("{} (trimmed).{}", stem, ext))
+                .to_string_lossy()
+                .to_string()
+        }
+    };
+    if Path::new(&output_str) == input {
+        return Err("Le rognage ne peut pas remplacer le fichier source".to_string());
+    }
+    let output_str_clone = output_str.clone();
+
+    tauri::async_runtime::spawn_blocking(move || service.rogner(&proposal, &output_str_clone))
+        .await
+        .map_err(|e| e.to_string())??;
 
     Ok(output_str)
 }
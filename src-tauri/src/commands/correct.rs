@@ -1,8 +1,12 @@
 use crate::commands::apply::apply_auto_correct_logic;
 use crate::db::Database;
-use crate::models::{Album, AppError};
+use crate::models::changeset::TagField;
+use crate::models::{Album, AppError, ChangeSet, RenumberMode};
 use crate::services::processor::ReplacementRule;
-use crate::services::{ExceptionService, MetadataProcessorService};
+use crate::services::{
+    CompilationService, ConsistencyService, ExceptionService, MetadataProcessorService, NumberingService,
+    PlannerService,
+};
 use regex::Regex;
 use std::path::Path;
 use tauri::State;
@@ -17,7 +21,7 @@ pub async fn preview_auto_correct(
     // Fetch exceptions
     let mut exceptions_list = ExceptionService::get_all(&db)?;
     // Sort by length descending to handle overlapping matches (e.g. "New York" before "New")
-    exceptions_list.sort_by(|a, b| b.original.len().cmp(&a.original.len()));
+    exceptions_list.sort_by_key(|ex| std::cmp::Reverse(ex.original.len()));
 
     let mut rules = Vec::new();
     for ex in exceptions_list {
@@ -55,6 +59,11 @@ pub async fn preview_auto_correct(
         }
     }
 
+    // Remove "NN - " prefix (e.g. "01 - ")
+    let re_prefix = Regex::new(r"^\d{2,3}\s*-\s*").unwrap();
+
+    let compilation = CompilationService::est_compilation(&album);
+    let various_artists = CompilationService::nom_various_artists();
     // 1. Correct Filename Only (as requested by user workflow change)
     for track in &mut album.tracks {
         // processor.nettoyer_track(track, &exceptions_map); // Disabled: User wants to clean filename instead
@@ -70,8 +79,6 @@ pub async fn preview_auto_correct(
         let path = Path::new(&track.filename);
         if let Some(stem) = path.file_stem() {
             let stem_str = stem.to_string_lossy().to_string();
-            // Remove "NN - " prefix (e.g. "01 - ")
-            let re_prefix = Regex::new(r"^\d{2,3}\s*-\s*").unwrap();
             let clean_title = re_prefix.replace(&stem_str, "").to_string();
 
             if track.title != clean_title {
@@ -90,9 +97,15 @@ pub async fn preview_auto_correct(
             track.is_modified = true;
         }
 
-        // Enforce Album Artist == Artist (User Rule)
-        if track.album_artist != track.artist {
-            track.album_artist = track.artist.clone();
+        // Enforce Album Artist == Artist (User Rule), "Various Artists" pour une compilation
+        let album_artist = if compilation {
+            various_artists.clone()
+        } else {
+            track.artist.clone()
+        };
+        if track.album_artist != album_artist || track.compilation != compilation {
+            track.album_artist = album_artist;
+            track.compilation = compilation;
             track.is_modified = true;
         }
     }
@@ -108,13 +121,44 @@ pub async fn preview_auto_correct(
         };
 
         album.title = format!("{}{}", year_str, first_track.album);
-        album.artist = first_track.artist.clone();
+        album.artist = if compilation {
+            various_artists
+        } else {
+            first_track.artist.clone()
+        };
     }
 
     Ok(album)
 }
 
+/// Aperçu de la renumérotation, à enregistrer ensuite comme une correction classique
+#[tauri::command]
+pub async fn preview_renumber(mut album: Album, mode: RenumberMode) -> Result<Album, AppError> {
+    NumberingService::renumeroter(&mut album, mode);
+    Ok(album)
+}
+
+/// Aperçu de l'unification des tags d'album (valeur choisie ou majoritaire)
+#[tauri::command]
+pub async fn preview_unify(
+    mut album: Album,
+    field: Option<TagField>,
+    value: Option<String>,
+) -> Result<Album, AppError> {
+    ConsistencyService::unifier(&mut album, field, value);
+    Ok(album)
+}
+
 #[tauri::command]
-pub async fn apply_auto_correct(album: Album) -> Result<Album, AppError> {
-    apply_auto_correct_logic(album).await
+pub async fn plan_auto_correct(album: Album) -> Result<ChangeSet, AppError> {
+    PlannerService::planifier(&album)
+}
+
+#[tauri::command]
+pub async fn apply_auto_correct(
+    db: State<'_, Database>,
+    album: Album,
+    plan: ChangeSet,
+) -> Result<Album, AppError> {
+    apply_auto_correct_logic(&db, album, plan).await
 }
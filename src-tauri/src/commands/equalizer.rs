@@ -0,0 +1,59 @@
+use crate::db::Database;
+use crate::models::{AppError, EqPreset, EqSettings};
+use crate::services::player::AudioPlayerState;
+use crate::services::{EqImportService, EqPresetService};
+use std::path::Path;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_equalizer(db: State<'_, Database>) -> Result<EqSettings, AppError> {
+    EqPresetService::charger(&db)
+}
+
+#[tauri::command]
+pub async fn set_equalizer(
+    db: State<'_, Database>,
+    state: State<'_, AudioPlayerState>,
+    settings: EqSettings,
+) -> Result<(), AppError> {
+    EqPresetService::enregistrer(&db, &settings)?;
+    state.set_equalizer(settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_eq_presets(db: State<'_, Database>) -> Result<Vec<EqPreset>, AppError> {
+    EqPresetService::lister(&db)
+}
+
+/// Un préréglage du même nom est remplacé
+#[tauri::command]
+pub async fn save_eq_preset(
+    db: State<'_, Database>,
+    name: String,
+    settings: EqSettings,
+) -> Result<EqPreset, AppError> {
+    EqPresetService::sauvegarder(&db, &name, settings)
+}
+
+#[tauri::command]
+pub async fn delete_eq_preset(db: State<'_, Database>, id: i64) -> Result<(), AppError> {
+    EqPresetService::supprimer(&db, id)
+}
+
+/// Importe un fichier EqualizerAPO / AutoEq comme préréglage, nommé d'après le fichier par défaut
+#[tauri::command]
+pub async fn import_eq_preset(
+    db: State<'_, Database>,
+    path: String,
+    name: Option<String>,
+) -> Result<EqPreset, AppError> {
+    let path = Path::new(&path);
+    let settings = EqImportService::lire(path)?;
+    let name = name.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+    EqPresetService::sauvegarder(&db, &name, settings)
+}
@@ -0,0 +1,18 @@
+use crate::db::Database;
+use crate::models::{Album, AppError};
+use crate::services::validator_integrity::regles_integrite;
+use crate::services::{IntegrityService, ValidatorService};
+use tauri::{AppHandle, Manager};
+
+/// Décode intégralement chaque piste et ajoute les problèmes trouvés à la validation de l'album
+#[tauri::command]
+pub async fn verify_album(app: AppHandle, mut album: Album) -> Result<Album, AppError> {
+    // Décodage complet de tout l'album : hors du runtime async
+    tauri::async_runtime::spawn_blocking(move || {
+        let rapports = IntegrityService::verifier_album(&app.state::<Database>(), &album)?;
+        ValidatorService::evaluer_avec(&mut album, regles_integrite(&rapports));
+        Ok(album)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(e.to_string()))?
+}
@@ -0,0 +1,16 @@
+use crate::db::Database;
+use crate::models::{AppError, JournalOperation};
+use crate::services::JournalService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_operations(db: State<'_, Database>) -> Result<Vec<JournalOperation>, AppError> {
+    JournalService::lister(&db)
+}
+
+#[tauri::command]
+pub async fn undo_last_operation(
+    db: State<'_, Database>,
+) -> Result<Option<JournalOperation>, AppError> {
+    JournalService::annuler_derniere(&db)
+}
@@ -0,0 +1,24 @@
+use crate::db::Database;
+use crate::models::{Album, AlbumLoudness, AppError};
+use crate::services::LoudnessService;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Mesure EBU R128 de l'album, avec un événement "loudness-progress" avant chaque piste
+#[tauri::command]
+pub async fn analyze_loudness(
+    app: AppHandle,
+    album: Album,
+    write_tags: bool,
+) -> Result<AlbumLoudness, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut resultat = LoudnessService::analyser_album(&album, |progression| {
+            let _ = app.emit("loudness-progress", progression);
+        })?;
+        if write_tags {
+            LoudnessService::ecrire_tags(&app.state::<Database>(), &mut resultat)?;
+        }
+        Ok(resultat)
+    })
+    .await
+    .map_err(|e| AppError::Unknown(e.to_string()))?
+}
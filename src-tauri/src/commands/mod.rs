@@ -1,16 +1,32 @@
 pub mod apply;
+pub mod cleaning_rules;
 pub mod converter;
 pub mod correct;
 pub mod cover;
+pub mod equalizer;
 pub mod exception;
 pub mod history;
+pub mod integrity;
+pub mod journal;
+pub mod loudness;
 pub mod metadata;
+pub mod output;
 pub mod player;
 pub mod playlist;
+pub mod quarantine;
+pub mod queue;
+pub mod render;
 pub mod scan;
+pub mod settings;
+pub mod silence;
+pub mod spectral;
+pub mod validator;
+pub mod waveform;
 pub mod write;
 
-pub use correct::{apply_auto_correct, preview_auto_correct};
+pub use correct::{
+    apply_auto_correct, plan_auto_correct, preview_auto_correct, preview_renumber, preview_unify,
+};
 pub use exception::{add_exception, delete_exception, get_exceptions};
 pub use scan::scan_directory;
 pub use scan::scan_junk;
@@ -0,0 +1,29 @@
+use crate::db::Database;
+use crate::models::{AppError, OutputDevice, OutputSettings};
+use crate::services::player::AudioPlayerState;
+use crate::services::OutputService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_output_devices() -> Result<Vec<OutputDevice>, AppError> {
+    OutputService::lister()
+}
+
+#[tauri::command]
+pub async fn get_output_settings(db: State<'_, Database>) -> Result<OutputSettings, AppError> {
+    OutputService::charger(&db)
+}
+
+/// Bascule la lecture sur la sortie demandée et renvoie son nom ; le réglage n'est enregistré qu'en cas de succès
+#[tauri::command]
+pub async fn set_output(
+    db: State<'_, Database>,
+    state: State<'_, AudioPlayerState>,
+    settings: OutputSettings,
+) -> Result<String, AppError> {
+    let nom = state
+        .set_output(settings.clone())
+        .map_err(AppError::Audio)?;
+    OutputService::enregistrer(&db, &settings)?;
+    Ok(nom)
+}
@@ -1,9 +1,18 @@
+use crate::db::Database;
+use crate::models::{AbLoop, EqSettings, PlayerStateInfo, ReplayGainSettings, SeekResult};
 use crate::services::player::AudioPlayerState;
+use crate::services::player_transport::{VITESSE_MAX, VITESSE_MIN};
+use crate::services::{GaplessService, ReplayGainService};
 use tauri::State;
 
+/// `from_album` : lecture lancée depuis un album, le mode ReplayGain automatique utilise alors le gain d'album
 #[tauri::command]
-pub async fn play_track(state: State<'_, AudioPlayerState>, path: String) -> Result<(), String> {
-    state.play(&path)
+pub async fn play_track(
+    state: State<'_, AudioPlayerState>,
+    path: String,
+    from_album: Option<bool>,
+) -> Result<(), String> {
+    state.play(&path, from_album.unwrap_or(false))
 }
 
 #[tauri::command]
@@ -36,8 +45,107 @@ pub async fn seek_track(state: State<'_, AudioPlayerState>, time: f32) -> Result
     Ok(())
 }
 
+/// Positionnement à la trame près ; renvoie la position réellement atteinte
 #[tauri::command]
-pub async fn set_eq(state: State<'_, AudioPlayerState>, bass: f32, mid: f32, treble: f32) -> Result<(), String> {
-    state.set_eq(bass, mid, treble);
+pub async fn seek_exact(
+    state: State<'_, AudioPlayerState>,
+    position_sec: f64,
+) -> Result<SeekResult, String> {
+    state.seek_exact(position_sec)
+}
+
+/// `speed` : 1 pour la vitesse normale, de 0,25 à 4 ; la hauteur est conservée
+#[tauri::command]
+pub async fn set_playback_speed(
+    state: State<'_, AudioPlayerState>,
+    speed: f32,
+) -> Result<(), String> {
+    if !(VITESSE_MIN..=VITESSE_MAX).contains(&speed) {
+        return Err(format!(
+            "Vitesse hors limites ({} - {})",
+            VITESSE_MIN, VITESSE_MAX
+        ));
+    }
+    state.set_speed(speed);
+    Ok(())
+}
+
+/// Boucle la lecture entre `start_sec` et `end_sec` sur la piste courante
+#[tauri::command]
+pub async fn set_ab_loop(
+    state: State<'_, AudioPlayerState>,
+    start_sec: f64,
+    end_sec: f64,
+) -> Result<AbLoop, String> {
+    state
+        .set_loop(Some((start_sec, end_sec)))?
+        .ok_or_else(|| "Boucle refusée par le lecteur".to_string())
+}
+
+#[tauri::command]
+pub async fn clear_ab_loop(state: State<'_, AudioPlayerState>) -> Result<(), String> {
+    state.set_loop(None).map(|_| ())
+}
+
+/// Réglage rapide graves / médiums / aigus, non enregistré (voir `set_equalizer`)
+#[tauri::command]
+pub async fn set_eq(
+    state: State<'_, AudioPlayerState>,
+    bass: f32,
+    mid: f32,
+    treble: f32,
+) -> Result<(), String> {
+    state.set_equalizer(EqSettings::trois_bandes(bass, mid, treble));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_replaygain(db: State<'_, Database>) -> Result<ReplayGainSettings, String> {
+    ReplayGainService::charger(&db).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_replaygain(
+    db: State<'_, Database>,
+    state: State<'_, AudioPlayerState>,
+    settings: ReplayGainSettings,
+) -> Result<(), String> {
+    ReplayGainService::enregistrer(&db, &settings).map_err(|e| e.to_string())?;
+    state.set_replaygain(settings);
     Ok(())
 }
+
+#[tauri::command]
+pub async fn get_crossfade(db: State<'_, Database>) -> Result<f32, String> {
+    GaplessService::charger_fondu(&db).map_err(|e| e.to_string())
+}
+
+/// `seconds` : durée du fondu enchaîné, 0 pour enchaîner sans blanc
+#[tauri::command]
+pub async fn set_crossfade(
+    db: State<'_, Database>,
+    state: State<'_, AudioPlayerState>,
+    seconds: f32,
+) -> Result<(), String> {
+    GaplessService::definir_fondu(&db, seconds).map_err(|e| e.to_string())?;
+    state.set_crossfade(seconds.max(0.0));
+    Ok(())
+}
+
+/// Active ou coupe les événements "player-meter" (crête, RMS et spectre, environ 30 par seconde)
+#[tauri::command]
+pub async fn set_meter_enabled(
+    state: State<'_, AudioPlayerState>,
+    enabled: bool,
+) -> Result<(), String> {
+    state.set_meter(enabled);
+    Ok(())
+}
+
+/// État courant du lecteur, pour le frontend qui préfère interroger plutôt qu'écouter les événements
+#[tauri::command]
+pub async fn get_player_state(
+    state: State<'_, AudioPlayerState>,
+) -> Result<PlayerStateInfo, String> {
+    Ok(state.state())
+}
@@ -0,0 +1,38 @@
+use crate::db::Database;
+use crate::models::{AppError, QuarantineEntry, QuarantineSettings};
+use crate::services::QuarantineService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_quarantine(db: State<'_, Database>) -> Result<Vec<QuarantineEntry>, AppError> {
+    QuarantineService::lister(&db)
+}
+
+#[tauri::command]
+pub async fn restore_quarantined(
+    db: State<'_, Database>,
+    id: i64,
+) -> Result<QuarantineEntry, AppError> {
+    QuarantineService::restaurer(&db, id)
+}
+
+/// Supprime les fichiers dont la durée de rétention est dépassée (aussi fait au démarrage)
+#[tauri::command]
+pub async fn purge_quarantine(db: State<'_, Database>) -> Result<usize, AppError> {
+    QuarantineService::purger(&db)
+}
+
+#[tauri::command]
+pub async fn get_quarantine_settings(
+    db: State<'_, Database>,
+) -> Result<QuarantineSettings, AppError> {
+    QuarantineService::reglages(&db)
+}
+
+#[tauri::command]
+pub async fn set_quarantine_settings(
+    db: State<'_, Database>,
+    settings: QuarantineSettings,
+) -> Result<(), AppError> {
+    QuarantineService::definir_reglages(&db, &settings)
+}
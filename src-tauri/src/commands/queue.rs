@@ -0,0 +1,85 @@
+use crate::models::playlist::PlaylistTrack;
+use crate::models::{QueueEntry, QueueState, RepeatMode, Track};
+use crate::services::player::AudioPlayerState;
+use std::path::Path;
+use tauri::State;
+
+/// Remplace la file par les pistes d'un album (gain d'album en mode ReplayGain automatique)
+#[tauri::command]
+pub async fn play_album(
+    state: State<'_, AudioPlayerState>,
+    tracks: Vec<Track>,
+    start_index: Option<usize>,
+) -> Result<(), String> {
+    let entries = tracks.iter().map(QueueEntry::from).collect();
+    state.play_queue(entries, start_index.unwrap_or(0), true);
+    Ok(())
+}
+
+/// Remplace la file par les pistes d'une playlist ; les chemins relatifs partent du dossier de la playlist
+#[tauri::command]
+pub async fn play_playlist(
+    state: State<'_, AudioPlayerState>,
+    playlist_path: String,
+    tracks: Vec<PlaylistTrack>,
+    start_index: Option<usize>,
+) -> Result<(), String> {
+    let dossier = Path::new(&playlist_path).parent().unwrap_or(Path::new(""));
+    let entries = tracks
+        .iter()
+        .map(|track| QueueEntry {
+            // Un chemin absolu remplace le dossier lors de la jonction
+            path: dossier.join(&track.path).to_string_lossy().to_string(),
+            ..QueueEntry::from(track)
+        })
+        .collect();
+    state.play_queue(entries, start_index.unwrap_or(0), false);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn enqueue_tracks(
+    state: State<'_, AudioPlayerState>,
+    tracks: Vec<Track>,
+) -> Result<(), String> {
+    state.enqueue(tracks.iter().map(QueueEntry::from).collect());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clear_queue(state: State<'_, AudioPlayerState>) -> Result<(), String> {
+    state.clear_queue();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn next_track(state: State<'_, AudioPlayerState>) -> Result<(), String> {
+    state.next();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn previous_track(state: State<'_, AudioPlayerState>) -> Result<(), String> {
+    state.previous();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_shuffle(state: State<'_, AudioPlayerState>, shuffle: bool) -> Result<(), String> {
+    state.set_shuffle(shuffle);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_repeat(
+    state: State<'_, AudioPlayerState>,
+    repeat: RepeatMode,
+) -> Result<(), String> {
+    state.set_repeat(repeat);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_queue(state: State<'_, AudioPlayerState>) -> Result<QueueState, String> {
+    Ok(state.queue_state())
+}
@@ -0,0 +1,42 @@
+use crate::db::Database;
+use crate::models::{AppError, RenderOptions, RenderResult};
+use crate::services::{EqPresetService, RenderService, ReplayGainService};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Exporte `path` en WAV vers `dest` à travers l'EQ et le ReplayGain (ceux du lecteur par défaut)
+#[tauri::command]
+pub async fn render_track(
+    db: State<'_, Database>,
+    path: String,
+    dest: String,
+    options: Option<RenderOptions>,
+) -> Result<RenderResult, AppError> {
+    let options = options.unwrap_or_default();
+    let eq = match options.eq {
+        Some(eq) => eq,
+        None => EqPresetService::charger(&db)?,
+    };
+    let replaygain = match options.replaygain {
+        Some(reglages) => reglages,
+        None => ReplayGainService::charger(&db)?,
+    };
+    if Path::new(&dest) == Path::new(&path) {
+        return Err(AppError::Validation(
+            "Le rendu ne peut pas remplacer le fichier source".to_string(),
+        ));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        RenderService::rendre(
+            &PathBuf::from(path),
+            &PathBuf::from(dest),
+            eq,
+            &replaygain,
+            options.from_album,
+            options.format,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Unknown(e.to_string()))?
+}
@@ -1,5 +1,5 @@
 use crate::db::Database;
-use crate::models::{AppError, ScanResult};
+use crate::models::{Album, AppError, JunkEntry, ScanResult};
 use crate::services::{ScannerService, InspectorService};
 use tauri::State;
 
@@ -24,35 +24,6 @@ pub async fn scan_directory(path: String, db: State<'_, Database>) -> Result<Sca
 }
 
 #[tauri::command]
-pub async fn scan_junk(
-    path: String, 
-    artist: Option<String>, 
-    title: Option<String>,
-    year: Option<u32>,
-    year_min: Option<u32>,
-    year_max: Option<u32>
-) -> Result<Vec<String>, AppError> {
-    // Calcul de la chaîne d'année (identique à check_files)
-    let year_str = if let (Some(min), Some(max)) = (year_min, year_max) {
-             if min > 0 && max > 0 && min != max {
-                 let max_short = max % 100;
-                 format!("{}-{:02}", min, max_short)
-             } else {
-                 year.unwrap_or(0).to_string()
-             }
-    } else {
-             year.unwrap_or(0).to_string()
-    };
-    
-    let context = if let (Some(a), Some(t)) = (artist.as_ref(), title.as_ref()) {
-        if !a.is_empty() && !t.is_empty() {
-            Some((a.as_str(), year_str.as_str(), t.as_str()))
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    InspectorService::detecter_fichiers_inutiles(&path, context)
+pub async fn scan_junk(album: Album) -> Result<Vec<JunkEntry>, AppError> {
+    InspectorService::detecter_fichiers_inutiles(&album)
 }
@@ -0,0 +1,17 @@
+use crate::db::Database;
+use crate::models::AppError;
+use crate::services::CompilationService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_various_artists_name() -> Result<String, AppError> {
+    Ok(CompilationService::nom_various_artists())
+}
+
+#[tauri::command]
+pub async fn set_various_artists_name(
+    db: State<'_, Database>,
+    name: String,
+) -> Result<(), AppError> {
+    CompilationService::definir_nom(&db, &name)
+}
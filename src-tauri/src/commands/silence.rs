@@ -0,0 +1,23 @@
+use crate::models::{Album, AppError, SilenceAnalysis};
+use crate::services::validator_silence::regles_silence;
+use crate::services::{SilenceService, ValidatorService};
+
+/// Mesure silences et écrêtage de chaque piste lisible ; `propose_trim` ajoute un rognage pour les FLAC/WAV trop silencieux
+#[tauri::command]
+pub async fn analyze_silence(
+    mut album: Album,
+    propose_trim: bool,
+) -> Result<SilenceAnalysis, AppError> {
+    // Décodage complet de l'album : hors du runtime async
+    tauri::async_runtime::spawn_blocking(move || {
+        let (reports, errors) = SilenceService::analyser_album(&album, propose_trim);
+        ValidatorService::evaluer_avec(&mut album, regles_silence(&reports));
+        Ok(SilenceAnalysis {
+            album,
+            reports,
+            errors,
+        })
+    })
+    .await
+    .map_err(|e| AppError::Unknown(e.to_string()))?
+}
@@ -0,0 +1,23 @@
+use crate::models::{Album, AppError, SpectralAnalysis};
+use crate::services::validator_spectral::regles_spectrales;
+use crate::services::{SpectralService, ValidatorService};
+
+/// Analyse spectrale des pistes sans perte ; `render_spectrogram` écrit un PNG par piste dans le dossier temporaire
+#[tauri::command]
+pub async fn analyze_spectrum(
+    mut album: Album,
+    render_spectrogram: bool,
+) -> Result<SpectralAnalysis, AppError> {
+    // Décodage et FFT de tout l'album : hors du runtime async
+    tauri::async_runtime::spawn_blocking(move || {
+        let dossier = std::env::temp_dir().join("tagotomatik_spectrograms");
+        let reports = SpectralService::analyser_album(
+            &album,
+            render_spectrogram.then_some(dossier.as_path()),
+        )?;
+        ValidatorService::evaluer_avec(&mut album, regles_spectrales(&reports));
+        Ok(SpectralAnalysis { album, reports })
+    })
+    .await
+    .map_err(|e| AppError::Unknown(e.to_string()))?
+}
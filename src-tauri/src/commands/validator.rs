@@ -0,0 +1,19 @@
+use crate::db::Database;
+use crate::models::{AppError, Severity, ValidationRuleInfo};
+use crate::services::ValidatorService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_validation_rules() -> Result<Vec<ValidationRuleInfo>, AppError> {
+    Ok(ValidatorService::lister_regles())
+}
+
+#[tauri::command]
+pub async fn set_validation_rule(
+    db: State<'_, Database>,
+    code: String,
+    enabled: bool,
+    severity: Option<Severity>,
+) -> Result<ValidationRuleInfo, AppError> {
+    ValidatorService::configurer_regle(&db, &code, enabled, severity)
+}
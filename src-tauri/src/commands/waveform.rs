@@ -0,0 +1,16 @@
+use crate::db::Database;
+use crate::models::{AppError, Waveform};
+use crate::services::WaveformService;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+/// Crêtes min/max de la piste à plusieurs résolutions, recalculées seulement si le fichier a changé
+#[tauri::command]
+pub async fn get_waveform(app: AppHandle, path: String) -> Result<Waveform, AppError> {
+    // Décodage complet de la piste en l'absence de cache : hors du runtime async
+    tauri::async_runtime::spawn_blocking(move || {
+        WaveformService::obtenir(&app.state::<Database>(), Path::new(&path))
+    })
+    .await
+    .map_err(|e| AppError::Unknown(e.to_string()))?
+}
@@ -1,7 +1,10 @@
+use crate::db::Database;
 use crate::models::{Album, AppError};
-use crate::services::{AudioService, IOService, RenamerService, ValidatorService};
+use crate::services::journal::Transaction;
+use crate::services::{AudioService, RenamerService, ValidatorService};
 use regex::Regex;
 use std::path::PathBuf;
+use tauri::State;
 
 fn sanitize_filename(name: &str) -> String {
     name.replace("/", "_")
@@ -16,7 +19,24 @@ fn sanitize_filename(name: &str) -> String {
 }
 
 #[tauri::command]
-pub async fn save_album_changes(mut album: Album) -> Result<Album, AppError> {
+pub async fn save_album_changes(
+    db: State<'_, Database>,
+    mut album: Album,
+) -> Result<Album, AppError> {
+    // Toutes les écritures sont journalisées : en cas d'échec on revient à l'état initial
+    let mut tx = Transaction::demarrer(&db, "save_album_changes", &album.path)?;
+    if let Err(e) = enregistrer_album(&mut tx, &mut album) {
+        return Err(tx.annuler_apres(e));
+    }
+    tx.valider()?;
+
+    // 4. Re-evaluate album status (Clean/Dirty)
+    ValidatorService::evaluate_album_status(&mut album);
+
+    Ok(album)
+}
+
+fn enregistrer_album(tx: &mut Transaction, album: &mut Album) -> Result<(), AppError> {
     let audio_service = AudioService::new();
 
     // 1. Save tags FIRST (before renaming, so path is still valid)
@@ -29,7 +49,7 @@ pub async fn save_album_changes(mut album: Album) -> Result<Album, AppError> {
                     track.path
                 )));
             }
-            audio_service.ecrire_metadonnees(track)?;
+            tx.ecrire_tags(&audio_service, track)?;
             track.is_modified = false;
             track.original_metadata = Some(Box::new(track.clone()));
         }
@@ -55,7 +75,7 @@ pub async fn save_album_changes(mut album: Album) -> Result<Album, AppError> {
 
         // Check if path has changed (ignoring case if needed, but here strict equality)
         if new_path != old_path {
-            IOService::rename_file(old_path.to_str().unwrap(), new_path.to_str().unwrap())?;
+            tx.renommer(&old_path, &new_path)?;
             track.path = new_path.to_string_lossy().to_string();
         }
     }
@@ -103,10 +123,7 @@ pub async fn save_album_changes(mut album: Album) -> Result<Album, AppError> {
 
             if new_folder_name != current_folder_name {
                 let new_folder_path = parent_of_folder.join(&new_folder_name);
-                IOService::rename_folder(
-                    current_folder.to_str().unwrap(),
-                    new_folder_path.to_str().unwrap(),
-                )?;
+                tx.renommer(current_folder, &new_folder_path)?;
 
                 // Update Album Path
                 album.path = new_folder_path.to_string_lossy().to_string();
@@ -150,8 +167,5 @@ pub async fn save_album_changes(mut album: Album) -> Result<Album, AppError> {
         }
     }
 
-    // 4. Re-evaluate album status (Clean/Dirty)
-    ValidatorService::evaluate_album_status(&mut album);
-
-    Ok(album)
+    Ok(())
 }
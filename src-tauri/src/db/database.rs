@@ -9,7 +9,11 @@ pub struct Database {
 impl Database {
     pub fn init() -> Result<Self, AppError> {
         // Move DB out of src-tauri to avoid infinite rebuild loop in dev mode
-        let path = "../tagotomatik.db";
+        Self::open("../tagotomatik.db")
+    }
+
+    /// Ouvre (ou crée) la base à l'emplacement donné, ":memory:" pour une base temporaire
+    pub fn open(path: &str) -> Result<Self, AppError> {
         let conn = Connection::open(path).map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
         conn.execute(
@@ -34,6 +38,101 @@ impl Database {
         )
         .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS journal_operations (
+                id INTEGER PRIMARY KEY,
+                kind TEXT NOT NULL,
+                album_path TEXT NOT NULL,
+                status TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS journal_steps (
+                id INTEGER PRIMARY KEY,
+                operation_id INTEGER NOT NULL REFERENCES journal_operations(id),
+                seq INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                path TEXT NOT NULL,
+                target TEXT,
+                snapshot TEXT,
+                undone INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS quarantine (
+                id INTEGER PRIMARY KEY,
+                original_path TEXT NOT NULL,
+                stored_path TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                in_trash INTEGER NOT NULL DEFAULT 0,
+                deleted_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cleaning_rules (
+                id INTEGER PRIMARY KEY,
+                pattern TEXT,
+                extension TEXT,
+                min_size INTEGER,
+                max_size INTEGER,
+                action TEXT NOT NULL,
+                rename_to TEXT
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS integrity_cache (
+                hash TEXT PRIMARY KEY,
+                report TEXT NOT NULL,
+                checked_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS eq_presets (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                settings TEXT NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS waveform_cache (
+                hash TEXT PRIMARY KEY,
+                waveform TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_used INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
         Ok(Database {
             conn: Mutex::new(conn),
         })
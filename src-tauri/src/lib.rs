@@ -7,22 +7,57 @@ use commands::cover::CoverServiceState;
 use commands::playlist::PlaylistServiceState;
 use commands::{
     add_exception, apply_auto_correct,
-    converter::{convert_file, delete_file},
+    cleaning_rules::{add_cleaning_rule, delete_cleaning_rule, get_cleaning_rules},
+    converter::{apply_trim, convert_file, delete_file, get_ffmpeg_info},
     cover::{apply_cover, apply_local_cover, download_cover, read_cover, read_track_cover, search_cover},
-    delete_exception, get_exceptions,
+    delete_exception,
+    equalizer::{
+        delete_eq_preset, get_equalizer, import_eq_preset, list_eq_presets, save_eq_preset,
+        set_equalizer,
+    },
+    get_exceptions,
     history::get_scan_history,
+    integrity::verify_album,
+    journal::{list_operations, undo_last_operation},
+    loudness::analyze_loudness,
     metadata::search_musicbrainz,
-    player::{pause_track, play_track, resume_track, seek_track, set_eq, set_volume, stop_track},
+    output::{get_output_settings, list_output_devices, set_output},
+    plan_auto_correct,
+    player::{
+        clear_ab_loop, get_crossfade, get_player_state, get_replaygain, pause_track, play_track,
+        resume_track, seek_exact, seek_track, set_ab_loop, set_crossfade, set_eq,
+        set_meter_enabled, set_playback_speed, set_replaygain, set_volume, stop_track,
+    },
     playlist::{
         add_to_playlist, create_playlist, get_playlist_tracks, list_playlists, write_playlist,
     },
-    preview_auto_correct, save_album_changes, scan_directory, scan_junk,
+    preview_auto_correct, preview_renumber, preview_unify,
+    quarantine::{
+        get_quarantine_settings, list_quarantine, purge_quarantine, restore_quarantined,
+        set_quarantine_settings,
+    },
+    queue::{
+        clear_queue, enqueue_tracks, get_queue, next_track, play_album, play_playlist,
+        previous_track, set_repeat, set_shuffle,
+    },
+    render::render_track,
+    save_album_changes, scan_directory, scan_junk,
+    settings::{get_various_artists_name, set_various_artists_name},
+    silence::analyze_silence,
+    spectral::analyze_spectrum,
+    validator::{list_validation_rules, set_validation_rule},
+    waveform::get_waveform,
 };
 use db::Database;
 use services::cover::CoverService;
 use services::player::AudioPlayerState;
 use services::playlist::PlaylistService;
+use services::{
+    CleaningRuleService, CompilationService, EqPresetService, GaplessService, OutputService,
+    QuarantineService, ReplayGainService, ValidatorService,
+};
 use std::sync::Mutex;
+use tauri::Manager;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -33,7 +68,42 @@ fn greet(name: &str) -> String {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let db = Database::init().expect("error initializing database");
+    if let Err(e) = CleaningRuleService::charger(&db) {
+        eprintln!("Chargement des règles de nettoyage impossible: {}", e);
+    }
+    if let Err(e) = CompilationService::charger(&db) {
+        eprintln!("Chargement du nom des compilations impossible: {}", e);
+    }
+    if let Err(e) = ValidatorService::charger_config(&db) {
+        eprintln!("Chargement des réglages du validateur impossible: {}", e);
+    }
+    // Purge des fichiers en quarantaine dont la durée de rétention est dépassée
+    if let Err(e) = QuarantineService::purger(&db) {
+        eprintln!("Purge de la quarantaine impossible: {}", e);
+    }
     let player_state = AudioPlayerState::new();
+    match OutputService::charger(&db) {
+        // Le lecteur démarre sur la sortie par défaut : on ne bascule que si un autre choix est enregistré
+        Ok(reglages) if reglages != Default::default() => {
+            if let Err(e) = player_state.set_output(reglages) {
+                eprintln!("Sortie audio enregistrée indisponible: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Chargement de la sortie audio impossible: {}", e),
+    }
+    match ReplayGainService::charger(&db) {
+        Ok(reglages) => player_state.set_replaygain(reglages),
+        Err(e) => eprintln!("Chargement des réglages ReplayGain impossible: {}", e),
+    }
+    match GaplessService::charger_fondu(&db) {
+        Ok(secondes) => player_state.set_crossfade(secondes),
+        Err(e) => eprintln!("Chargement du fondu enchaîné impossible: {}", e),
+    }
+    match EqPresetService::charger(&db) {
+        Ok(settings) => player_state.set_equalizer(settings),
+        Err(e) => eprintln!("Chargement de l'égaliseur impossible: {}", e),
+    }
     let cover_service = CoverServiceState(Mutex::new(CoverService::new()));
     let playlist_service = PlaylistServiceState(Mutex::new(PlaylistService::new()));
 
@@ -45,17 +115,28 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            // Le lecteur émet ses événements (piste, état, position, erreurs) via l'AppHandle
+            app.state::<AudioPlayerState>().attacher(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             scan_directory,
             scan_junk,
             preview_auto_correct,
+            preview_renumber,
+            preview_unify,
+            plan_auto_correct,
             apply_auto_correct,
             read_cover,
             read_track_cover,
             add_exception,
             get_exceptions,
             delete_exception,
+            add_cleaning_rule,
+            get_cleaning_rules,
+            delete_cleaning_rule,
             save_album_changes,
             play_track,
             pause_track,
@@ -63,7 +144,37 @@ pub fn run() {
             stop_track,
             set_volume,
             seek_track,
+            seek_exact,
+            set_playback_speed,
+            set_ab_loop,
+            clear_ab_loop,
             set_eq,
+            get_equalizer,
+            set_equalizer,
+            list_eq_presets,
+            save_eq_preset,
+            delete_eq_preset,
+            import_eq_preset,
+            render_track,
+            get_waveform,
+            get_replaygain,
+            get_player_state,
+            set_replaygain,
+            get_crossfade,
+            set_crossfade,
+            set_meter_enabled,
+            list_output_devices,
+            get_output_settings,
+            set_output,
+            play_album,
+            play_playlist,
+            enqueue_tracks,
+            clear_queue,
+            next_track,
+            previous_track,
+            set_shuffle,
+            set_repeat,
+            get_queue,
             search_cover,
             download_cover,
             apply_cover,
@@ -74,8 +185,25 @@ pub fn run() {
             add_to_playlist,
             write_playlist,
             convert_file,
+            get_ffmpeg_info,
             delete_file,
+            apply_trim,
             get_scan_history,
+            list_operations,
+            undo_last_operation,
+            list_quarantine,
+            restore_quarantined,
+            purge_quarantine,
+            get_quarantine_settings,
+            set_quarantine_settings,
+            get_various_artists_name,
+            set_various_artists_name,
+            list_validation_rules,
+            set_validation_rule,
+            verify_album,
+            analyze_spectrum,
+            analyze_silence,
+            analyze_loudness,
             search_musicbrainz
         ])
         .run(tauri::generate_context!())
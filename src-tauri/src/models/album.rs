@@ -1,4 +1,5 @@
 use super::track::Track;
+use super::validation::ValidationIssue;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -25,7 +26,9 @@ pub struct Album {
     pub tracks: Vec<Track>,
     pub status: AlbumStatus,
     #[serde(default)]
-    pub issues: Vec<String>,
+    pub issues: Vec<String>, // Messages des problèmes, pour affichage direct
+    #[serde(default)]
+    pub validation: Vec<ValidationIssue>,
 }
 
 impl Album {
@@ -43,6 +46,7 @@ impl Album {
             tracks: Vec::new(),
             status: AlbumStatus::Processing,
             issues: Vec::new(),
+            validation: Vec::new(),
         }
     }
 }
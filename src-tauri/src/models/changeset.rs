@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+/// Champ de tag concerné par une modification planifiée
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TagField {
+    Title,
+    Artist,
+    AlbumArtist,
+    Album,
+    Year,
+    TrackNumber,
+    TrackTotal,
+    DiscNumber,
+    Genre,
+    Compilation,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: TagField,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FolderRename {
+    pub from: String, // Chemin absolu actuel
+    pub to: String,   // Chemin absolu cible
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FileRename {
+    pub from: String, // Relatif au dossier de l'album
+    pub to: String,   // Relatif au dossier de l'album
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TagDiff {
+    pub file: String, // Relatif au dossier de l'album, après renommage
+    pub changes: Vec<FieldChange>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CoverMove {
+    pub from: String,
+    pub to: String,
+    pub reason: String,
+    pub rule: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FileDeletion {
+    pub path: String,
+    pub size: u64,
+    pub reason: String,
+    pub rule: String, // Règle de nettoyage à l'origine de la suppression
+}
+
+/// Plan complet d'une correction automatique, calculé sans toucher au disque.
+/// Les chemins de fichiers sont relatifs au dossier de l'album (après renommage du dossier).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChangeSet {
+    pub album_path: String,
+    pub fingerprint: String, // Empreinte du dossier au moment de la planification
+    pub folder_rename: Option<FolderRename>,
+    pub file_renames: Vec<FileRename>,
+    pub tag_diffs: Vec<TagDiff>,
+    pub cover_moves: Vec<CoverMove>,
+    pub deletions: Vec<FileDeletion>,
+}
+
+impl ChangeSet {
+    pub fn new(album_path: String, fingerprint: String) -> Self {
+        Self {
+            album_path,
+            fingerprint,
+            folder_rename: None,
+            file_renames: Vec::new(),
+            tag_diffs: Vec::new(),
+            cover_moves: Vec::new(),
+            deletions: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folder_rename.is_none()
+            && self.file_renames.is_empty()
+            && self.tag_diffs.is_empty()
+            && self.cover_moves.is_empty()
+            && self.deletions.is_empty()
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// Action appliquée par le nettoyage à un fichier reconnu par une règle
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Keep,
+    Delete,
+    Rename,
+}
+
+impl RuleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleAction::Keep => "keep",
+            RuleAction::Delete => "delete",
+            RuleAction::Rename => "rename",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "keep" => RuleAction::Keep,
+            "rename" => RuleAction::Rename,
+            _ => RuleAction::Delete,
+        }
+    }
+}
+
+/// Règle de nettoyage : tous les critères renseignés doivent correspondre
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CleaningRule {
+    pub id: Option<i64>,
+    pub pattern: Option<String>,   // Glob (*, ?, **), sur le chemin relatif s'il contient un "/"
+    pub extension: Option<String>, // Sans le point, insensible à la casse
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub action: RuleAction,
+    pub rename_to: Option<String>, // Nouveau nom de fichier pour l'action "rename"
+}
+
+impl CleaningRule {
+    /// Description lisible, reprise dans les rapports de nettoyage
+    pub fn label(&self) -> String {
+        let mut criteres = Vec::new();
+        if let Some(pattern) = &self.pattern {
+            criteres.push(pattern.clone());
+        }
+        if let Some(ext) = &self.extension {
+            criteres.push(format!(".{}", ext));
+        }
+        if let Some(min) = self.min_size {
+            criteres.push(format!(">= {} o", min));
+        }
+        if let Some(max) = self.max_size {
+            criteres.push(format!("<= {} o", max));
+        }
+        let action = match (self.action, &self.rename_to) {
+            (RuleAction::Keep, _) => "garder".to_string(),
+            (RuleAction::Delete, _) => "supprimer".to_string(),
+            (RuleAction::Rename, Some(to)) => format!("renommer en {}", to),
+            (RuleAction::Rename, None) => "renommer".to_string(),
+        };
+        format!(
+            "Règle #{} ({} : {})",
+            self.id.unwrap_or(0),
+            action,
+            criteres.join(", ")
+        )
+    }
+}
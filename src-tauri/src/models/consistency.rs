@@ -0,0 +1,16 @@
+use super::changeset::TagField;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldValue {
+    pub value: String,
+    pub tracks: Vec<String>, // Chemins des pistes portant cette valeur
+}
+
+/// Champ dont les valeurs diffèrent d'une piste à l'autre, valeur majoritaire en premier
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldConsistency {
+    pub field: TagField,
+    pub disc: Option<u32>, // Renseigné pour le total de pistes, vérifié disque par disque
+    pub values: Vec<FieldValue>,
+}
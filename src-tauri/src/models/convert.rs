@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Format cible d'une conversion
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConvertFormat {
+    #[default]
+    Mp3,
+    Flac,
+    Opus,
+    Wav,
+    Aac,
+}
+
+impl ConvertFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Flac => "flac",
+            Self::Opus => "opus",
+            Self::Wav => "wav",
+            Self::Aac => "m4a",
+        }
+    }
+
+    /// FLAC et WAV sont encodés sans ffmpeg
+    pub fn est_natif(self) -> bool {
+        matches!(self, Self::Flac | Self::Wav)
+    }
+}
+
+/// Préréglage de qualité, traduit pour chaque format (débit, VBR, niveau de compression FLAC)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConvertQuality {
+    Low,
+    #[default]
+    Standard,
+    High,
+    Extreme,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConvertOptions {
+    #[serde(default)]
+    pub format: ConvertFormat,
+    #[serde(default)]
+    pub quality: ConvertQuality,
+    pub bitrate_kbps: Option<u32>, // Débit constant imposé, à la place du préréglage (formats avec perte)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConvertResult {
+    pub source: String,
+    pub output: String,
+    pub format: ConvertFormat,
+    pub encoder: String, // "natif" ou version de ffmpeg
+    #[serde(default)]
+    pub lost_fields: Vec<String>, // Champs de la source absents ou différents une fois relus dans la sortie
+    pub tag_error: Option<String>, // Report des tags impossible : la conversion audio reste valable
+}
+
+/// ffmpeg trouvé sur le système, pour les formats qui n'ont pas d'encodeur natif
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FfmpegInfo {
+    pub available: bool,
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+pub const EQ_MAX_BANDS: usize = 32;
+
+/// Forme de filtre d'une bande (formules du RBJ Audio EQ Cookbook)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EqFilterType {
+    Peaking,
+    LowShelf,
+    HighShelf,
+    LowPass,
+    HighPass,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EqBand {
+    pub filter_type: EqFilterType,
+    pub frequency_hz: f32,
+    pub gain_db: f32, // Ignoré par les passe-bas / passe-haut
+    pub q: f32,
+    pub enabled: bool,
+}
+
+impl EqBand {
+    pub fn new(filter_type: EqFilterType, frequency_hz: f32, gain_db: f32, q: f32) -> Self {
+        Self {
+            filter_type,
+            frequency_hz,
+            gain_db,
+            q,
+            enabled: true,
+        }
+    }
+}
+
+/// Égaliseur complet : préampli, correction anti-saturation et bandes, persisté en JSON
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EqSettings {
+    pub enabled: bool,
+    pub preamp_db: f32,
+    pub auto_preamp: bool, // Baisse le niveau du maximum de la courbe au-dessus de 0 dB
+    pub bands: Vec<EqBand>,
+}
+
+impl Default for EqSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            preamp_db: 0.0,
+            auto_preamp: true,
+            bands: Vec::new(),
+        }
+    }
+}
+
+impl EqSettings {
+    /// Ancien réglage graves / médiums / aigus (250 Hz, 1 kHz, 4 kHz)
+    pub fn trois_bandes(bass: f32, mid: f32, treble: f32) -> Self {
+        Self {
+            bands: vec![
+                EqBand::new(EqFilterType::LowShelf, 250.0, bass, 0.707),
+                EqBand::new(EqFilterType::Peaking, 1000.0, mid, 1.0),
+                EqBand::new(EqFilterType::HighShelf, 4000.0, treble, 0.707),
+            ],
+            ..Self::default()
+        }
+    }
+}
+
+/// Préréglage nommé, enregistré en base
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EqPreset {
+    pub id: Option<i64>,
+    pub name: String,
+    pub settings: EqSettings,
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityProblem {
+    pub code: String, // DECODE_ERROR, TRUNCATED, SAMPLE_COUNT_MISMATCH, FLAC_MD5_MISMATCH, NOT_VERIFIABLE
+    pub message: String,
+}
+
+/// Résultat du décodage complet d'un fichier, mis en cache par empreinte du contenu
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityReport {
+    pub path: String,
+    pub hash: String,
+    pub decoded_frames: u64,
+    pub expected_frames: Option<u64>,
+    pub md5_match: Option<bool>, // FLAC uniquement, None si STREAMINFO ne contient pas de MD5
+    pub problems: Vec<IntegrityProblem>,
+}
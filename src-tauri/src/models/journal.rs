@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Opération enregistrée dans le journal (apply_auto_correct, save_album_changes...)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalOperation {
+    pub id: i64,
+    pub kind: String,
+    pub album_path: String,
+    pub created_at: String,
+    pub status: String, // "en_cours", "terminee", "annulee"
+    pub step_count: usize,
+    pub undoable: bool, // Faux si un fichier a été supprimé définitivement (rien à restaurer)
+}
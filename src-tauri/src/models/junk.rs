@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Nature d'un fichier signalé par l'inspecteur
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JunkCategory {
+    Cover,  // Pochette mal nommée ou en double
+    Image,  // Autre image (scans, artwork)
+    Text,   // .nfo, .txt, .log, .cue...
+    System, // Thumbs.db, .DS_Store, desktop.ini...
+    Other,
+}
+
+/// Ce que le nettoyage fera du fichier
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JunkAction {
+    Delete,
+    Rename,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JunkEntry {
+    pub path: String, // Relatif au dossier de l'album
+    pub size: u64,
+    pub category: JunkCategory,
+    pub action: JunkAction,
+    pub target: Option<String>, // Nouveau chemin relatif pour un renommage
+    pub reason: String,
+    pub rule: String, // Règle de nettoyage appliquée
+}
+
+impl JunkCategory {
+    pub fn depuis_nom(name: &str) -> Self {
+        let lower = name.to_lowercase();
+        let ext = lower.rsplit_once('.').map(|(_, e)| e).unwrap_or("");
+        if ["thumbs.db", ".ds_store", "desktop.ini"].contains(&lower.as_str()) {
+            JunkCategory::System
+        } else if lower.starts_with("cover.") || lower.starts_with("folder.") {
+            JunkCategory::Cover
+        } else if ["jpg", "jpeg", "png", "bmp", "gif", "webp"].contains(&ext) {
+            JunkCategory::Image
+        } else if ["nfo", "txt", "log", "cue", "sfv", "md5", "url"].contains(&ext) {
+            JunkCategory::Text
+        } else {
+            JunkCategory::Other
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Mesure EBU R128 d'une piste ; le gain vise la référence ReplayGain 2.0 (-18 LUFS)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackLoudness {
+    pub path: String,
+    pub integrated_lufs: f64,
+    pub true_peak: f64, // Linéaire, 1.0 = 0 dBTP
+    pub track_gain_db: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlbumLoudness {
+    pub album_path: String,
+    pub integrated_lufs: f64, // Mesurée sur l'ensemble des blocs de toutes les pistes
+    pub album_peak: f64,
+    pub album_gain_db: f64,
+    pub tracks: Vec<TrackLoudness>,
+    pub errors: Vec<String>, // Pistes non mesurées (format non décodable...), exclues du gain d'album
+    pub tags_written: bool,
+}
+
+/// Avancement du traitement par lot, émis avant chaque piste
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoudnessProgress {
+    pub current: usize,
+    pub total: usize,
+    pub path: String,
+}
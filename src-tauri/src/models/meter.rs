@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Nombre de bandes du spectre, réparties en échelle logarithmique de 20 Hz à 20 kHz
+pub const METER_BANDS: usize = 32;
+
+/// Relevé du vu-mètre émis dans "player-meter" ; valeurs en dBFS arrondies au dixième
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MeterFrame {
+    pub peak_db: Vec<f32>, // Un par canal
+    pub rms_db: Vec<f32>,
+    pub bands_db: Vec<f32>, // METER_BANDS valeurs, canaux mélangés
+}
@@ -1,12 +1,54 @@
 pub mod album;
+pub mod changeset;
+pub mod cleaning_rule;
+pub mod consistency;
+pub mod convert;
+pub mod equalizer;
 pub mod error;
 pub mod exception;
+pub mod integrity;
+pub mod journal;
+pub mod junk;
+pub mod loudness;
+pub mod meter;
+pub mod numbering;
+pub mod output;
+pub mod player;
 pub mod playlist;
+pub mod quarantine;
+pub mod render;
+pub mod replaygain;
 pub mod scan;
+pub mod silence;
+pub mod spectral;
 pub mod track;
+pub mod validation;
+pub mod waveform;
 
 pub use album::{Album, AlbumStatus};
+pub use changeset::ChangeSet;
+pub use cleaning_rule::{CleaningRule, RuleAction};
+pub use convert::{ConvertFormat, ConvertOptions, ConvertQuality, ConvertResult, FfmpegInfo};
+pub use equalizer::{EqBand, EqFilterType, EqPreset, EqSettings, EQ_MAX_BANDS};
 pub use error::AppError;
 pub use exception::CaseException;
+pub use integrity::{IntegrityProblem, IntegrityReport};
+pub use journal::JournalOperation;
+pub use junk::{JunkAction, JunkCategory, JunkEntry};
+pub use loudness::{AlbumLoudness, LoudnessProgress, TrackLoudness};
+pub use meter::{MeterFrame, METER_BANDS};
+pub use numbering::RenumberMode;
+pub use output::{OutputDevice, OutputSettings};
+pub use player::{
+    AbLoop, PlaybackStatus, PlayerError, PlayerStateInfo, QueueEntry, QueueState, RepeatMode,
+    SeekResult, TrackChanged,
+};
+pub use quarantine::{DeletionMode, QuarantineEntry, QuarantineSettings};
+pub use render::{RenderFormat, RenderOptions, RenderResult};
+pub use replaygain::{ReplayGainMode, ReplayGainSettings, ReplayGainTags};
 pub use scan::ScanResult;
+pub use silence::{SilenceAnalysis, SilenceReport, TrimProposal};
+pub use spectral::{SpectralAnalysis, SpectralReport};
 pub use track::Track;
+pub use validation::{Severity, SuggestedFix, ValidationIssue, ValidationRuleInfo};
+pub use waveform::{Waveform, WaveformLevel};
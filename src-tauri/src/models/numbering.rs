@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// Ordre de référence pour la renumérotation automatique
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RenumberMode {
+    FilenameOrder,  // Ordre naturel des noms de fichiers, par disque
+    DiscTrackOrder, // Ordre actuel disque/piste, trous et doublons résorbés
+}
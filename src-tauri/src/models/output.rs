@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// Périphérique de sortie proposé par l'hôte audio (ALSA, WASAPI, CoreAudio...)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutputDevice {
+    pub name: String,
+    pub is_default: bool,
+    pub sample_rate: Option<u32>, // Configuration par défaut du périphérique
+    pub channels: Option<u16>,
+    pub buffer_min: Option<u32>, // Tailles de tampon acceptées, en trames, si l'hôte les connaît
+    pub buffer_max: Option<u32>,
+}
+
+/// Sortie du lecteur, persistée dans la table settings
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct OutputSettings {
+    pub device: Option<String>,     // None : périphérique par défaut
+    pub buffer_frames: Option<u32>, // None : tampon choisi par l'hôte
+    #[serde(default)]
+    pub null_sink: bool, // Lecture sans carte son, au rythme réel (tests, serveur)
+    #[serde(default)]
+    pub capture_path: Option<String>, // Avec null_sink : enregistre la sortie dans ce WAV
+}
@@ -0,0 +1,121 @@
+use super::playlist::PlaylistTrack;
+use super::Track;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepeatMode {
+    #[default]
+    Off,
+    One,
+    All,
+}
+
+/// Élément de la file de lecture, construit depuis une piste d'album ou de playlist
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QueueEntry {
+    pub path: String,
+    pub title: String,
+    pub artist: String,
+    pub duration_sec: u64,
+}
+
+impl QueueEntry {
+    /// Lecture d'un fichier isolé : le nom de fichier tient lieu de titre
+    pub fn depuis_chemin(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            title: Path::new(path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            artist: String::new(),
+            duration_sec: 0,
+        }
+    }
+}
+
+impl From<&Track> for QueueEntry {
+    fn from(track: &Track) -> Self {
+        Self {
+            path: track.path.clone(),
+            title: track.title.clone(),
+            artist: track.artist.clone(),
+            duration_sec: track.duration_sec,
+        }
+    }
+}
+
+impl From<&PlaylistTrack> for QueueEntry {
+    fn from(track: &PlaylistTrack) -> Self {
+        Self {
+            path: track.path.clone(),
+            title: track.title.clone(),
+            artist: track.artist.clone(),
+            duration_sec: track.duration,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueueState {
+    pub entries: Vec<QueueEntry>,
+    pub order: Vec<usize>,      // Ordre de lecture (indices dans entries)
+    pub current: Option<usize>, // Indice dans entries
+    pub shuffle: bool,
+    pub repeat: RepeatMode,
+}
+
+/// Payload de l'événement "player-track-changed"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackChanged {
+    pub index: usize,
+    pub entry: QueueEntry,
+    pub auto_advance: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaybackStatus {
+    #[default]
+    Stopped,
+    Playing,
+    Paused,
+    Ended, // Fin de file atteinte
+    Error,
+}
+
+/// Instantané de l'état du lecteur : payload de "player-state" et réponse de get_player_state
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PlayerStateInfo {
+    pub status: PlaybackStatus,
+    pub path: Option<String>,
+    pub position_sec: f64,
+    pub duration_sec: Option<f64>,
+    pub volume: f32,
+    pub speed: f32,
+    pub ab_loop: Option<AbLoop>,
+    pub error: Option<String>,
+    pub output: String,               // Nom de la sortie active
+    pub output_error: Option<String>, // Sortie demandée inutilisable : lecture sur la sortie nulle
+}
+
+/// Boucle A-B active sur la piste courante, bornes ramenées à la trame près
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct AbLoop {
+    pub start_sec: f64,
+    pub end_sec: f64,
+}
+
+/// Réponse d'un positionnement : position réellement atteinte, en secondes et en trames
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct SeekResult {
+    pub position_sec: f64,
+    pub frame: u64,
+}
+
+/// Payload de "player-error"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlayerError {
+    pub path: String,
+    pub message: String,
+}
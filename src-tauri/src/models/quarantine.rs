@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Destination des fichiers supprimés par le nettoyage
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeletionMode {
+    Quarantine, // Dossier de quarantaine de l'application (par défaut)
+    Trash,      // Corbeille freedesktop (Linux uniquement, sinon quarantaine)
+    Permanent,  // Suppression définitive, sur demande explicite
+}
+
+impl DeletionMode {
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "trash" => DeletionMode::Trash,
+            "permanent" => DeletionMode::Permanent,
+            _ => DeletionMode::Quarantine,
+        }
+    }
+
+    pub fn as_setting(&self) -> &'static str {
+        match self {
+            DeletionMode::Quarantine => "quarantine",
+            DeletionMode::Trash => "trash",
+            DeletionMode::Permanent => "permanent",
+        }
+    }
+}
+
+/// Réglages de la quarantaine, exposés au frontend
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QuarantineSettings {
+    pub deletion_mode: DeletionMode,
+    pub quarantine_dir: String,
+    pub retention_days: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuarantineEntry {
+    pub id: i64,
+    pub original_path: String,
+    pub stored_path: String,
+    pub size: u64,
+    pub reason: String,
+    pub deleted_at: String,
+    pub in_trash: bool,
+}
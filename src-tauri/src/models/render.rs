@@ -0,0 +1,34 @@
+use super::{EqSettings, ReplayGainSettings};
+use serde::{Deserialize, Serialize};
+
+/// Format des échantillons du WAV produit
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderFormat {
+    #[default]
+    Pcm16,
+    Pcm24,
+    Float32, // Sans écrêtage : utile pour vérifier la chaîne DSP
+}
+
+/// Réglages du rendu ; sans EQ ni ReplayGain, ceux du lecteur sont utilisés
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RenderOptions {
+    pub eq: Option<EqSettings>,
+    pub replaygain: Option<ReplayGainSettings>,
+    #[serde(default)]
+    pub from_album: bool,
+    #[serde(default)]
+    pub format: RenderFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RenderResult {
+    pub source: String,
+    pub output: String,
+    pub frames: u64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub peak: f32,            // Pic échantillon en sortie de chaîne, avant conversion
+    pub clipped_samples: u64, // Échantillons écrêtés par la conversion en entiers
+}
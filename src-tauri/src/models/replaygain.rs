@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayGainMode {
+    Off,
+    Track,
+    Album,
+    Auto, // Album quand la lecture part d'un album, piste sinon
+}
+
+/// Réglages de lecture ReplayGain, persistés dans la table settings
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReplayGainSettings {
+    pub mode: ReplayGainMode,
+    pub preamp_db: f32,
+    pub fallback_db: f32, // Gain appliqué aux fichiers sans tag (0 par défaut : volume inchangé)
+    pub prevent_clipping: bool,
+}
+
+impl Default for ReplayGainSettings {
+    fn default() -> Self {
+        Self {
+            mode: ReplayGainMode::Auto,
+            preamp_db: 0.0,
+            fallback_db: 0.0,
+            prevent_clipping: true,
+        }
+    }
+}
+
+/// Gains lus dans les tags d'un fichier (REPLAYGAIN_* ou R128_* ramenés à -18 LUFS)
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ReplayGainTags {
+    pub track_gain_db: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub album_gain_db: Option<f32>,
+    pub album_peak: Option<f32>,
+}
@@ -0,0 +1,30 @@
+use super::Album;
+use serde::{Deserialize, Serialize};
+
+/// Rognage proposé d'une piste sans perte : trames conservées [start_frame, end_frame[
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TrimProposal {
+    pub path: String,
+    pub sample_rate: u32,
+    pub start_frame: u64,
+    pub end_frame: u64,
+}
+
+/// Silences en début et fin de piste, et plages d'échantillons écrêtés
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SilenceReport {
+    pub path: String,
+    pub duration_sec: f64,
+    pub leading_silence_sec: f64,
+    pub trailing_silence_sec: f64,
+    pub clipped_runs: u64, // Suites d'au moins 3 échantillons à pleine échelle sur un canal
+    pub clipped_samples: u64,
+    pub trim: Option<TrimProposal>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SilenceAnalysis {
+    pub album: Album,
+    pub reports: Vec<SilenceReport>,
+    pub errors: Vec<String>, // Pistes que le décodeur n'a pas su lire
+}
@@ -0,0 +1,20 @@
+use super::Album;
+use serde::{Deserialize, Serialize};
+
+/// Analyse spectrale d'une piste sans perte : coupure effective et source avec perte suspectée
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpectralReport {
+    pub path: String,
+    pub sample_rate: u32,
+    pub cutoff_hz: f32,
+    pub drop_db: f32, // Chute de niveau de part et d'autre de la coupure
+    pub lossy_suspect: bool,
+    pub suspected_source: Option<String>, // ex. "MP3 128 kbps (coupure ~16 kHz)"
+    pub spectrogram_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpectralAnalysis {
+    pub album: Album,
+    pub reports: Vec<SpectralReport>,
+}
@@ -10,7 +10,13 @@ pub struct Track {
     pub album: String,
     pub year: Option<u32>,
     pub track_number: Option<u32>,
+    #[serde(default)]
+    pub track_total: Option<u32>,
+    #[serde(default)]
+    pub disc_number: Option<u32>,
     pub genre: Option<String>,
+    #[serde(default)]
+    pub compilation: bool, // TCMP / COMPILATION / cpil
     pub duration_sec: u64,
     pub format: String, // "mp3", "flac"...
     pub bit_rate: Option<u32>,
@@ -33,7 +39,10 @@ impl Track {
             album: String::new(),
             year: None,
             track_number: None,
+            track_total: None,
+            disc_number: None,
             genre: None,
+            compilation: false,
             duration_sec: 0,
             format: String::new(),
             bit_rate: None,
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// Gravité d'un problème : seules les erreurs rendent un album "Dirty"
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "error" => Some(Severity::Error),
+            "warning" => Some(Severity::Warning),
+            "info" => Some(Severity::Info),
+            _ => None,
+        }
+    }
+}
+
+/// Correction proposée au frontend pour résoudre un problème
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestedFix {
+    AutoCorrect,   // plan_auto_correct / apply_auto_correct
+    SearchCover,   // Recherche de pochette
+    WritePlaylist, // Génération de la playlist
+    EditTags,      // Saisie manuelle
+    Renumber,      // preview_renumber puis enregistrement
+    Unify,         // preview_unify puis enregistrement
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ValidationIssue {
+    pub code: String, // Code stable, ex: "COVER_MISSING"
+    pub severity: Severity,
+    pub message: String,
+    pub tracks: Vec<String>, // Chemins des pistes concernées (vide = tout l'album)
+    pub fix: Option<SuggestedFix>,
+}
+
+/// Description d'une règle du validateur et de son réglage courant
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ValidationRuleInfo {
+    pub code: String,
+    pub description: String,
+    pub default_severity: Severity,
+    pub enabled: bool,
+    pub severity: Severity,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Crêtes min/max d'une résolution, canaux confondus ; le point i couvre `frames_per_point` trames
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WaveformLevel {
+    pub frames_per_point: f64,
+    pub min: Vec<f32>,
+    pub max: Vec<f32>,
+}
+
+/// Forme d'onde d'une piste, mise en cache par empreinte du contenu
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Waveform {
+    pub path: String,
+    pub hash: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub frames: u64,
+    pub levels: Vec<WaveformLevel>, // De la plus grossière à la plus fine
+}
@@ -69,7 +69,12 @@ impl AudioService {
             track.album = tag.album().unwrap_or_default().to_string();
             track.year = tag.year();
             track.track_number = tag.track();
+            track.track_total = tag.track_total();
+            track.disc_number = tag.disk();
             track.genre = tag.genre().map(|s| s.to_string());
+            track.compilation = tag
+                .get_string(&ItemKey::FlagCompilation)
+                .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
             track.has_cover = tag.picture_count() > 0;
         }
 
@@ -80,6 +85,15 @@ impl AudioService {
     }
 
     pub fn ecrire_metadonnees(&self, track: &Track) -> Result<(), AppError> {
+        self.ecrire(track, false)
+    }
+
+    /// Remet les tags dans l'état photographié : les champs absents de la photo sont retirés
+    pub fn restaurer_metadonnees(&self, track: &Track) -> Result<(), AppError> {
+        self.ecrire(track, true)
+    }
+
+    fn ecrire(&self, track: &Track, effacer_absents: bool) -> Result<(), AppError> {
         let path = Path::new(&track.path);
 
         let mut tagged_file = Probe::open(path)
@@ -112,19 +126,7 @@ impl AudioService {
             }
         };
 
-        tag.set_title(track.title.clone());
-        tag.set_artist(track.artist.clone());
-        tag.set_album(track.album.clone());
-        tag.insert_text(ItemKey::AlbumArtist, track.album_artist.clone());
-        if let Some(ref genre) = track.genre {
-            tag.set_genre(genre.clone());
-        }
-        if let Some(year) = track.year {
-            tag.set_year(year);
-        }
-        if let Some(track_num) = track.track_number {
-            tag.set_track(track_num);
-        }
+        Self::appliquer_champs(tag, track, effacer_absents);
 
         // Ensure ISRC is removed from the primary tag as well (redundant but safe)
         tag.remove_key(&ItemKey::Isrc);
@@ -146,19 +148,7 @@ impl AudioService {
                 }
 
                 // Re-apply metadata
-                tag.set_title(track.title.clone());
-                tag.set_artist(track.artist.clone());
-                tag.set_album(track.album.clone());
-                tag.insert_text(ItemKey::AlbumArtist, track.album_artist.clone());
-                if let Some(ref genre) = track.genre {
-                    tag.set_genre(genre.clone());
-                }
-                if let Some(year) = track.year {
-                    tag.set_year(year);
-                }
-                if let Some(track_num) = track.track_number {
-                    tag.set_track(track_num);
-                }
+                Self::appliquer_champs(tag, track, effacer_absents);
 
                 // Retry save
                 tag.save_to_path(path).map_err(|e| {
@@ -174,7 +164,99 @@ impl AudioService {
 
         Ok(())
     }
+
+    /// Reporte les champs de la piste dans le tag (les champs optionnels absents sont laissés
+    /// tels quels, ou retirés avec `effacer_absents`)
+    fn appliquer_champs(tag: &mut lofty::Tag, track: &Track, effacer_absents: bool) {
+        tag.set_title(track.title.clone());
+        tag.set_artist(track.artist.clone());
+        tag.set_album(track.album.clone());
+        tag.insert_text(ItemKey::AlbumArtist, track.album_artist.clone());
+        if let Some(ref genre) = track.genre {
+            tag.set_genre(genre.clone());
+        }
+        if let Some(year) = track.year {
+            tag.set_year(year);
+        }
+        if let Some(track_num) = track.track_number {
+            tag.set_track(track_num);
+        }
+        if let Some(total) = track.track_total {
+            tag.set_track_total(total);
+        }
+        if let Some(disc) = track.disc_number {
+            tag.set_disk(disc);
+        }
+        if track.compilation {
+            tag.insert_text(ItemKey::FlagCompilation, "1".to_string());
+        } else {
+            tag.remove_key(&ItemKey::FlagCompilation);
+        }
+        if effacer_absents {
+            Self::effacer_absents(tag, track);
+        }
+    }
+
+    fn effacer_absents(tag: &mut lofty::Tag, track: &Track) {
+        if track.title.is_empty() {
+            tag.remove_title();
+        }
+        if track.artist.is_empty() {
+            tag.remove_artist();
+        }
+        if track.album.is_empty() {
+            tag.remove_album();
+        }
+        if track.album_artist.is_empty() {
+            tag.remove_key(&ItemKey::AlbumArtist);
+        }
+        if track.genre.is_none() {
+            tag.remove_genre();
+        }
+        if track.year.is_none() {
+            tag.remove_year();
+        }
+        if track.track_number.is_none() {
+            tag.remove_track();
+        }
+        if track.track_total.is_none() {
+            tag.remove_track_total();
+        }
+        if track.disc_number.is_none() {
+            tag.remove_disk();
+        }
+    }
+
     pub fn definir_cover(&self, track_path: &str, cover_path: &str) -> Result<(), AppError> {
+        // Lire le fichier image
+        let img_data = std::fs::read(cover_path)
+            .map_err(|e| AppError::Audio(format!("Erreur lecture cover: {}", e)))?;
+
+        // On assume JPEG car le CoverService convertit tout en JPEG
+        let picture =
+            Picture::new_unchecked(PictureType::CoverFront, MimeType::Jpeg, None, img_data);
+        self.poser_cover(track_path, picture)
+    }
+
+    /// Pochette avant embarquée (à défaut, la première image du tag)
+    pub fn lire_cover(&self, chemin: &str) -> Result<Option<Picture>, AppError> {
+        let tagged_file = Probe::open(chemin)
+            .map_err(|e| AppError::Audio(format!("Erreur d'ouverture: {}", e)))?
+            .read()
+            .map_err(|e| AppError::Audio(format!("Erreur de lecture: {}", e)))?;
+        let Some(tag) = tagged_file.primary_tag() else {
+            return Ok(None);
+        };
+        let pictures = tag.pictures();
+        Ok(pictures
+            .iter()
+            .find(|p| p.pic_type() == PictureType::CoverFront)
+            .or(pictures.first())
+            .cloned())
+    }
+
+    /// Remplace la pochette avant du fichier
+    pub fn poser_cover(&self, track_path: &str, mut picture: Picture) -> Result<(), AppError> {
         let path = Path::new(track_path);
 
         let mut tagged_file = Probe::open(path)
@@ -191,19 +273,9 @@ impl AudioService {
             }
         };
 
-        // Lire le fichier image
-        let img_data = std::fs::read(cover_path)
-            .map_err(|e| AppError::Audio(format!("Erreur lecture cover: {}", e)))?;
-
-        // Créer l'objet Picture
-        // On assume JPEG car le CoverService convertit tout en JPEG
-        let picture =
-            Picture::new_unchecked(PictureType::CoverFront, MimeType::Jpeg, None, img_data);
-
-        // Supprimer les anciennes covers
+        // Supprimer les anciennes covers, puis ajouter la nouvelle
+        picture.set_pic_type(PictureType::CoverFront);
         tag.remove_picture_type(PictureType::CoverFront);
-
-        // Ajouter la nouvelle
         tag.push_picture(picture);
 
         tag.save_to_path(path)
@@ -0,0 +1,145 @@
+use super::loudness::REFERENCE_REPLAYGAIN_LUFS;
+use super::AudioService;
+use crate::models::{AlbumLoudness, AppError, ReplayGainTags, TrackLoudness};
+use lofty::{FileType, ItemKey, ItemValue, Probe, Tag, TagExt, TagItem, TaggedFileExt};
+use std::path::Path;
+
+/// Référence des gains R128_* d'Opus (RFC 7845), exprimés en Q7.8
+const REFERENCE_R128_LUFS: f64 = -23.0;
+
+const CLES_REPLAYGAIN: [ItemKey; 4] = [
+    ItemKey::ReplayGainTrackGain,
+    ItemKey::ReplayGainTrackPeak,
+    ItemKey::ReplayGainAlbumGain,
+    ItemKey::ReplayGainAlbumPeak,
+];
+const CLES_R128: [&str; 2] = ["R128_TRACK_GAIN", "R128_ALBUM_GAIN"];
+
+impl AudioService {
+    /// Lit les gains présents dans n'importe quel tag du fichier ; les valeurs R128_* sont ramenées à -18 LUFS
+    pub fn lire_gain(&self, chemin: &str) -> Result<ReplayGainTags, AppError> {
+        let tagged_file = Probe::open(Path::new(chemin))
+            .map_err(|e| AppError::Audio(format!("Erreur d'ouverture: {}", e)))?
+            .read()
+            .map_err(|e| AppError::Audio(format!("Erreur de lecture: {}", e)))?;
+
+        let mut gains = ReplayGainTags::default();
+        for tag in tagged_file.tags() {
+            let valeur = |key: ItemKey| tag.get_string(&key).and_then(nombre);
+            gains.track_gain_db = gains
+                .track_gain_db
+                .or_else(|| valeur(ItemKey::ReplayGainTrackGain));
+            gains.track_peak = gains
+                .track_peak
+                .or_else(|| valeur(ItemKey::ReplayGainTrackPeak));
+            gains.album_gain_db = gains
+                .album_gain_db
+                .or_else(|| valeur(ItemKey::ReplayGainAlbumGain));
+            gains.album_peak = gains
+                .album_peak
+                .or_else(|| valeur(ItemKey::ReplayGainAlbumPeak));
+
+            let r128 = |nom: &str| r128_en_replaygain(tag, nom);
+            gains.track_gain_db = gains.track_gain_db.or_else(|| r128("R128_TRACK_GAIN"));
+            gains.album_gain_db = gains.album_gain_db.or_else(|| r128("R128_ALBUM_GAIN"));
+        }
+        Ok(gains)
+    }
+
+    /// Écrit les gains de piste et d'album (REPLAYGAIN_*, ou R128_* pour Opus)
+    pub fn ecrire_gain(
+        &self,
+        piste: &TrackLoudness,
+        album: &AlbumLoudness,
+    ) -> Result<(), AppError> {
+        let gains = ReplayGainTags {
+            track_gain_db: Some(piste.track_gain_db as f32),
+            track_peak: Some(piste.true_peak as f32),
+            album_gain_db: Some(album.album_gain_db as f32),
+            album_peak: Some(album.album_peak as f32),
+        };
+        self.restaurer_gain(&piste.path, &gains)
+    }
+
+    /// Remplace les tags de gain par `gains` ; un gain absent retire la clé.
+    /// Opus (RFC 7845) n'admet que R128_TRACK_GAIN/R128_ALBUM_GAIN, sans pics : les
+    /// REPLAYGAIN_* y sont retirés.
+    pub fn restaurer_gain(&self, chemin: &str, gains: &ReplayGainTags) -> Result<(), AppError> {
+        let path = Path::new(chemin);
+        let mut tagged_file = Probe::open(path)
+            .map_err(|e| AppError::Audio(format!("Erreur d'ouverture: {}", e)))?
+            .read()
+            .map_err(|e| AppError::Audio(format!("Erreur de lecture: {}", e)))?;
+
+        let opus = tagged_file.file_type() == FileType::Opus;
+        if tagged_file.primary_tag().is_none() {
+            tagged_file.insert_tag(Tag::new(tagged_file.primary_tag_type()));
+        }
+        let tag = tagged_file
+            .primary_tag_mut()
+            .ok_or_else(|| AppError::Audio("Format sans tag inscriptible".into()))?;
+
+        if opus {
+            CLES_REPLAYGAIN.iter().for_each(|key| tag.remove_key(key));
+            for (nom, gain) in CLES_R128
+                .into_iter()
+                .zip([gains.track_gain_db, gains.album_gain_db])
+            {
+                let key = ItemKey::Unknown(nom.to_string());
+                tag.remove_key(&key);
+                if let Some(gain) = gain {
+                    // Clé libre : `insert_text` refuse les clés qu'il ne connaît pas
+                    let q78 = replaygain_en_r128(gain).to_string();
+                    tag.insert_unchecked(TagItem::new(key, ItemValue::Text(q78)));
+                }
+            }
+            return tag
+                .save_to_path(path)
+                .map_err(|e| AppError::Audio(format!("Erreur sauvegarde tags: {}", e)));
+        }
+
+        let valeurs = [
+            gains.track_gain_db.map(|g| format!("{:.2} dB", g)),
+            gains.track_peak.map(|p| format!("{:.6}", p)),
+            gains.album_gain_db.map(|g| format!("{:.2} dB", g)),
+            gains.album_peak.map(|p| format!("{:.6}", p)),
+        ];
+        for (key, valeur) in CLES_REPLAYGAIN.into_iter().zip(valeurs) {
+            match valeur {
+                Some(valeur) => {
+                    tag.insert_text(key, valeur);
+                }
+                None => tag.remove_key(&key),
+            }
+        }
+
+        tag.save_to_path(path)
+            .map_err(|e| AppError::Audio(format!("Erreur sauvegarde tags: {}", e)))
+    }
+}
+
+/// "-6.52 dB" ou "0.988553" : seul le nombre compte
+fn nombre(valeur: &str) -> Option<f32> {
+    valeur
+        .trim()
+        .trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace())
+        .parse()
+        .ok()
+}
+
+fn r128_en_replaygain(tag: &Tag, nom: &str) -> Option<f32> {
+    let q78: f32 = tag
+        .get_string(&ItemKey::Unknown(nom.to_string()))?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(q78 / 256.0 + (REFERENCE_REPLAYGAIN_LUFS - REFERENCE_R128_LUFS) as f32)
+}
+
+/// Gain ReplayGain (-18 LUFS) en gain R128 Q7.8 (-23 LUFS, 1/256 dB)
+pub(super) fn replaygain_en_r128(gain_db: f32) -> i16 {
+    let r128_db = gain_db - (REFERENCE_REPLAYGAIN_LUFS - REFERENCE_R128_LUFS) as f32;
+    (r128_db * 256.0)
+        .round()
+        .clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16
+}
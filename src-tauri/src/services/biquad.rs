@@ -0,0 +1,136 @@
+use crate::models::{EqBand, EqFilterType};
+use std::f32::consts::PI;
+
+// --- BASIC BIQUAD FILTER IMPL ---
+#[derive(Clone)]
+pub struct Biquad {
+    a0: f32,
+    a1: f32,
+    a2: f32,
+    b1: f32,
+    b2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Default for Biquad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Biquad {
+    pub fn new() -> Self {
+        Self {
+            a0: 1.0,
+            a1: 0.0,
+            a2: 0.0,
+            b1: 0.0,
+            b2: 0.0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// Vide l'état interne (après un saut dans le flux)
+    pub fn reinitialiser(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        let out = sample * self.a0 + self.z1;
+        self.z1 = sample * self.a1 + self.z2 - self.b1 * out;
+        self.z2 = sample * self.a2 - self.b2 * out;
+        out
+    }
+
+    /// Coefficients RBJ de la bande ; l'état interne est conservé pour éviter un clic au réglage
+    pub fn configurer(&mut self, band: &EqBand, rate: u32) {
+        let a = 10.0f32.powf(band.gain_db / 40.0);
+        let w0 = 2.0 * PI * band.frequency_hz / rate as f32;
+        let alpha = w0.sin() / (2.0 * band.q.max(0.01));
+        let cos_w0 = w0.cos();
+        let sqrt_a = a.sqrt();
+
+        let (b0, b1, b2, a0, a1, a2) = match band.filter_type {
+            EqFilterType::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos_w0,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos_w0,
+                1.0 - alpha / a,
+            ),
+            EqFilterType::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 + 2.0 * sqrt_a * alpha),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 - 2.0 * sqrt_a * alpha),
+                (a + 1.0) + (a - 1.0) * cos_w0 + 2.0 * sqrt_a * alpha,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                (a + 1.0) + (a - 1.0) * cos_w0 - 2.0 * sqrt_a * alpha,
+            ),
+            EqFilterType::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 + 2.0 * sqrt_a * alpha),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 - 2.0 * sqrt_a * alpha),
+                (a + 1.0) - (a - 1.0) * cos_w0 + 2.0 * sqrt_a * alpha,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                (a + 1.0) - (a - 1.0) * cos_w0 - 2.0 * sqrt_a * alpha,
+            ),
+            EqFilterType::LowPass => (
+                (1.0 - cos_w0) / 2.0,
+                1.0 - cos_w0,
+                (1.0 - cos_w0) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+            EqFilterType::HighPass => (
+                (1.0 + cos_w0) / 2.0,
+                -(1.0 + cos_w0),
+                (1.0 + cos_w0) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos_w0,
+                1.0 - alpha,
+            ),
+        };
+
+        let a0 = min_check(a0);
+        if a0 == 0.0 {
+            *self = Self {
+                z1: self.z1,
+                z2: self.z2,
+                ..Self::new()
+            };
+            return;
+        }
+        self.a0 = min_check(b0) / a0;
+        self.a1 = min_check(b1) / a0;
+        self.a2 = min_check(b2) / a0;
+        self.b1 = min_check(a1) / a0;
+        self.b2 = min_check(a2) / a0;
+    }
+
+    /// Gain du filtre en dB à la fréquence donnée
+    pub fn reponse_db(&self, freq: f32, rate: u32) -> f32 {
+        let w = 2.0 * PI * freq / rate as f32;
+        let (c1, s1, c2, s2) = (w.cos(), w.sin(), (2.0 * w).cos(), (2.0 * w).sin());
+        let num_re = self.a0 + self.a1 * c1 + self.a2 * c2;
+        let num_im = -(self.a1 * s1 + self.a2 * s2);
+        let den_re = 1.0 + self.b1 * c1 + self.b2 * c2;
+        let den_im = -(self.b1 * s1 + self.b2 * s2);
+        let puissance =
+            (num_re * num_re + num_im * num_im) / (den_re * den_re + den_im * den_im).max(1e-12);
+        10.0 * puissance.max(1e-12).log10()
+    }
+}
+
+// Helper to prevent NaN/Infinity issues slightly
+fn min_check(val: f32) -> f32 {
+    if val.is_finite() {
+        val
+    } else {
+        0.0
+    }
+}
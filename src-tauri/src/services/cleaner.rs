@@ -1,5 +1,6 @@
-use crate::models::Track;
-use crate::services::RenamerService;
+use crate::models::changeset::{CoverMove, FileDeletion, FileRename};
+use crate::models::AppError;
+use crate::services::journal::Transaction;
 use std::fs;
 use std::path::Path;
 
@@ -16,171 +17,56 @@ impl CleanerService {
         Self
     }
 
-    /// Renomme les fichiers physiques des pistes
-    pub fn rename_track_files(
+    /// Renomme les fichiers physiques des pistes selon le plan
+    pub fn executer_renommages(
         &self,
-        tracks: &mut [Track],
+        tx: &mut Transaction,
         album_path: &Path,
-        _renamer: &RenamerService, // Unused now
-    ) {
-        for track in tracks {
-            let current_path = Path::new(&track.path);
-            if !current_path.exists() {
-                continue;
-            }
-
-            // Use the filename from the track struct (which might have been cleaned/edited)
-            // instead of regenerating it from tags.
-            let new_filename = track.filename.clone();
-
-            let target_path = album_path.join(&new_filename);
-
-            // Avoid overwriting if source == target
-            if target_path != current_path {
-                // Create parent dir if missing (should be album_path)
-                if let Some(parent) = target_path.parent() {
-                    if !parent.exists() {
-                        let _ = fs::create_dir_all(parent);
-                    }
-                }
-
-                match fs::rename(current_path, &target_path) {
-                    Ok(_) => {
-                        track.path = target_path.to_string_lossy().to_string();
-                        track.filename = new_filename;
-                    }
-                    Err(e) => eprintln!("Error renaming file: {}", e),
+        renames: &[FileRename],
+    ) -> Result<(), AppError> {
+        for rename in renames {
+            let target_path = album_path.join(&rename.to);
+
+            // Create parent dir if missing (should be album_path)
+            if let Some(parent) = target_path.parent() {
+                if !parent.exists() {
+                    fs::create_dir_all(parent)?;
                 }
             }
-        }
-    }
 
-    /// Gère l'image de couverture (recherche récursive et renommage)
-    pub fn handle_cover_image(&self, album_path: &Path) {
-        let mut images = Vec::new();
-        // Simple BFS/DFS to find all images
-        let mut stack = vec![album_path.to_path_buf()];
-        while let Some(dir) = stack.pop() {
-            if let Ok(entries) = fs::read_dir(&dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_dir() {
-                        stack.push(path);
-                    } else if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                        let ext_lower = ext.to_lowercase();
-                        if ["jpg", "jpeg", "bmp", "gif"].contains(&ext_lower.as_str()) {
-                            images.push(path);
-                        }
-                    }
-                }
-            }
+            tx.renommer(&album_path.join(&rename.from), &target_path)?;
         }
+        Ok(())
+    }
 
-        // Priority: cover.jpg > front.jpg > largest file
-        let target_cover = album_path.join("cover.jpg");
-
-        // Rename known patterns to cover.jpg
-        let rename_patterns = ["front", "folder", "album", "albumart", "artwork"];
-
-        for image_path in &images {
-            if let Some(stem) = image_path.file_stem().and_then(|s| s.to_str()) {
-                let stem_lower = stem.to_lowercase();
-                if rename_patterns.contains(&stem_lower.as_str()) {
-                    // If cover.jpg doesn't exist, rename this one to cover.jpg
-                    if !target_cover.exists() {
-                        let _ = fs::rename(image_path, &target_cover);
-                        return; // Done
-                    } else {
-                        // If cover.jpg exists, delete this duplicate/variant
-                        let _ = fs::remove_file(image_path);
-                    }
-                }
-                // Also handle albumart_... patterns if needed, but let's stick to exact matches first
-            }
-        }
-
-        if !target_cover.exists() && !images.is_empty() {
-            let best_image = images
-                .iter()
-                .find(|p| {
-                    let name = p
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("")
-                        .to_lowercase();
-                    name == "cover.jpg"
-                })
-                .or_else(|| {
-                    images.iter().find(|p| {
-                        let name = p
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("")
-                            .to_lowercase();
-                        name == "front.jpg"
-                    })
-                })
-                .or_else(|| {
-                    images
-                        .iter()
-                        .max_by_key(|p| p.metadata().map(|m| m.len()).unwrap_or(0))
-                });
-
-            if let Some(src) = best_image {
-                if src.exists() {
-                    // Check existence as it might have been renamed/deleted above
-                    let _ = fs::rename(src, &target_cover);
-                }
-            }
+    /// Déplace les images de pochette selon le plan
+    pub fn executer_deplacements(
+        &self,
+        tx: &mut Transaction,
+        album_path: &Path,
+        moves: &[CoverMove],
+    ) -> Result<(), AppError> {
+        for m in moves {
+            tx.renommer(&album_path.join(&m.from), &album_path.join(&m.to))?;
         }
+        Ok(())
     }
 
-    /// Nettoie le dossier (fichiers indésirables et dossiers vides)
-    pub fn clean_directory(&self, album_path: &Path) {
-        let audio_ext = ["mp3", "flac", "ogg", "m4a", "wav"];
-
-        // Pass 1: Delete junk files recursively (Strict Whitelist Logic)
-        let mut dirs_to_check = vec![album_path.to_path_buf()];
-        while let Some(dir) = dirs_to_check.pop() {
-            if let Ok(entries) = fs::read_dir(&dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_dir() {
-                        dirs_to_check.push(path);
-                    } else {
-                        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                        let ext = path
-                            .extension()
-                            .and_then(|e| e.to_str())
-                            .unwrap_or("")
-                            .to_lowercase();
-
-                        // Règle stricte : On garde uniquement les fichiers audio et "cover.jpg"
-                        let is_audio = audio_ext.contains(&ext.as_str());
-                        let is_cover = name == "cover.jpg"; // Strict case check
-
-                        if !is_audio && !is_cover {
-                            // TENTATIVE DE SAUVETAGE : Si c'est "Cover.jpg", "COVER.jpg" etc., on le normalise
-                            if name.to_lowercase() == "cover.jpg" {
-                                let target_path = path.with_file_name("cover.jpg");
-                                if !target_path.exists() {
-                                    if let Ok(_) = fs::rename(&path, &target_path) {
-                                        // Renommage réussi, le fichier est sauvé
-                                        continue;
-                                    }
-                                }
-                            }
-
-                            // C'est un fichier inutile ou un doublon, on supprime
-                            let _ = fs::remove_file(&path);
-                        }
-                    }
-                }
-            }
+    /// Retire les fichiers indésirables (quarantaine ou corbeille selon les réglages)
+    pub fn executer_suppressions(
+        &self,
+        tx: &mut Transaction,
+        album_path: &Path,
+        deletions: &[FileDeletion],
+    ) -> Result<(), AppError> {
+        for d in deletions {
+            tx.supprimer(&album_path.join(&d.path), &d.reason)?;
         }
+        Ok(())
+    }
 
-        // Pass 2: Delete empty directories
+    /// Supprime les sous-dossiers devenus vides
+    pub fn supprimer_dossiers_vides(&self, album_path: &Path) {
         for _ in 0..3 {
             let mut subdirs = Vec::new();
             let mut stack = vec![album_path.to_path_buf()];
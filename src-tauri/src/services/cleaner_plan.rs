@@ -0,0 +1,288 @@
+use super::cleaning_rules::REGLE_PAR_DEFAUT;
+use super::{CleanerService, CleaningRuleService};
+use crate::models::changeset::{CoverMove, FileDeletion, FileRename};
+use crate::models::{Album, CleaningRule, RuleAction};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Planification du nettoyage, séparée de cleaner.rs pour limiter la taille du fichier.
+// Les plans travaillent sur une liste virtuelle de fichiers, ce qui permet de les
+// prévisualiser (plan_auto_correct) avant de les exécuter.
+
+const COVER_NAME: &str = "cover.jpg";
+const REGLE_POCHETTE: &str = "Choix de la pochette";
+
+/// Fichier présent dans le dossier d'un album (chemin relatif au dossier)
+#[derive(Debug, Clone)]
+pub struct FichierAlbum {
+    pub chemin: PathBuf,
+    pub taille: u64,
+}
+
+impl CleanerService {
+    /// Liste récursivement les fichiers de l'album (chemins relatifs, triés)
+    pub fn lister_fichiers(&self, album_path: &Path) -> Vec<FichierAlbum> {
+        let mut fichiers = Vec::new();
+        let mut stack = vec![album_path.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            if let Ok(entries) = fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        stack.push(path);
+                    } else if let Ok(relative) = path.strip_prefix(album_path) {
+                        fichiers.push(FichierAlbum {
+                            chemin: relative.to_path_buf(),
+                            taille: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                        });
+                    }
+                }
+            }
+        }
+        fichiers.sort_by(|a, b| a.chemin.cmp(&b.chemin));
+        fichiers
+    }
+
+    /// Renommages des pistes, exécutés dans l'ordre du plan. Une cible occupée à ce moment-là
+    /// (fichier qui reste, piste déjà renommée, même nom à la casse près) reçoit un suffixe
+    /// " (2)", " (3)"... : un renommage n'écrase jamais un fichier.
+    /// `fichiers` est mis à jour pour refléter l'état après exécution.
+    pub fn planifier_renommages(
+        &self,
+        album: &Album,
+        root: &Path,
+        fichiers: &mut [FichierAlbum],
+    ) -> Vec<FileRename> {
+        let cle = |p: &Path| p.to_string_lossy().to_lowercase();
+        let mut occupes: HashSet<String> = fichiers.iter().map(|f| cle(&f.chemin)).collect();
+        let mut renames = Vec::new();
+        for track in &album.tracks {
+            let Ok(relative) = Path::new(&track.path).strip_prefix(root) else {
+                continue;
+            };
+            let cible = PathBuf::from(&track.filename);
+            if relative == cible {
+                continue;
+            }
+            occupes.remove(&cle(relative));
+            let cible = std::iter::once(cible.clone())
+                .chain((2..).map(|n| suffixer(&cible, n)))
+                .find(|c| !occupes.contains(&cle(c)))
+                .unwrap_or(cible);
+            occupes.insert(cle(&cible));
+            if relative == cible {
+                continue;
+            }
+
+            let reason = if relative.parent().is_some_and(|p| !p.as_os_str().is_empty()) {
+                "Déplacement à la racine de l'album".to_string()
+            } else if cible != Path::new(&track.filename) {
+                format!("Nom de fichier corrigé ({} est déjà pris)", track.filename)
+            } else {
+                "Nom de fichier corrigé".to_string()
+            };
+            renames.push(FileRename {
+                from: chemin_str(relative),
+                to: chemin_str(&cible),
+                reason,
+            });
+            deplacer(fichiers, relative, &cible);
+        }
+        renames
+    }
+
+    /// Planifie le traitement de la pochette (renommage en cover.jpg, doublons), avant le nettoyage.
+    /// Les images visées par une règle de nettoyage (ex: back.jpg) ne sont pas choisies, sauf
+    /// si seule une image promise à la suppression peut servir de pochette.
+    /// `fichiers` est mis à jour pour refléter l'état après exécution du plan.
+    pub fn planifier_cover(
+        &self,
+        fichiers: &mut Vec<FichierAlbum>,
+        regles: &[CleaningRule],
+    ) -> (Vec<CoverMove>, Vec<FileDeletion>) {
+        let mut moves = Vec::new();
+        let mut deletions = Vec::new();
+        let mut libres = Vec::new();
+        let mut a_supprimer = Vec::new();
+        for f in fichiers.iter().filter(|f| {
+            let ext = extension_minuscule(&f.chemin);
+            ["jpg", "jpeg", "bmp", "gif"].contains(&ext.as_str())
+        }) {
+            let verdict = CleaningRuleService::evaluer(regles, &f.chemin, f.taille);
+            if verdict.regle == REGLE_PAR_DEFAUT {
+                libres.push(f.clone());
+            } else if verdict.action == RuleAction::Delete {
+                a_supprimer.push(f.clone());
+            }
+            // Gardée ou renommée par une règle : on n'y touche pas
+        }
+        let images: Vec<FichierAlbum> = if libres.is_empty() {
+            a_supprimer
+        } else {
+            libres
+        };
+
+        // Priority: cover.jpg > front.jpg > largest file
+        let target_cover = PathBuf::from(COVER_NAME);
+
+        // Rename known patterns to cover.jpg
+        let rename_patterns = ["front", "folder", "album", "albumart", "artwork"];
+
+        for image in &images {
+            let stem_lower = image
+                .chemin
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if rename_patterns.contains(&stem_lower.as_str()) {
+                if !contient(fichiers, &target_cover) {
+                    moves.push(CoverMove {
+                        from: chemin_str(&image.chemin),
+                        to: COVER_NAME.to_string(),
+                        reason: format!("Image \"{}\" reconnue comme pochette", stem_lower),
+                        rule: REGLE_POCHETTE.to_string(),
+                    });
+                    deplacer(fichiers, &image.chemin, &target_cover);
+                    return (moves, deletions); // Done
+                } else {
+                    deletions.push(FileDeletion {
+                        path: chemin_str(&image.chemin),
+                        size: image.taille,
+                        reason: "Doublon de pochette (cover.jpg existe déjà)".to_string(),
+                        rule: REGLE_POCHETTE.to_string(),
+                    });
+                    fichiers.retain(|f| f.chemin != image.chemin);
+                }
+            }
+        }
+
+        if !contient(fichiers, &target_cover) && !images.is_empty() {
+            let nom = |f: &FichierAlbum| {
+                f.chemin
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_lowercase())
+                    .unwrap_or_default()
+            };
+            let best_image = images
+                .iter()
+                .find(|f| nom(f) == COVER_NAME)
+                .map(|f| (f, "Pochette trouvée dans un sous-dossier"))
+                .or_else(|| {
+                    images
+                        .iter()
+                        .find(|f| nom(f) == "front.jpg")
+                        .map(|f| (f, "Image front.jpg utilisée comme pochette"))
+                })
+                .or_else(|| {
+                    images
+                        .iter()
+                        .max_by_key(|f| f.taille)
+                        .map(|f| (f, "Plus grande image du dossier utilisée comme pochette"))
+                });
+
+            if let Some((src, reason)) = best_image {
+                if contient(fichiers, &src.chemin) {
+                    moves.push(CoverMove {
+                        from: chemin_str(&src.chemin),
+                        to: COVER_NAME.to_string(),
+                        reason: reason.to_string(),
+                        rule: REGLE_POCHETTE.to_string(),
+                    });
+                    deplacer(fichiers, &src.chemin, &target_cover);
+                }
+            }
+        }
+
+        (moves, deletions)
+    }
+
+    /// Planifie le nettoyage (fichiers indésirables) selon les règles de nettoyage,
+    /// puis la liste blanche par défaut.
+    /// `fichiers` est mis à jour pour refléter l'état après exécution du plan.
+    pub fn planifier_nettoyage(
+        &self,
+        fichiers: &mut Vec<FichierAlbum>,
+        regles: &[CleaningRule],
+    ) -> (Vec<CoverMove>, Vec<FileDeletion>) {
+        let mut moves = Vec::new();
+        let mut deletions = Vec::new();
+
+        for fichier in fichiers.clone() {
+            let verdict = CleaningRuleService::evaluer(regles, &fichier.chemin, fichier.taille);
+            match verdict.action {
+                RuleAction::Keep => continue,
+                RuleAction::Rename => {
+                    // TENTATIVE DE SAUVETAGE : "Cover.jpg" → "cover.jpg", ou renommage demandé par une règle
+                    let new_name = verdict.rename_to.clone().unwrap_or_default();
+                    let target_path = fichier.chemin.with_file_name(&new_name);
+                    if target_path == fichier.chemin {
+                        continue;
+                    }
+                    if !new_name.is_empty() && !contient(fichiers, &target_path) {
+                        moves.push(CoverMove {
+                            from: chemin_str(&fichier.chemin),
+                            to: chemin_str(&target_path),
+                            reason: format!("Renommage en {}", new_name),
+                            rule: verdict.regle.clone(),
+                        });
+                        deplacer(fichiers, &fichier.chemin, &target_path);
+                    }
+                    // Cible déjà prise (plusieurs fichiers pour un même nom) : le fichier reste
+                    // tel quel, un conflit de nom ne devient jamais une suppression
+                    continue;
+                }
+                RuleAction::Delete => {}
+            }
+
+            // C'est un fichier inutile ou un doublon, on supprime
+            let ext = extension_minuscule(&fichier.chemin);
+            let reason = if verdict.regle != REGLE_PAR_DEFAUT {
+                "Supprimé par une règle de nettoyage".to_string()
+            } else if ext.is_empty() {
+                "Fichier sans extension".to_string()
+            } else {
+                format!("Extension .{} hors liste blanche", ext)
+            };
+            deletions.push(FileDeletion {
+                path: chemin_str(&fichier.chemin),
+                size: fichier.taille,
+                reason,
+                rule: verdict.regle,
+            });
+            fichiers.retain(|f| f.chemin != fichier.chemin);
+        }
+
+        (moves, deletions)
+    }
+}
+
+fn extension_minuscule(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+fn chemin_str(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn contient(fichiers: &[FichierAlbum], chemin: &Path) -> bool {
+    fichiers.iter().any(|f| f.chemin == chemin)
+}
+
+fn deplacer(fichiers: &mut [FichierAlbum], from: &Path, to: &Path) {
+    if let Some(f) = fichiers.iter_mut().find(|f| f.chemin == from) {
+        f.chemin = to.to_path_buf();
+    }
+}
+
+/// "nom.ext" -> "nom (n).ext"
+fn suffixer(cible: &Path, n: u32) -> PathBuf {
+    let stem = cible.file_stem().unwrap_or_default().to_string_lossy();
+    match cible.extension() {
+        Some(ext) => cible.with_file_name(format!("{} ({}).{}", stem, n, ext.to_string_lossy())),
+        None => cible.with_file_name(format!("{} ({})", stem, n)),
+    }
+}
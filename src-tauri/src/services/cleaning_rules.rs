@@ -0,0 +1,283 @@
+use crate::db::Database;
+use crate::models::{AppError, CleaningRule, RuleAction};
+use lazy_static::lazy_static;
+use regex::Regex;
+use rusqlite::params;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+const AUDIO_EXTENSIONS: [&str; 5] = ["mp3", "flac", "ogg", "m4a", "wav"];
+const PLAYLIST_EXTENSIONS: [&str; 2] = ["m3u", "m3u8"];
+const COVER_NAME: &str = "cover.jpg";
+pub const REGLE_PAR_DEFAUT: &str = "Liste blanche par défaut";
+
+lazy_static! {
+    // Copie en mémoire des règles de la base, utilisée par le validateur qui n'a pas accès à la base
+    static ref REGLES_ACTIVES: RwLock<Vec<CleaningRule>> = RwLock::new(Vec::new());
+    // Globs des règles actives, compilés une fois au chargement plutôt qu'à chaque fichier
+    static ref GLOBS: RwLock<HashMap<String, Regex>> = RwLock::new(HashMap::new());
+}
+
+/// Décision du nettoyage pour un fichier, avec la règle qui l'a motivée
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub action: RuleAction,
+    pub rename_to: Option<String>,
+    pub regle: String,
+}
+
+pub struct CleaningRuleService;
+
+impl CleaningRuleService {
+    pub fn create(db: &Database, mut rule: CleaningRule) -> Result<CleaningRule, AppError> {
+        Self::valider(&mut rule)?;
+        {
+            let conn = verrouiller(db)?;
+            conn.execute(
+                "INSERT INTO cleaning_rules (pattern, extension, min_size, max_size, action, rename_to)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    rule.pattern,
+                    rule.extension,
+                    rule.min_size,
+                    rule.max_size,
+                    rule.action.as_str(),
+                    rule.rename_to
+                ],
+            )?;
+            rule.id = Some(conn.last_insert_rowid());
+        }
+
+        Self::charger(db)?;
+        Ok(rule)
+    }
+
+    /// Normalise l'extension et refuse les règles incomplètes ou dangereuses
+    pub fn valider(rule: &mut CleaningRule) -> Result<(), AppError> {
+        if rule.pattern.is_none()
+            && rule.extension.is_none()
+            && rule.min_size.is_none()
+            && rule.max_size.is_none()
+        {
+            return Err(AppError::Validation(
+                "Une règle doit avoir au moins un critère".to_string(),
+            ));
+        }
+        if rule.action == RuleAction::Rename && rule.rename_to.is_none() {
+            return Err(AppError::Validation(
+                "Une règle de renommage doit indiquer le nouveau nom".to_string(),
+            ));
+        }
+        if let Some(nom) = &rule.rename_to {
+            // Un nom seul : la cible doit rester dans le dossier du fichier renommé
+            if nom.contains(['/', '\\']) || Path::new(nom).file_name() != Some(nom.as_ref()) {
+                return Err(AppError::Validation(format!(
+                    "Nouveau nom invalide, sans dossier ni \"..\" : {}",
+                    nom
+                )));
+            }
+        }
+        rule.extension = rule
+            .extension
+            .take()
+            .map(|e| e.trim_start_matches('.').to_lowercase());
+        if rule.action == RuleAction::Delete && vise_audio(rule) {
+            return Err(AppError::Validation(
+                "Une règle de suppression ne peut pas viser des fichiers audio".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn get_all(db: &Database) -> Result<Vec<CleaningRule>, AppError> {
+        let conn = verrouiller(db)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, pattern, extension, min_size, max_size, action, rename_to
+             FROM cleaning_rules ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(CleaningRule {
+                id: Some(row.get(0)?),
+                pattern: row.get(1)?,
+                extension: row.get(2)?,
+                min_size: row.get(3)?,
+                max_size: row.get(4)?,
+                action: RuleAction::from_db(&row.get::<_, String>(5)?),
+                rename_to: row.get(6)?,
+            })
+        })?;
+
+        let mut rules = Vec::new();
+        for row in rows {
+            rules.push(row?);
+        }
+        Ok(rules)
+    }
+
+    pub fn delete(db: &Database, id: i64) -> Result<(), AppError> {
+        verrouiller(db)?.execute("DELETE FROM cleaning_rules WHERE id = ?1", params![id])?;
+        Self::charger(db)
+    }
+
+    /// Recharge la copie en mémoire des règles (au démarrage et après chaque modification)
+    pub fn charger(db: &Database) -> Result<(), AppError> {
+        let rules = Self::get_all(db)?;
+        if let Ok(mut globs) = GLOBS.write() {
+            *globs = rules
+                .iter()
+                .filter_map(|r| r.pattern.as_ref())
+                .filter_map(|p| Some((p.clone(), compiler_glob(p)?)))
+                .collect();
+        }
+        if let Ok(mut actives) = REGLES_ACTIVES.write() {
+            *actives = rules;
+        }
+        Ok(())
+    }
+
+    pub fn actives() -> Vec<CleaningRule> {
+        REGLES_ACTIVES
+            .read()
+            .map(|r| r.clone())
+            .unwrap_or_default()
+    }
+
+    /// Décide du sort d'un fichier (chemin relatif à l'album).
+    /// Les règles utilisateur passent en premier, dans l'ordre de création,
+    /// puis la liste blanche par défaut : audio, playlists et "cover.jpg" strictement en minuscules.
+    pub fn evaluer(regles: &[CleaningRule], chemin: &Path, taille: u64) -> Verdict {
+        if let Some(rule) = regles.iter().find(|r| Self::correspond(r, chemin, taille)) {
+            return Verdict {
+                action: rule.action,
+                rename_to: rule.rename_to.clone(),
+                regle: rule.label(),
+            };
+        }
+
+        let name = chemin
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let ext = chemin
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let (action, rename_to, regle) = if AUDIO_EXTENSIONS.contains(&ext.as_str()) {
+            (RuleAction::Keep, None, "Fichier audio")
+        } else if PLAYLIST_EXTENSIONS.contains(&ext.as_str()) {
+            // Une playlist mal nommée n'est pas inutile : le validateur la signale à part
+            (RuleAction::Keep, None, "Playlist")
+        } else if name == COVER_NAME {
+            (RuleAction::Keep, None, "Pochette cover.jpg")
+        } else if name.to_lowercase() == COVER_NAME {
+            (
+                RuleAction::Rename,
+                Some(COVER_NAME.to_string()),
+                "Casse de cover.jpg",
+            )
+        } else {
+            (RuleAction::Delete, None, REGLE_PAR_DEFAUT)
+        };
+        Verdict {
+            action,
+            rename_to,
+            regle: regle.to_string(),
+        }
+    }
+
+    pub fn correspond(rule: &CleaningRule, chemin: &Path, taille: u64) -> bool {
+        if let Some(ext) = &rule.extension {
+            let file_ext = chemin
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if !file_ext.eq_ignore_ascii_case(ext.trim_start_matches('.')) {
+                return false;
+            }
+        }
+        if rule.min_size.is_some_and(|min| taille < min)
+            || rule.max_size.is_some_and(|max| taille > max)
+        {
+            return false;
+        }
+        match &rule.pattern {
+            Some(pattern) => glob_correspond(pattern, chemin),
+            None => true,
+        }
+    }
+}
+
+/// Sans "/", le motif porte sur le nom du fichier ; "scans/" désigne tout le dossier.
+fn glob_correspond(pattern: &str, chemin: &Path) -> bool {
+    let relatif = chemin.to_string_lossy().replace('\\', "/");
+    let cible = if pattern.contains('/') {
+        relatif.as_str()
+    } else {
+        relatif.rsplit('/').next().unwrap_or_default()
+    };
+    if let Some(re) = GLOBS.read().ok().as_ref().and_then(|g| g.get(pattern)) {
+        return re.is_match(cible);
+    }
+    // Règle absente de la base (aperçu, tests) : compilée à la volée
+    compiler_glob(pattern).is_some_and(|re| re.is_match(cible))
+}
+
+/// Glob insensible à la casse : "*" et "?" dans un segment, "**" sur plusieurs dossiers
+fn compiler_glob(pattern: &str) -> Option<Regex> {
+    let motif = if pattern.ends_with('/') {
+        format!("{}**", pattern)
+    } else {
+        pattern.to_string()
+    };
+
+    let mut regex = String::from("(?i)^");
+    let mut chars = motif.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            other => regex.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).ok()
+}
+
+/// Vrai si la règle peut correspondre à un fichier audio : son extension en est une, ou un
+/// exemple concret du motif (jokers remplacés) reste reconnu avec une extension audio
+fn vise_audio(rule: &CleaningRule) -> bool {
+    if let Some(ext) = &rule.extension {
+        return AUDIO_EXTENSIONS.contains(&ext.as_str());
+    }
+    let Some(pattern) = &rule.pattern else {
+        return true; // Critères de taille seuls : tous les fichiers sont concernés
+    };
+    let Some(re) = compiler_glob(pattern) else {
+        return false;
+    };
+    let exemple = pattern
+        .replace("**", "")
+        .replace('*', "piste")
+        .replace('?', "a");
+    AUDIO_EXTENSIONS.iter().any(|ext| {
+        let deja_audio = exemple.to_lowercase().ends_with(&format!(".{}", ext));
+        re.is_match(&format!("{}.{}", exemple, ext)) || (deja_audio && re.is_match(&exemple))
+    })
+}
+
+fn verrouiller(db: &Database) -> Result<std::sync::MutexGuard<'_, rusqlite::Connection>, AppError> {
+    db.conn
+        .lock()
+        .map_err(|_| AppError::DatabaseError("Lock error".into()))
+}
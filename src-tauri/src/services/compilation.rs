@@ -0,0 +1,113 @@
+use super::{ConsistencyService, SettingsService};
+use crate::db::Database;
+use crate::models::changeset::TagField;
+use crate::models::{Album, AppError, Track};
+use lazy_static::lazy_static;
+use std::sync::RwLock;
+
+pub const SETTING_VARIOUS_ARTISTS: &str = "compilation.various_artists_name";
+const DEFAULT_VARIOUS_ARTISTS: &str = "Various Artists";
+const ALIAS_VARIOUS_ARTISTS: [&str; 5] = [
+    "various artists",
+    "various",
+    "va",
+    "v.a.",
+    "artistes divers",
+];
+
+lazy_static! {
+    // Nom configuré pour l'artiste des compilations, lu par le scanner et le validateur sans la base
+    static ref NOM_VARIOUS_ARTISTS: RwLock<String> = RwLock::new(DEFAULT_VARIOUS_ARTISTS.to_string());
+}
+
+pub struct CompilationService;
+
+impl CompilationService {
+    pub fn nom_various_artists() -> String {
+        NOM_VARIOUS_ARTISTS
+            .read()
+            .map(|n| n.clone())
+            .unwrap_or_else(|_| DEFAULT_VARIOUS_ARTISTS.to_string())
+    }
+
+    pub fn charger(db: &Database) -> Result<(), AppError> {
+        let nom = SettingsService::lire_ou(db, SETTING_VARIOUS_ARTISTS, DEFAULT_VARIOUS_ARTISTS)?;
+        if let Ok(mut current) = NOM_VARIOUS_ARTISTS.write() {
+            *current = nom;
+        }
+        Ok(())
+    }
+
+    pub fn definir_nom(db: &Database, nom: &str) -> Result<(), AppError> {
+        let nom = nom.trim();
+        if nom.is_empty() {
+            return Err(AppError::Validation(
+                "Le nom des compilations ne peut pas être vide".to_string(),
+            ));
+        }
+        SettingsService::ecrire(db, SETTING_VARIOUS_ARTISTS, nom)?;
+        Self::charger(db)
+    }
+
+    pub fn est_nom_various_artists(nom: &str) -> bool {
+        let nom = nom.trim().to_lowercase();
+        nom == Self::nom_various_artists().to_lowercase()
+            || ALIAS_VARIOUS_ARTISTS.contains(&nom.as_str())
+    }
+
+    /// Compilation : drapeau de compilation sur la majorité des pistes, album artist
+    /// "Various Artists" (ou alias), ou artistes principaux différents d'une piste à l'autre
+    pub fn est_compilation(album: &Album) -> bool {
+        if album.tracks.is_empty() {
+            return false;
+        }
+        let marquees = album.tracks.iter().filter(|t| t.compilation).count();
+        if marquees * 2 > album.tracks.len() {
+            return true;
+        }
+
+        let tracks: Vec<&Track> = album.tracks.iter().collect();
+        // Un album artist partagé par la majorité des pistes tranche la question
+        if let Some(album_artist) = ConsistencyService::repartition(&tracks, TagField::AlbumArtist)
+            .first()
+            .filter(|v| v.tracks.len() * 2 > album.tracks.len())
+        {
+            return Self::est_nom_various_artists(&album_artist.value);
+        }
+
+        let mut artistes: Vec<String> = album
+            .tracks
+            .iter()
+            .map(|t| Self::artiste_principal(&t.artist))
+            .filter(|a| !a.is_empty())
+            .collect();
+        artistes.sort();
+        artistes.dedup();
+        album.tracks.len() > 2 && artistes.len() * 2 > album.tracks.len()
+    }
+
+    /// Artiste de l'album : nom des compilations, sinon artiste de la première piste
+    pub fn artiste_album(album: &Album) -> String {
+        if Self::est_compilation(album) {
+            Self::nom_various_artists()
+        } else {
+            album
+                .tracks
+                .first()
+                .map(|t| t.artist.clone())
+                .filter(|a| !a.is_empty())
+                .unwrap_or_else(|| album.artist.clone())
+        }
+    }
+
+    /// Retire les invités ("feat.", "ft.") pour comparer les artistes principaux
+    fn artiste_principal(artist: &str) -> String {
+        let lower = artist.to_lowercase();
+        let fin = [" feat.", " feat ", " ft.", " featuring "]
+            .iter()
+            .filter_map(|sep| lower.find(sep))
+            .min()
+            .unwrap_or(lower.len());
+        lower[..fin].trim().to_string()
+    }
+}
@@ -0,0 +1,123 @@
+use super::{CompilationService, NumberingService, PlannerService};
+use crate::models::changeset::{FieldChange, TagField};
+use crate::models::consistency::{FieldConsistency, FieldValue};
+use crate::models::{Album, Track};
+
+// Champs qui doivent être identiques sur toutes les pistes d'un album
+const CHAMPS_ALBUM: [TagField; 5] = [
+    TagField::Album,
+    TagField::AlbumArtist,
+    TagField::Year,
+    TagField::Genre,
+    TagField::TrackTotal,
+];
+
+pub struct ConsistencyService;
+
+impl ConsistencyService {
+    /// Valeurs non vides d'un champ, de la plus fréquente à la moins fréquente
+    pub fn repartition(tracks: &[&Track], field: TagField) -> Vec<FieldValue> {
+        let mut values: Vec<FieldValue> = Vec::new();
+        for track in tracks {
+            let Some(value) = PlannerService::valeur_champ(track, field) else {
+                continue;
+            };
+            if value.trim().is_empty() {
+                continue;
+            }
+            match values.iter_mut().find(|v| v.value == value) {
+                Some(v) => v.tracks.push(track.path.clone()),
+                None => values.push(FieldValue {
+                    value,
+                    tracks: vec![track.path.clone()],
+                }),
+            }
+        }
+        // Tri stable : à égalité, la première valeur rencontrée l'emporte
+        values.sort_by_key(|v| std::cmp::Reverse(v.tracks.len()));
+        values
+    }
+
+    /// Champs en désaccord. Sur une compilation, année et genre peuvent varier.
+    pub fn champs_incoherents(album: &Album) -> Vec<FieldConsistency> {
+        let compilation = CompilationService::est_compilation(album);
+        let mut resultats = Vec::new();
+
+        for field in CHAMPS_ALBUM {
+            if compilation && matches!(field, TagField::Year | TagField::Genre) {
+                continue;
+            }
+            for (disc, tracks) in Self::groupes(album, field) {
+                let values = Self::repartition(&tracks, field);
+                if values.len() > 1 {
+                    resultats.push(FieldConsistency {
+                        field,
+                        disc,
+                        values,
+                    });
+                }
+            }
+        }
+        resultats
+    }
+
+    /// Applique une valeur commune à toutes les pistes : la valeur choisie,
+    /// sinon la valeur majoritaire ("Various Artists" pour l'album artist d'une compilation).
+    /// Sans champ précisé, tous les champs d'album sont unifiés.
+    pub fn unifier(album: &mut Album, field: Option<TagField>, value: Option<String>) {
+        let compilation = CompilationService::est_compilation(album);
+        let champs: Vec<TagField> = match field {
+            Some(field) => vec![field],
+            None => CHAMPS_ALBUM
+                .into_iter()
+                .filter(|f| !(compilation && matches!(f, TagField::Year | TagField::Genre)))
+                .collect(),
+        };
+
+        for field in champs {
+            let mut affectations: Vec<(String, String)> = Vec::new(); // (chemin, valeur)
+            for (_, tracks) in Self::groupes(album, field) {
+                let cible = match (&value, field) {
+                    (Some(v), _) => Some(v.clone()),
+                    (None, TagField::AlbumArtist) if compilation => {
+                        Some(CompilationService::nom_various_artists())
+                    }
+                    (None, _) => Self::repartition(&tracks, field)
+                        .first()
+                        .map(|v| v.value.clone()),
+                };
+                if let Some(cible) = cible {
+                    affectations.extend(tracks.iter().map(|t| (t.path.clone(), cible.clone())));
+                }
+            }
+
+            for track in &mut album.tracks {
+                let Some((_, cible)) = affectations.iter().find(|(p, _)| *p == track.path) else {
+                    continue;
+                };
+                let old = PlannerService::valeur_champ(track, field);
+                if old.as_deref() != Some(cible.as_str()) {
+                    let change = FieldChange {
+                        field,
+                        old,
+                        new: Some(cible.clone()),
+                    };
+                    PlannerService::appliquer_changements(track, &[change]);
+                    track.is_modified = true;
+                }
+            }
+        }
+    }
+
+    /// Pistes à comparer entre elles : tout l'album, ou chaque disque pour le total de pistes
+    fn groupes(album: &Album, field: TagField) -> Vec<(Option<u32>, Vec<&Track>)> {
+        if field == TagField::TrackTotal {
+            NumberingService::par_disque(&album.tracks)
+                .into_iter()
+                .map(|(disc, tracks)| (Some(disc), tracks))
+                .collect()
+        } else {
+            vec![(None, album.tracks.iter().collect())]
+        }
+    }
+}
@@ -1,4 +1,17 @@
-use std::process::Command;
+use super::ffmpeg::FfmpegService;
+use super::flac_encoder::{format_accepte, EncodeurFlac, TAILLE_BLOC};
+use super::pcm::Pcm;
+use super::render::{ecrire_entete, terminer_donnees};
+use super::wav_trim::rogner_wav;
+use super::AudioService;
+use crate::models::{
+    AppError, ConvertFormat, ConvertOptions, ConvertQuality, ConvertResult, RenderFormat, Track,
+    TrimProposal,
+};
+use lofty::{Probe, TagExt, TaggedFileExt};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
 
 pub struct ConverterService;
 
@@ -13,31 +26,210 @@ impl ConverterService {
         Self
     }
 
-    pub fn convert_to_mp3(
+    /// Convertit vers le format demandé : FLAC et WAV en natif, les autres formats via ffmpeg.
+    /// Un fichier que le décodeur natif ne sait pas lire, ou dont la résolution dépasse ce que
+    /// l'encodeur natif écrit (WAV entier 32 bits), passe aussi par ffmpeg s'il est présent.
+    /// Les tags et la pochette sont ensuite recopiés puis relus pour signaler ce qui s'est perdu.
+    pub fn convertir(
         &self,
         input_path: &str,
         output_path: &str,
-        bitrate: &str,
-    ) -> Result<(), String> {
-        // Check if ffmpeg is installed
-        // For now, assume it is in PATH
-
-        let status = Command::new("ffmpeg")
-            .arg("-i")
-            .arg(input_path)
-            .arg("-codec:a")
-            .arg("libmp3lame")
-            .arg("-b:a")
-            .arg(bitrate)
-            .arg("-y") // Overwrite
-            .arg(output_path)
-            .status()
-            .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
-
-        if status.success() {
-            Ok(())
+        options: &ConvertOptions,
+    ) -> Result<ConvertResult, String> {
+        let natif = if options.format.est_natif() {
+            Pcm::ouvrir(Path::new(input_path))
+                .ok()
+                .filter(|pcm| Self::encodable(pcm, options.format))
         } else {
-            Err("FFmpeg conversion failed".to_string())
+            None
+        };
+        let encoder = match natif {
+            Some(pcm) => {
+                Self::encoder_natif(pcm, output_path, options).map_err(|e| e.to_string())?;
+                "natif".to_string()
+            }
+            None => {
+                let ffmpeg = FfmpegService::detecter();
+                FfmpegService::encoder(&ffmpeg, input_path, output_path, options)?;
+                format!("ffmpeg {}", ffmpeg.version.unwrap_or_default())
+                    .trim_end()
+                    .to_string()
+            }
+        };
+        let (lost_fields, tag_error) = match Self::reporter_tags(input_path, output_path) {
+            Ok(perdus) => (perdus, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        Ok(ConvertResult {
+            source: input_path.to_string(),
+            output: output_path.to_string(),
+            format: options.format,
+            encoder,
+            lost_fields,
+            tag_error,
+        })
+    }
+
+    /// Recopie chaque champ de la piste et la pochette avant, puis relit la sortie :
+    /// renvoie les champs qui n'ont pas survécu (format sans le champ, tag qui le tronque...)
+    fn reporter_tags(input_path: &str, output_path: &str) -> Result<Vec<String>, AppError> {
+        let audio = AudioService::new();
+        let source = audio.lire_metadonnees(input_path)?;
+        let cover = audio.lire_cover(input_path)?;
+        let mut copie = source.clone();
+        copie.path = output_path.to_string();
+        audio.ecrire_metadonnees(&copie)?;
+        if let Some(picture) = &cover {
+            audio.poser_cover(output_path, picture.clone())?;
+        }
+
+        let relu = audio.lire_metadonnees(output_path)?;
+        let mut perdus = champs_perdus(&source, &relu);
+        let relue = audio.lire_cover(output_path)?;
+        if cover.is_some_and(|p| relue.is_none_or(|r| r.data() != p.data())) {
+            perdus.push("cover".to_string());
+        }
+        Ok(perdus)
+    }
+
+    fn encodable(pcm: &Pcm, format: ConvertFormat) -> bool {
+        match format {
+            ConvertFormat::Flac => format_accepte(pcm.bits, pcm.channels),
+            _ => pcm.bits <= 24,
+        }
+    }
+
+    fn encoder_natif(
+        mut pcm: Pcm,
+        output_path: &str,
+        options: &ConvertOptions,
+    ) -> Result<(), AppError> {
+        let sortie = BufWriter::new(File::create(output_path)?);
+        let mut tampon = Vec::new();
+        if options.format == ConvertFormat::Flac {
+            let niveau = match options.quality {
+                ConvertQuality::Low => 0,
+                ConvertQuality::Standard => 5,
+                _ => 8,
+            };
+            let mut flac = EncodeurFlac::new(sortie, pcm.rate, pcm.channels, pcm.bits, niveau)?;
+            while pcm.lire(&mut tampon, TAILLE_BLOC)? > 0 {
+                flac.ecrire(&tampon)?;
+            }
+            flac.terminer()?;
+            return Ok(());
+        }
+
+        // WAV entier 16 ou 24 bits selon la résolution d'origine
+        let (format, largeur) = if pcm.bits <= 16 {
+            (RenderFormat::Pcm16, 16)
+        } else {
+            (RenderFormat::Pcm24, 24)
+        };
+        let decalage = largeur as i32 - pcm.bits as i32;
+        let mut sortie = sortie;
+        ecrire_entete(&mut sortie, format, pcm.channels, pcm.rate, 0)?;
+        let mut octets = 0u64;
+        while pcm.lire(&mut tampon, TAILLE_BLOC)? > 0 {
+            for &x in &tampon {
+                let x = if decalage >= 0 {
+                    x << decalage
+                } else {
+                    x >> -decalage
+                };
+                sortie.write_all(&x.to_le_bytes()[..largeur / 8])?;
+                octets += largeur as u64 / 8;
+            }
+        }
+        let taille = u32::try_from(octets)
+            .ok()
+            .filter(|t| *t < u32::MAX - 44)
+            .ok_or_else(|| AppError::Validation("WAV de plus de 4 Go".to_string()))?;
+        terminer_donnees(&mut sortie, taille)?;
+        sortie.seek(SeekFrom::Start(0))?;
+        ecrire_entete(&mut sortie, format, pcm.channels, pcm.rate, taille)?;
+        sortie.flush()?;
+        Ok(())
+    }
+
+    /// Applique un rognage proposé par l'analyse des silences, sans perte : copie des trames
+    /// pour un WAV, découpe à l'échantillon près et réencodage FLAC (sans perte) pour un FLAC
+    pub fn rogner(&self, proposition: &TrimProposal, output_path: &str) -> Result<(), String> {
+        let source = Path::new(&proposition.path);
+        let extension = source
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "wav" => rogner_wav(
+                source,
+                Path::new(output_path),
+                proposition.start_frame,
+                proposition.end_frame,
+            )
+            .map_err(|e| e.to_string()),
+            "flac" => Self::rogner_flac(
+                source,
+                Path::new(output_path),
+                proposition.start_frame,
+                proposition.end_frame,
+            )
+            .map_err(|e| e.to_string()),
+            _ => Err(format!(
+                "Rognage sans perte impossible pour le format {}",
+                extension
+            )),
+        }
+    }
+
+    /// Réencode les trames [debut, fin[ du FLAC puis recopie son tag (pochette comprise) tel quel
+    fn rogner_flac(source: &Path, dest: &Path, debut: u64, fin: u64) -> Result<(), AppError> {
+        let mut pcm = Pcm::ouvrir(source)?;
+        let channels = usize::from(pcm.channels);
+        let sortie = BufWriter::new(File::create(dest)?);
+        let mut flac = EncodeurFlac::new(sortie, pcm.rate, pcm.channels, pcm.bits, 8)?;
+        let (mut tampon, mut position) = (Vec::new(), 0u64);
+        while position < fin {
+            let lues = pcm.lire(&mut tampon, TAILLE_BLOC)? as u64;
+            if lues == 0 {
+                break;
+            }
+            let de = debut.clamp(position, position + lues) - position;
+            let a = fin.min(position + lues) - position;
+            if a > de {
+                flac.ecrire(&tampon[de as usize * channels..a as usize * channels])?;
+            }
+            position += lues;
+        }
+        flac.terminer()?;
+
+        let tagged_file = Probe::open(source)
+            .map_err(|e| AppError::Audio(e.to_string()))?
+            .read()
+            .map_err(|e| AppError::Audio(e.to_string()))?;
+        if let Some(tag) = tagged_file.primary_tag() {
+            tag.save_to_path(dest)
+                .map_err(|e| AppError::Audio(format!("{}: {}", dest.display(), e)))?;
         }
+        Ok(())
     }
 }
+
+fn champs_perdus(source: &Track, sortie: &Track) -> Vec<String> {
+    [
+        ("title", source.title == sortie.title),
+        ("artist", source.artist == sortie.artist),
+        ("album_artist", source.album_artist == sortie.album_artist),
+        ("album", source.album == sortie.album),
+        ("year", source.year == sortie.year),
+        ("track_number", source.track_number == sortie.track_number),
+        ("track_total", source.track_total == sortie.track_total),
+        ("disc_number", source.disc_number == sortie.disc_number),
+        ("genre", source.genre == sortie.genre),
+        ("compilation", source.compilation == sortie.compilation),
+    ]
+    .into_iter()
+    .filter(|(_, garde)| !garde)
+    .map(|(champ, _)| champ.to_string())
+    .collect()
+}
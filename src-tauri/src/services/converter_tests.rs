@@ -0,0 +1,211 @@
+use super::converter::ConverterService;
+use super::ffmpeg::FfmpegService;
+use super::integrity::IntegrityService;
+use super::pcm::Pcm;
+use super::test_fixtures::{dossier_temp, ecrire_wav};
+use super::AudioService;
+use crate::models::{ConvertFormat, ConvertOptions, ConvertQuality};
+use lofty::{MimeType, Picture, PictureType};
+use std::fs;
+use std::path::Path;
+
+fn lire_tout(path: &Path) -> (u32, Vec<i32>) {
+    let mut pcm = Pcm::ouvrir(path).unwrap();
+    let (mut tout, mut tampon) = (Vec::new(), Vec::new());
+    while pcm.lire(&mut tampon, 1000).unwrap() > 0 {
+        tout.extend_from_slice(&tampon);
+    }
+    (pcm.bits, tout)
+}
+
+// Sinusoïde bruitée (générateur congruentiel) suivie d'un silence : tous les types de sous-trames servent
+fn signal(trames: usize, channels: usize, amplitude: f32) -> Vec<i32> {
+    let mut graine = 12345u32;
+    (0..trames * channels)
+        .map(|i| {
+            let (t, c) = (i / channels, i % channels);
+            if t >= trames * 2 / 3 {
+                return 0;
+            }
+            graine = graine.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let bruit = (graine >> 16) as f32 / 65536.0 - 0.5;
+            let phase = t as f32 * (0.03 + c as f32 * 0.011);
+            ((phase.sin() * 0.8 + bruit * 0.05) * amplitude) as i32
+        })
+        .collect()
+}
+
+#[test]
+fn test_conversion_flac_et_wav_sans_perte() {
+    let dir = dossier_temp("convert");
+    let service = ConverterService::new();
+    let convertir = |source: &Path, dest: &Path, format, quality| {
+        let options = ConvertOptions {
+            format,
+            quality,
+            bitrate_kbps: None,
+        };
+        service
+            .convertir(&source.to_string_lossy(), &dest.to_string_lossy(), &options)
+            .unwrap()
+    };
+
+    // Stéréo 16 bits, dernier bloc incomplet
+    let stereo = dir.join("stereo.wav");
+    let echantillons = signal(15_000, 2, 30_000.0);
+    ecrire_wav(&stereo, 44100, 2, 16, &echantillons);
+    for quality in [ConvertQuality::Low, ConvertQuality::High] {
+        let flac = dir.join(format!("stereo_{:?}.flac", quality));
+        let resultat = convertir(&stereo, &flac, ConvertFormat::Flac, quality);
+        assert_eq!(resultat.encoder, "natif");
+        assert_eq!(lire_tout(&flac), (16, echantillons.clone()));
+        assert!(fs::metadata(&flac).unwrap().len() < fs::metadata(&stereo).unwrap().len());
+
+        let rapport = IntegrityService::verifier_fichier(&flac, "");
+        assert_eq!(rapport.md5_match, Some(true));
+        assert_eq!(rapport.expected_frames, Some(15_000));
+        assert!(rapport.problems.is_empty());
+    }
+
+    // Mono 24 bits : la résolution est conservée jusqu'au WAV reconstruit
+    let mono = dir.join("mono.wav");
+    let echantillons = signal(5_000, 1, 8_000_000.0);
+    ecrire_wav(&mono, 44100, 1, 24, &echantillons);
+    let flac = dir.join("mono.flac");
+    convertir(&mono, &flac, ConvertFormat::Flac, ConvertQuality::Standard);
+    assert_eq!(lire_tout(&flac), (24, echantillons.clone()));
+    let wav = dir.join("mono (converted).wav");
+    convertir(&flac, &wav, ConvertFormat::Wav, ConvertQuality::Standard);
+    assert_eq!(lire_tout(&wav), (24, echantillons));
+
+    // Entier 32 bits : au-delà de l'encodeur natif, la conversion passe par ffmpeg
+    let large = dir.join("large.wav");
+    ecrire_wav(&large, 44100, 1, 32, &signal(1_000, 1, 2.0e9));
+    for (format, extension) in [(ConvertFormat::Flac, "flac"), (ConvertFormat::Wav, "wav")] {
+        let options = ConvertOptions {
+            format,
+            quality: ConvertQuality::Standard,
+            bitrate_kbps: None,
+        };
+        let sortie = dir.join(format!("large (converted).{}", extension));
+        match service.convertir(
+            &large.to_string_lossy(),
+            &sortie.to_string_lossy(),
+            &options,
+        ) {
+            Ok(resultat) => assert!(resultat.encoder.starts_with("ffmpeg")),
+            Err(e) => assert!(e.contains("ffmpeg"), "{}", e),
+        }
+    }
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_tags_et_pochette_reportes() {
+    let dir = dossier_temp("convert_tags");
+    let service = ConverterService::new();
+    let options = |format| ConvertOptions {
+        format,
+        ..Default::default()
+    };
+    let wav = dir.join("source.wav");
+    ecrire_wav(&wav, 44100, 2, 16, &signal(3_000, 2, 10_000.0));
+    let flac = dir.join("source.flac");
+    let s = |p: &Path| p.to_string_lossy().to_string();
+
+    // Source sans tag : rien à perdre
+    let resultat = service
+        .convertir(&s(&wav), &s(&flac), &options(ConvertFormat::Flac))
+        .unwrap();
+    assert!(
+        resultat.lost_fields.is_empty(),
+        "{:?}",
+        resultat.lost_fields
+    );
+    assert_eq!(resultat.tag_error, None);
+
+    let audio = AudioService::new();
+    let mut track = audio.lire_metadonnees(&s(&flac)).unwrap();
+    track.title = "Titre".to_string();
+    track.artist = "Artiste".to_string();
+    track.album_artist = "Artiste d'album".to_string();
+    track.album = "Album".to_string();
+    track.year = Some(1999);
+    track.track_number = Some(3);
+    track.track_total = Some(12);
+    track.disc_number = Some(2);
+    track.genre = Some("Jazz".to_string());
+    track.compilation = true;
+    audio.ecrire_metadonnees(&track).unwrap();
+    let image = vec![0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3, 4];
+    let picture = Picture::new_unchecked(PictureType::Other, MimeType::Jpeg, None, image.clone());
+    audio.poser_cover(&s(&flac), picture).unwrap();
+
+    // Chaque champ et la pochette avant se retrouvent dans la copie
+    let copie = dir.join("copie.flac");
+    let resultat = service
+        .convertir(&s(&flac), &s(&copie), &options(ConvertFormat::Flac))
+        .unwrap();
+    assert!(
+        resultat.lost_fields.is_empty(),
+        "{:?}",
+        resultat.lost_fields
+    );
+    let relu = audio.lire_metadonnees(&s(&copie)).unwrap();
+    assert_eq!(relu.album_artist, "Artiste d'album");
+    assert_eq!((relu.track_total, relu.disc_number), (Some(12), Some(2)));
+    assert!(relu.compilation);
+    let cover = audio.lire_cover(&s(&copie)).unwrap().unwrap();
+    assert_eq!(cover.pic_type(), PictureType::CoverFront);
+    assert_eq!(cover.data(), image);
+
+    // Le rapport reste cohérent avec une relecture du WAV produit
+    let sortie = dir.join("copie.wav");
+    let resultat = service
+        .convertir(&s(&flac), &s(&sortie), &options(ConvertFormat::Wav))
+        .unwrap();
+    let relu = audio.lire_metadonnees(&s(&sortie)).unwrap();
+    assert_eq!(
+        resultat.lost_fields.contains(&"album".to_string()),
+        relu.album != "Album"
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_arguments_ffmpeg() {
+    let options = |format, quality, bitrate_kbps| ConvertOptions {
+        format,
+        quality,
+        bitrate_kbps,
+    };
+    assert_eq!(
+        FfmpegService::arguments_codec(&options(
+            ConvertFormat::Mp3,
+            ConvertQuality::Standard,
+            None
+        )),
+        ["-codec:a", "libmp3lame", "-q:a", "2"]
+    );
+    assert_eq!(
+        FfmpegService::arguments_codec(&options(
+            ConvertFormat::Mp3,
+            ConvertQuality::Low,
+            Some(160)
+        )),
+        ["-codec:a", "libmp3lame", "-b:a", "160k"]
+    );
+    assert_eq!(
+        FfmpegService::arguments_codec(&options(ConvertFormat::Opus, ConvertQuality::High, None)),
+        ["-codec:a", "libopus", "-b:a", "192k"]
+    );
+
+    // Sans ffmpeg, l'erreur le dit au lieu d'un échec muet
+    let info = FfmpegService::detecter();
+    if !info.available {
+        let erreur = FfmpegService::executer(&info, "a.flac", &[], "a.mp3").unwrap_err();
+        assert!(erreur.contains("ffmpeg"));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::models::{AppError, EqBand, EqFilterType, EqSettings, EQ_MAX_BANDS};
+use std::fs;
+use std::path::Path;
+
+// Q d'une bande en tiers d'octave, pour approcher une courbe GraphicEQ
+const Q_TIERS_OCTAVE: f32 = 4.32;
+// Q par défaut des filtres en plateau EqualizerAPO sans Q explicite
+const Q_PLATEAU: f32 = 0.707;
+
+/// Import des fichiers texte EqualizerAPO / AutoEq (`Preamp:`, `Filter:`, `GraphicEQ:`)
+pub struct EqImportService;
+
+impl EqImportService {
+    pub fn lire(path: &Path) -> Result<EqSettings, AppError> {
+        Self::parser(&fs::read_to_string(path)?)
+    }
+
+    pub fn parser(texte: &str) -> Result<EqSettings, AppError> {
+        let mut settings = EqSettings::default();
+        for ligne in texte.lines().map(str::trim) {
+            let Some((cle, valeur)) = ligne.split_once(':') else {
+                continue;
+            };
+            let cle = cle.trim().to_lowercase();
+            if cle == "preamp" {
+                settings.preamp_db =
+                    nombre(valeur.split_whitespace().next()).ok_or_else(|| erreur(ligne))?;
+            } else if cle == "filter" || cle.starts_with("filter ") {
+                if let Some(band) = Self::parser_filtre(valeur) {
+                    settings.bands.push(band);
+                }
+            } else if cle == "graphiceq" {
+                let points = Self::parser_points(valeur).ok_or_else(|| erreur(ligne))?;
+                settings.bands.extend(Self::approcher_courbe(&points));
+            }
+        }
+
+        if settings.bands.is_empty() {
+            return Err(AppError::Validation(
+                "Aucun filtre EqualizerAPO ou GraphicEQ reconnu dans le fichier".to_string(),
+            ));
+        }
+        if settings.bands.len() > EQ_MAX_BANDS {
+            return Err(AppError::Validation(format!(
+                "{} filtres dans le fichier, l'égaliseur est limité à {} bandes",
+                settings.bands.len(),
+                EQ_MAX_BANDS
+            )));
+        }
+        Ok(settings)
+    }
+
+    /// "ON PK Fc 105 Hz Gain -3.1 dB Q 0.70" ; les types inconnus sont ignorés
+    fn parser_filtre(valeur: &str) -> Option<EqBand> {
+        let mots: Vec<&str> = valeur.split_whitespace().collect();
+        let (etat, type_filtre) = (mots.first()?, mots.get(1)?);
+        let filter_type = match type_filtre.to_uppercase().as_str() {
+            "PK" | "PEQ" | "MODAL" => EqFilterType::Peaking,
+            "LS" | "LSC" => EqFilterType::LowShelf,
+            "HS" | "HSC" => EqFilterType::HighShelf,
+            "LP" | "LPQ" => EqFilterType::LowPass,
+            "HP" | "HPQ" => EqFilterType::HighPass,
+            _ => return None,
+        };
+        let parametre = |nom: &str| {
+            let i = mots.iter().position(|m| m.eq_ignore_ascii_case(nom))?;
+            nombre(mots.get(i + 1).copied())
+        };
+        let q_defaut = match filter_type {
+            EqFilterType::Peaking => 1.0,
+            _ => Q_PLATEAU,
+        };
+        Some(EqBand {
+            filter_type,
+            frequency_hz: parametre("Fc")?,
+            gain_db: parametre("Gain").unwrap_or(0.0),
+            q: parametre("Q").unwrap_or(q_defaut),
+            enabled: etat.eq_ignore_ascii_case("ON"),
+        })
+    }
+
+    /// "20 -7.9; 21 -7.9; ..." : couples fréquence / gain triés par fréquence
+    fn parser_points(valeur: &str) -> Option<Vec<(f32, f32)>> {
+        let mut points = valeur
+            .split(';')
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| {
+                let mut it = p.split_whitespace();
+                Some((nombre(it.next())?, nombre(it.next())?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        (!points.is_empty()).then_some(points)
+    }
+
+    /// Une bande en cloche par tiers d'octave (20 Hz - 20 kHz), au gain interpolé de la courbe
+    fn approcher_courbe(points: &[(f32, f32)]) -> Vec<EqBand> {
+        (-17..=13)
+            .map(|k| 1000.0 * 2f32.powf(k as f32 / 3.0))
+            .map(|freq| {
+                let gain = interpoler(points, freq);
+                EqBand::new(EqFilterType::Peaking, freq.round(), gain, Q_TIERS_OCTAVE)
+            })
+            .filter(|b| b.gain_db.abs() >= 0.05)
+            .collect()
+    }
+}
+
+/// Interpolation linéaire sur une échelle de fréquence logarithmique
+fn interpoler(points: &[(f32, f32)], freq: f32) -> f32 {
+    let apres = points.partition_point(|p| p.0 < freq);
+    if apres == 0 {
+        return points[0].1;
+    }
+    if apres == points.len() {
+        return points[points.len() - 1].1;
+    }
+    let ((f0, g0), (f1, g1)) = (points[apres - 1], points[apres]);
+    if f1 <= f0 || f0 <= 0.0 {
+        return g1;
+    }
+    let t = (freq / f0).ln() / (f1 / f0).ln();
+    g0 + (g1 - g0) * t
+}
+
+fn nombre(mot: Option<&str>) -> Option<f32> {
+    mot?.replace(',', ".").parse().ok()
+}
+
+fn erreur(ligne: &str) -> AppError {
+    AppError::Validation(format!("Ligne illisible : {}", ligne))
+}
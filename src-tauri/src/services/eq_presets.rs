@@ -0,0 +1,129 @@
+use super::SettingsService;
+use crate::db::Database;
+use crate::models::{AppError, EqPreset, EqSettings, EQ_MAX_BANDS};
+use rusqlite::params;
+use std::sync::MutexGuard;
+
+const SETTING_EQUALIZER: &str = "player.equalizer";
+
+/// Égaliseur courant (table settings) et préréglages nommés (table eq_presets)
+pub struct EqPresetService;
+
+impl EqPresetService {
+    pub fn charger(db: &Database) -> Result<EqSettings, AppError> {
+        Ok(SettingsService::lire(db, SETTING_EQUALIZER)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn enregistrer(db: &Database, settings: &EqSettings) -> Result<(), AppError> {
+        Self::valider(settings)?;
+        SettingsService::ecrire(db, SETTING_EQUALIZER, &vers_json(settings)?)
+    }
+
+    pub fn valider(settings: &EqSettings) -> Result<(), AppError> {
+        if settings.bands.len() > EQ_MAX_BANDS {
+            return Err(AppError::Validation(format!(
+                "Un égaliseur est limité à {} bandes",
+                EQ_MAX_BANDS
+            )));
+        }
+        if !settings.preamp_db.is_finite() || settings.preamp_db.abs() > 30.0 {
+            return Err(AppError::Validation(
+                "Le préampli doit être compris entre -30 et +30 dB".to_string(),
+            ));
+        }
+        for (i, band) in settings.bands.iter().enumerate() {
+            if !(band.frequency_hz > 0.0 && band.frequency_hz <= 24000.0) {
+                return Err(AppError::Validation(format!(
+                    "Bande {} : fréquence hors de 0 - 24000 Hz",
+                    i + 1
+                )));
+            }
+            if !(band.q > 0.0 && band.q <= 30.0) {
+                return Err(AppError::Validation(format!(
+                    "Bande {} : Q hors de 0 - 30",
+                    i + 1
+                )));
+            }
+            if !band.gain_db.is_finite() || band.gain_db.abs() > 30.0 {
+                return Err(AppError::Validation(format!(
+                    "Bande {} : gain hors de -30 / +30 dB",
+                    i + 1
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn lister(db: &Database) -> Result<Vec<EqPreset>, AppError> {
+        let conn = verrouiller(db)?;
+        let mut stmt = conn.prepare("SELECT id, name, settings FROM eq_presets ORDER BY name")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut presets = Vec::new();
+        for row in rows {
+            let (id, name, json) = row?;
+            // Un préréglage illisible (ancien format) est ignoré plutôt que de bloquer la liste
+            if let Ok(settings) = serde_json::from_str(&json) {
+                presets.push(EqPreset {
+                    id: Some(id),
+                    name,
+                    settings,
+                });
+            }
+        }
+        Ok(presets)
+    }
+
+    /// Crée le préréglage, ou remplace celui qui porte déjà ce nom
+    pub fn sauvegarder(
+        db: &Database,
+        name: &str,
+        settings: EqSettings,
+    ) -> Result<EqPreset, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation(
+                "Un préréglage doit avoir un nom".to_string(),
+            ));
+        }
+        Self::valider(&settings)?;
+
+        let conn = verrouiller(db)?;
+        conn.execute(
+            "INSERT INTO eq_presets (name, settings) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET settings = excluded.settings",
+            params![name, vers_json(&settings)?],
+        )?;
+        let id = conn.query_row("SELECT id FROM eq_presets WHERE name = ?1", [name], |row| {
+            row.get(0)
+        })?;
+        Ok(EqPreset {
+            id: Some(id),
+            name: name.to_string(),
+            settings,
+        })
+    }
+
+    pub fn supprimer(db: &Database, id: i64) -> Result<(), AppError> {
+        verrouiller(db)?.execute("DELETE FROM eq_presets WHERE id = ?1", [id])?;
+        Ok(())
+    }
+}
+
+fn vers_json(settings: &EqSettings) -> Result<String, AppError> {
+    serde_json::to_string(settings).map_err(|e| AppError::Unknown(e.to_string()))
+}
+
+fn verrouiller(db: &Database) -> Result<MutexGuard<'_, rusqlite::Connection>, AppError> {
+    db.conn
+        .lock()
+        .map_err(|_| AppError::DatabaseError("Lock error".into()))
+}
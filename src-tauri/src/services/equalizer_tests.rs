@@ -0,0 +1,95 @@
+use super::biquad::Biquad;
+use super::eq_import::EqImportService;
+use super::eq_presets::EqPresetService;
+use super::equalizer::{courbe, gain_global_db};
+use crate::db::Database;
+use crate::models::{EqBand, EqFilterType, EqSettings};
+
+const AUTOEQ: &str = "Preamp: -6.4 dB
+Filter 1: ON LSC Fc 105 Hz Gain 6.0 dB Q 0.70
+Filter 2: ON PK Fc 2990 Hz Gain -3.5 dB Q 1.94
+Filter 3: OFF HSC Fc 10000 Hz Gain 2.0 dB Q 0.70
+Filter 4: ON XX Fc 500 Hz Gain 1.0 dB Q 1.00
+";
+
+#[test]
+fn test_import_filtres_equalizer_apo() {
+    let settings = EqImportService::parser(AUTOEQ).unwrap();
+    assert!((settings.preamp_db + 6.4).abs() < 1e-4);
+    // Le type inconnu est ignoré, le filtre OFF est gardé désactivé
+    assert_eq!(settings.bands.len(), 3);
+    assert_eq!(settings.bands[0].filter_type, EqFilterType::LowShelf);
+    assert_eq!(settings.bands[1].frequency_hz, 2990.0);
+    assert_eq!(settings.bands[1].q, 1.94);
+    assert!(!settings.bands[2].enabled);
+
+    assert!(EqImportService::parser("rien d'utile").is_err());
+}
+
+#[test]
+fn test_import_graphic_eq() {
+    let settings = EqImportService::parser("GraphicEQ: 20 0; 100 0; 1000 6; 20000 6").unwrap();
+    // Bandes en tiers d'octave : rien sous 100 Hz, +6 dB au-delà de 1 kHz, interpolé entre les deux
+    let gain_a = |freq: f32| {
+        settings
+            .bands
+            .iter()
+            .find(|b| b.frequency_hz == freq)
+            .map(|b| b.gain_db)
+    };
+    assert_eq!(gain_a(50.0), None);
+    assert!((gain_a(2000.0).unwrap() - 6.0).abs() < 1e-3);
+    assert!((gain_a(315.0).unwrap() - 3.0).abs() < 0.1);
+}
+
+#[test]
+fn test_reponse_et_anti_saturation() {
+    let band = EqBand::new(EqFilterType::Peaking, 1000.0, 6.0, 1.0);
+    let mut filtre = Biquad::new();
+    filtre.configurer(&band, 48000);
+    assert!((filtre.reponse_db(1000.0, 48000) - 6.0).abs() < 0.05);
+    assert!(filtre.reponse_db(50.0, 48000).abs() < 0.1);
+
+    let settings = EqSettings {
+        preamp_db: -2.0,
+        bands: vec![band],
+        ..EqSettings::default()
+    };
+    // Courbe maximale à +4 dB : le préampli effectif la ramène à 0 dB
+    let maximum = courbe(&settings)
+        .iter()
+        .map(|p| p.1)
+        .fold(f32::MIN, f32::max);
+    assert!((maximum - 4.0).abs() < 0.1);
+    assert!((gain_global_db(&settings) + 6.0).abs() < 0.1);
+    let sans_auto = EqSettings {
+        auto_preamp: false,
+        ..settings
+    };
+    assert_eq!(gain_global_db(&sans_auto), -2.0);
+}
+
+#[test]
+fn test_prereglages_en_base() {
+    let db = Database::open(":memory:").unwrap();
+    let settings = EqImportService::parser(AUTOEQ).unwrap();
+    let preset = EqPresetService::sauvegarder(&db, "Casque", settings.clone()).unwrap();
+    // Même nom : le préréglage est remplacé, pas dupliqué
+    let plat = EqSettings::default();
+    EqPresetService::sauvegarder(&db, "Casque", plat.clone()).unwrap();
+    let presets = EqPresetService::lister(&db).unwrap();
+    assert_eq!(presets.len(), 1);
+    assert_eq!(presets[0].id, preset.id);
+    assert_eq!(presets[0].settings, plat);
+
+    EqPresetService::enregistrer(&db, &settings).unwrap();
+    assert_eq!(EqPresetService::charger(&db).unwrap(), settings);
+
+    let trop = EqSettings {
+        bands: vec![EqBand::new(EqFilterType::Peaking, 0.0, 1.0, 1.0)],
+        ..EqSettings::default()
+    };
+    assert!(EqPresetService::sauvegarder(&db, "Invalide", trop).is_err());
+    EqPresetService::supprimer(&db, preset.id.unwrap()).unwrap();
+    assert!(EqPresetService::lister(&db).unwrap().is_empty());
+}
@@ -0,0 +1,191 @@
+use crate::models::{ConvertFormat, ConvertOptions, ConvertQuality, FfmpegInfo};
+use std::io::Read;
+use std::process::{Command, ExitStatus, Stdio};
+
+// Une application lancée hors d'un terminal n'hérite pas toujours du PATH de l'utilisateur
+const EMPLACEMENTS: [&str; 4] = [
+    "/opt/homebrew/bin/ffmpeg",
+    "/usr/local/bin/ffmpeg",
+    "/usr/bin/ffmpeg",
+    "C:\\ffmpeg\\bin\\ffmpeg.exe",
+];
+// Lignes de stderr reprises dans le message d'erreur : la fin explique l'échec
+const LIGNES_ERREUR: usize = 8;
+// Trames lues à la fois sur la sortie du décodage
+const TRAMES_PAR_LECTURE: usize = 4096;
+const INTROUVABLE: &str = "ffmpeg introuvable : installez-le ou ajoutez-le au PATH";
+
+/// Détection de ffmpeg et encodage des formats sans encodeur natif (MP3, Opus, AAC)
+pub struct FfmpegService;
+
+impl FfmpegService {
+    pub fn detecter() -> FfmpegInfo {
+        std::iter::once("ffmpeg")
+            .chain(EMPLACEMENTS)
+            .find_map(|path| {
+                let sortie = Command::new(path).arg("-version").output().ok()?;
+                if !sortie.status.success() {
+                    return None;
+                }
+                // "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) ..."
+                let texte = String::from_utf8_lossy(&sortie.stdout);
+                let version = texte
+                    .lines()
+                    .next()
+                    .and_then(|l| l.strip_prefix("ffmpeg version "))
+                    .and_then(|l| l.split_whitespace().next())
+                    .map(str::to_string);
+                Some(FfmpegInfo {
+                    available: true,
+                    path: Some(path.to_string()),
+                    version,
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Encode `input` vers `output` ; tags et pochette sont reportés ensuite par le convertisseur
+    pub fn encoder(
+        info: &FfmpegInfo,
+        input: &str,
+        output: &str,
+        options: &ConvertOptions,
+    ) -> Result<(), String> {
+        let mut args: Vec<String> = ["-map", "0:a:0", "-map_metadata", "0"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        args.extend(Self::arguments_codec(options));
+        Self::executer(info, input, &args, output)
+    }
+
+    /// Arguments du codec selon le format et le préréglage (un débit imposé prime sur le préréglage)
+    pub fn arguments_codec(options: &ConvertOptions) -> Vec<String> {
+        use ConvertQuality::*;
+        let debit = |preset: [u32; 4]| {
+            let kbps = options.bitrate_kbps.unwrap_or(match options.quality {
+                Low => preset[0],
+                Standard => preset[1],
+                High => preset[2],
+                Extreme => preset[3],
+            });
+            vec!["-b:a".to_string(), format!("{}k", kbps)]
+        };
+        let (codec, reglage) = match options.format {
+            ConvertFormat::Mp3 => {
+                let reglage = match (options.bitrate_kbps, options.quality) {
+                    (Some(_), _) | (None, Extreme) => debit([0, 0, 0, 320]),
+                    // VBR LAME : V6, V2, V0
+                    (None, q) => vec![
+                        "-q:a".to_string(),
+                        (match q {
+                            Low => "6",
+                            Standard => "2",
+                            _ => "0",
+                        })
+                        .to_string(),
+                    ],
+                };
+                ("libmp3lame", reglage)
+            }
+            ConvertFormat::Opus => ("libopus", debit([64, 128, 192, 256])),
+            ConvertFormat::Aac => ("aac", debit([128, 192, 256, 320])),
+            ConvertFormat::Flac => ("flac", vec![]),
+            ConvertFormat::Wav => ("pcm_s16le", vec![]),
+        };
+        let mut args = vec!["-codec:a".to_string(), codec.to_string()];
+        args.extend(reglage);
+        args
+    }
+
+    /// Lance ffmpeg ; en cas d'échec, l'erreur reprend la fin de sa sortie d'erreur
+    pub fn executer(
+        info: &FfmpegInfo,
+        input: &str,
+        args: &[String],
+        output: &str,
+    ) -> Result<(), String> {
+        let Some(path) = &info.path else {
+            return Err(INTROUVABLE.to_string());
+        };
+        let sortie = Command::new(path)
+            .args(["-hide_banner", "-nostdin", "-i", input])
+            .args(args)
+            .arg("-y") // Overwrite
+            .arg(output)
+            .output()
+            .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
+        if sortie.status.success() {
+            return Ok(());
+        }
+        Err(echec(sortie.status, &sortie.stderr))
+    }
+
+    /// Décode `input` en flottants entrelacés (`canaux` canaux à `taux` Hz), transmis à `recevoir`
+    /// par trames entières au fil du décodage : la piste n'est jamais chargée en entier
+    pub fn decoder(
+        info: &FfmpegInfo,
+        input: &str,
+        canaux: u16,
+        taux: u32,
+        mut recevoir: impl FnMut(&[f32]),
+    ) -> Result<(), String> {
+        let Some(path) = &info.path else {
+            return Err(INTROUVABLE.to_string());
+        };
+        let mut processus = Command::new(path)
+            .args(["-hide_banner", "-nostdin", "-v", "error", "-i", input])
+            .args(["-map", "0:a:0", "-f", "f32le"])
+            .args(["-ac", &canaux.to_string(), "-ar", &taux.to_string()])
+            .arg("pipe:1")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
+
+        let mut stdout = processus
+            .stdout
+            .take()
+            .ok_or("Sortie de ffmpeg indisponible")?;
+        let trame = 4 * usize::from(canaux.max(1));
+        let mut octets = vec![0u8; trame * TRAMES_PAR_LECTURE];
+        let mut echantillons = Vec::with_capacity(octets.len() / 4);
+        let mut reste = 0;
+        loop {
+            let lus = stdout
+                .read(&mut octets[reste..])
+                .map_err(|e| format!("Lecture de la sortie de ffmpeg: {}", e))?;
+            if lus == 0 {
+                break;
+            }
+            // Une lecture peut couper une trame : la fin incomplète attend la lecture suivante
+            let total = reste + lus;
+            let complets = total - total % trame;
+            echantillons.clear();
+            echantillons.extend(
+                octets[..complets]
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            );
+            recevoir(&echantillons);
+            octets.copy_within(complets..total, 0);
+            reste = total - complets;
+        }
+
+        let sortie = processus
+            .wait_with_output()
+            .map_err(|e| format!("Failed to execute ffmpeg: {}", e))?;
+        if sortie.status.success() {
+            return Ok(());
+        }
+        Err(echec(sortie.status, &sortie.stderr))
+    }
+}
+
+/// Message d'erreur limité aux dernières lignes de stderr
+fn echec(status: ExitStatus, stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let lignes: Vec<&str> = stderr.lines().filter(|l| !l.trim().is_empty()).collect();
+    let fin = lignes[lignes.len().saturating_sub(LIGNES_ERREUR)..].join("\n");
+    format!("ffmpeg a échoué ({}) :\n{}", status, fin)
+}
@@ -0,0 +1,217 @@
+use super::flac_subframe::{analyser, crc16, crc8, ecrire_sous_trame, Bits, SousTrame};
+use crate::models::AppError;
+use std::io::{Seek, SeekFrom, Write};
+
+// Taille de bloc de libFLAC aux fréquences courantes
+pub const TAILLE_BLOC: usize = 4096;
+const VENDEUR: &[u8] = b"tagotomatik";
+const REMPLISSAGE: usize = 8192;
+
+/// Résolutions et nombres de canaux que l'encodeur sait écrire
+pub fn format_accepte(bits: u32, channels: u16) -> bool {
+    [8, 12, 16, 20, 24].contains(&bits) && (1..=8).contains(&channels)
+}
+
+/// Encodeur FLAC sans dépendance : prédicteurs fixes, codage de Rice partitionné et stéréo décorrélée
+pub struct EncodeurFlac<W: Write + Seek> {
+    sortie: W,
+    rate: u32,
+    channels: usize,
+    bits: u32,
+    ordre_max: usize,
+    partition_max: u32,
+    attente: Vec<i32>,
+    numero: u64,
+    trames: u64,
+    dernier_bloc: usize,
+    taille_trame: (u32, u32),
+    md5: md5::Context,
+}
+
+impl<W: Write + Seek> EncodeurFlac<W> {
+    /// `niveau` : 0 (rapide) à 8 (plus compact), comme l'option -0 ... -8 de flac
+    pub fn new(
+        mut sortie: W,
+        rate: u32,
+        channels: u16,
+        bits: u32,
+        niveau: u8,
+    ) -> Result<Self, AppError> {
+        if !format_accepte(bits, channels) {
+            return Err(AppError::Validation(format!(
+                "FLAC : {} bits / {} canaux non pris en charge",
+                bits, channels
+            )));
+        }
+        // Bloc STREAMINFO provisoire, complété par `terminer`
+        sortie.write_all(b"fLaC")?;
+        sortie.write_all(&[0, 0, 0, 34])?;
+        sortie.write_all(&[0u8; 34])?;
+        // Commentaires Vorbis vides puis remplissage, comme libFLAC : les tags et la pochette
+        // s'écrivent ensuite sans décaler l'audio (et lofty exige un bloc PADDING)
+        sortie.write_all(&[4, 0, 0, (8 + VENDEUR.len()) as u8])?;
+        sortie.write_all(&(VENDEUR.len() as u32).to_le_bytes())?;
+        sortie.write_all(VENDEUR)?;
+        sortie.write_all(&0u32.to_le_bytes())?;
+        sortie.write_all(&[0x81, 0, (REMPLISSAGE >> 8) as u8, 0])?;
+        sortie.write_all(&[0u8; REMPLISSAGE])?;
+        Ok(Self {
+            sortie,
+            rate,
+            channels: usize::from(channels),
+            bits,
+            ordre_max: if niveau < 3 { 2 } else { 4 },
+            partition_max: u32::from(niveau.min(8)),
+            attente: Vec::with_capacity(TAILLE_BLOC * usize::from(channels)),
+            numero: 0,
+            trames: 0,
+            dernier_bloc: 0,
+            taille_trame: (u32::MAX, 0),
+            md5: md5::Context::new(),
+        })
+    }
+
+    /// Échantillons entrelacés, à la résolution annoncée
+    pub fn ecrire(&mut self, echantillons: &[i32]) -> Result<(), AppError> {
+        let largeur = self.bits.div_ceil(8) as usize;
+        for &x in echantillons {
+            self.md5.consume(&x.to_le_bytes()[..largeur]);
+            self.attente.push(x);
+            if self.attente.len() == TAILLE_BLOC * self.channels {
+                self.encoder_bloc()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode le dernier bloc incomplet et écrit le STREAMINFO définitif
+    pub fn terminer(mut self) -> Result<W, AppError> {
+        if !self.attente.is_empty() {
+            self.encoder_bloc()?;
+        }
+        let bloc = if self.numero <= 1 {
+            self.dernier_bloc
+        } else {
+            TAILLE_BLOC
+        } as u64;
+        let mut b = Bits::default();
+        b.ecrire(bloc, 16);
+        b.ecrire(bloc, 16);
+        b.ecrire(u64::from(self.taille_trame.0.min(self.taille_trame.1)), 24);
+        b.ecrire(u64::from(self.taille_trame.1), 24);
+        b.ecrire(u64::from(self.rate), 20);
+        b.ecrire(self.channels as u64 - 1, 3);
+        b.ecrire(u64::from(self.bits - 1), 5);
+        b.ecrire(self.trames >> 32, 4);
+        b.ecrire(self.trames, 32);
+        b.octets.extend_from_slice(&self.md5.finalize().0);
+        self.sortie.seek(SeekFrom::Start(8))?;
+        self.sortie.write_all(&b.octets)?;
+        self.sortie.seek(SeekFrom::End(0))?;
+        self.sortie.flush()?;
+        Ok(self.sortie)
+    }
+
+    fn encoder_bloc(&mut self) -> Result<(), AppError> {
+        let n = self.attente.len() / self.channels;
+        let mut canaux: Vec<Vec<i64>> = (0..self.channels)
+            .map(|c| {
+                (0..n)
+                    .map(|i| i64::from(self.attente[i * self.channels + c]))
+                    .collect()
+            })
+            .collect();
+        self.attente.clear();
+        let (ordre, partition) = (self.ordre_max, self.partition_max);
+        let bps = self.bits;
+
+        let mut assignation = self.channels as u64 - 1;
+        let choix: Vec<(SousTrame, u32)> = if self.channels == 2 {
+            // Gauche/droite, gauche/côté, côté/droite ou milieu/côté : le plus compact l'emporte
+            let (g, d) = (&canaux[0], &canaux[1]);
+            let cote: Vec<i64> = g.iter().zip(d).map(|(a, b)| a - b).collect();
+            let milieu: Vec<i64> = g.iter().zip(d).map(|(a, b)| (a + b) >> 1).collect();
+            let cg = analyser(g, bps, ordre, partition);
+            let cd = analyser(d, bps, ordre, partition);
+            let cc = analyser(&cote, bps + 1, ordre, partition);
+            let cm = analyser(&milieu, bps, ordre, partition);
+            let options = [
+                (cg.1 + cd.1, 1u64),
+                (cg.1 + cc.1, 8),
+                (cc.1 + cd.1, 9),
+                (cm.1 + cc.1, 10),
+            ];
+            let meilleur = options.iter().min_by_key(|o| o.0).map_or(1, |o| o.1);
+            let (a, b) = match meilleur {
+                8 => ((cg.0, bps, g.clone()), (cc.0, bps + 1, cote)),
+                9 => ((cc.0, bps + 1, cote), (cd.0, bps, d.clone())),
+                10 => ((cm.0, bps, milieu), (cc.0, bps + 1, cote)),
+                _ => ((cg.0, bps, g.clone()), (cd.0, bps, d.clone())),
+            };
+            assignation = meilleur;
+            canaux = vec![a.2, b.2];
+            vec![(a.0, a.1), (b.0, b.1)]
+        } else {
+            canaux
+                .iter()
+                .map(|x| (analyser(x, bps, ordre, partition).0, bps))
+                .collect()
+        };
+
+        let mut b = Bits::default();
+        b.ecrire(0xFFF8, 16); // Synchronisation, blocs de taille fixe
+        b.ecrire(0b0111, 4); // Taille du bloc sur 16 bits en fin d'en-tête
+        b.ecrire(code_frequence(self.rate), 4);
+        b.ecrire(assignation, 4);
+        b.ecrire(code_resolution(self.bits), 3);
+        b.ecrire(0, 1);
+        b.utf8(self.numero);
+        b.ecrire(n as u64 - 1, 16);
+        let crc = crc8(&b.octets);
+        b.ecrire(u64::from(crc), 8);
+        for (x, (sous_trame, bps)) in canaux.iter().zip(&choix) {
+            ecrire_sous_trame(&mut b, x, *bps, sous_trame);
+        }
+        b.aligner();
+        let crc = crc16(&b.octets);
+        b.ecrire(u64::from(crc), 16);
+
+        self.sortie.write_all(&b.octets)?;
+        let taille = b.octets.len() as u32;
+        self.taille_trame = (
+            self.taille_trame.0.min(taille),
+            self.taille_trame.1.max(taille),
+        );
+        self.numero += 1;
+        self.trames += n as u64;
+        self.dernier_bloc = n;
+        Ok(())
+    }
+}
+
+fn code_frequence(rate: u32) -> u64 {
+    match rate {
+        88_200 => 1,
+        176_400 => 2,
+        192_000 => 3,
+        8_000 => 4,
+        16_000 => 5,
+        22_050 => 6,
+        24_000 => 7,
+        32_000 => 8,
+        44_100 => 9,
+        48_000 => 10,
+        96_000 => 11,
+        _ => 0, // Lue dans STREAMINFO
+    }
+}
+
+fn code_resolution(bits: u32) -> u64 {
+    match bits {
+        8 => 1,
+        12 => 2,
+        16 => 4,
+        20 => 5,
+        _ => 6,
+    }
+}
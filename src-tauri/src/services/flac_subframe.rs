@@ -0,0 +1,217 @@
+// 15 signale un échappement, que l'encodeur n'utilise pas
+const PARAMETRE_RICE_MAX: u32 = 14;
+
+/// Flux de bits MSB d'abord, comme le veut le format FLAC
+#[derive(Default)]
+pub(super) struct Bits {
+    pub octets: Vec<u8>,
+    acc: u64,
+    n: u32,
+}
+
+impl Bits {
+    pub fn ecrire(&mut self, valeur: u64, bits: u32) {
+        if bits == 0 {
+            return;
+        }
+        self.acc = (self.acc << bits) | (valeur & ((1u64 << bits) - 1));
+        self.n += bits;
+        while self.n >= 8 {
+            self.n -= 8;
+            self.octets.push((self.acc >> self.n) as u8);
+        }
+        self.acc &= (1u64 << self.n) - 1;
+    }
+
+    pub fn unaire(&mut self, mut zeros: u64) {
+        while zeros >= 32 {
+            self.ecrire(0, 32);
+            zeros -= 32;
+        }
+        self.ecrire(1, zeros as u32 + 1);
+    }
+
+    pub fn aligner(&mut self) {
+        if self.n > 0 {
+            self.ecrire(0, 8 - self.n);
+        }
+    }
+
+    /// Numéro de trame codé à la manière d'UTF-8 (jusqu'à 36 bits)
+    pub fn utf8(&mut self, v: u64) {
+        if v < 0x80 {
+            return self.ecrire(v, 8);
+        }
+        let mut n = 2;
+        while v >= 1 << (5 * n + 1) {
+            n += 1;
+        }
+        self.ecrire((0xFF00 >> n) & 0xFF | (v >> (6 * (n - 1))), 8);
+        for i in (0..n - 1).rev() {
+            self.ecrire(0x80 | ((v >> (6 * i)) & 0x3F), 8);
+        }
+    }
+}
+
+pub(super) fn crc8(octets: &[u8]) -> u8 {
+    octets.iter().fold(0u8, |mut crc, &o| {
+        crc ^= o;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+pub(super) fn crc16(octets: &[u8]) -> u16 {
+    octets.iter().fold(0u16, |mut crc, &o| {
+        crc ^= u16::from(o) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn zigzag(r: i64) -> u64 {
+    ((r << 1) ^ (r >> 63)) as u64
+}
+
+/// Codage retenu pour un canal
+pub(super) enum SousTrame {
+    Constante,
+    Brute,
+    Fixe {
+        ordre: usize,
+        residus: Vec<i64>,
+        partition: u32,
+        parametres: Vec<u32>,
+    },
+}
+
+/// Prédicteur fixe d'ordre 0 à 4 (différences successives)
+fn residus(x: &[i64], ordre: usize) -> Vec<i64> {
+    (ordre..x.len())
+        .map(|i| match ordre {
+            0 => x[i],
+            1 => x[i] - x[i - 1],
+            2 => x[i] - 2 * x[i - 1] + x[i - 2],
+            3 => x[i] - 3 * x[i - 1] + 3 * x[i - 2] - x[i - 3],
+            _ => x[i] - 4 * x[i - 1] + 6 * x[i - 2] - 4 * x[i - 3] + x[i - 4],
+        })
+        .collect()
+}
+
+/// Paramètre de Rice d'une partition, estimé depuis la somme des résidus (comme libFLAC)
+fn rice(somme: u64, nombre: u64) -> (u32, u64) {
+    (0..=PARAMETRE_RICE_MAX)
+        .map(|k| (k, nombre * u64::from(k + 1) + (somme >> k)))
+        .min_by_key(|&(_, bits)| bits)
+        .unwrap_or((0, 0))
+}
+
+/// Meilleur ordre de partition et paramètres de Rice ; renvoie aussi la taille estimée du résidu
+fn partitionner(residus: &[i64], ordre: usize, n: usize, max: u32) -> (u32, Vec<u32>, u64) {
+    // Partitions les plus fines possibles, regroupées ensuite deux à deux
+    let mut fin = 0;
+    while fin < max && n.is_multiple_of(1 << (fin + 1)) && (n >> (fin + 1)) > ordre {
+        fin += 1;
+    }
+    let taille = n >> fin;
+    let mut sommes: Vec<(u64, u64)> = (0..1usize << fin)
+        .map(|i| {
+            let debut = if i == 0 { 0 } else { i * taille - ordre };
+            let part = &residus[debut..(i + 1) * taille - ordre];
+            (part.iter().map(|&r| zigzag(r)).sum(), part.len() as u64)
+        })
+        .collect();
+
+    let mut meilleur = (0, Vec::new(), u64::MAX);
+    for p in (0..=fin).rev() {
+        let (parametres, bits): (Vec<u32>, Vec<u64>) = sommes
+            .iter()
+            .map(|&(somme, nombre)| rice(somme, nombre))
+            .unzip();
+        let cout = 6 + 4 * parametres.len() as u64 + bits.iter().sum::<u64>();
+        if cout < meilleur.2 {
+            meilleur = (p, parametres, cout);
+        }
+        sommes = sommes
+            .chunks(2)
+            .map(|c| c.iter().fold((0, 0), |acc, s| (acc.0 + s.0, acc.1 + s.1)))
+            .collect();
+    }
+    meilleur
+}
+
+pub(super) fn analyser(
+    x: &[i64],
+    bps: u32,
+    ordre_max: usize,
+    partition_max: u32,
+) -> (SousTrame, u64) {
+    if x.iter().all(|&v| v == x[0]) {
+        return (SousTrame::Constante, 8 + u64::from(bps));
+    }
+    let brut = 8 + x.len() as u64 * u64::from(bps);
+    let ordre = (0..=ordre_max.min(x.len() - 1))
+        .min_by_key(|&o| residus(x, o).iter().map(|r| r.unsigned_abs()).sum::<u64>())
+        .unwrap_or(0);
+    let r = residus(x, ordre);
+    let (partition, parametres, cout) = partitionner(&r, ordre, x.len(), partition_max);
+    let fixe = 8 + ordre as u64 * u64::from(bps) + cout;
+    if parametres.is_empty() || fixe >= brut {
+        return (SousTrame::Brute, brut);
+    }
+    let sous_trame = SousTrame::Fixe {
+        ordre,
+        residus: r,
+        partition,
+        parametres,
+    };
+    (sous_trame, fixe)
+}
+
+pub(super) fn ecrire_sous_trame(b: &mut Bits, x: &[i64], bps: u32, sous_trame: &SousTrame) {
+    let signe = |b: &mut Bits, v: i64| b.ecrire(v as u64, bps);
+    match sous_trame {
+        SousTrame::Constante => {
+            b.ecrire(0, 8);
+            signe(b, x[0]);
+        }
+        SousTrame::Brute => {
+            b.ecrire(0b0000_0010, 8);
+            x.iter().for_each(|&v| signe(b, v));
+        }
+        SousTrame::Fixe {
+            ordre,
+            residus,
+            partition,
+            parametres,
+        } => {
+            b.ecrire(((0b001000 | *ordre) << 1) as u64, 8);
+            x[..*ordre].iter().for_each(|&v| signe(b, v));
+            b.ecrire(0, 2);
+            b.ecrire(u64::from(*partition), 4);
+            let taille = x.len() >> partition;
+            let mut restants = residus.iter();
+            for (i, &k) in parametres.iter().enumerate() {
+                b.ecrire(u64::from(k), 4);
+                let nombre = if i == 0 { taille - ordre } else { taille };
+                for &r in restants.by_ref().take(nombre) {
+                    let u = zigzag(r);
+                    b.unaire(u >> k);
+                    b.ecrire(u, k);
+                }
+            }
+        }
+    }
+}
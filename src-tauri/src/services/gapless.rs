@@ -0,0 +1,151 @@
+use super::SettingsService;
+use crate::db::Database;
+use crate::models::AppError;
+use lofty::mp4::{AtomData, AtomIdent, Mp4File};
+use lofty::{AudioFile, ItemKey, ParseOptions, Probe, TaggedFileExt};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+const SETTING_CROSSFADE: &str = "player.crossfade_sec";
+// Noms du drapeau « album sans blanc » hors MP4 (TXXX ou commentaire Vorbis)
+const CLES_GAPLESS: [&str; 3] = ["ITUNESGAPLESS", "ITUNPGAP", "GAPLESS"];
+
+/// Délai et remplissage ajoutés par l'encodeur, en trames
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaplessInfo {
+    pub delay: u64,
+    pub padding: u64,
+    pub total_frames: Option<u64>, // Trames utiles, délai et remplissage exclus
+}
+
+impl GaplessInfo {
+    pub fn duree(&self, sample_rate: u32) -> Option<Duration> {
+        self.total_frames
+            .map(|t| Duration::from_secs_f64(t as f64 / f64::from(sample_rate.max(1))))
+    }
+}
+
+/// En-tête de lecture sans blanc (LAME/Xing) et réglage du fondu enchaîné.
+/// Le décodeur MP3 (symphonia) retire lui-même délai et remplissage : l'en-tête ne sert qu'à la durée exacte.
+pub struct GaplessService;
+
+impl GaplessService {
+    pub fn lire(path: &Path) -> Option<GaplessInfo> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "mp3" => Self::lire_lame(path),
+            _ => None,
+        }
+    }
+
+    /// Piste d'un album marqué sans blanc (pgap iTunes, ITUNESGAPLESS/GAPLESS ailleurs) : pas de fondu
+    pub fn est_album_gapless(path: &Path) -> bool {
+        if let Some(ilst) = Self::ilst(path) {
+            return ilst
+                .get(&AtomIdent::Fourcc(*b"pgap"))
+                .is_some_and(|a| a.data().any(|d| matches!(d, AtomData::Bool(true))));
+        }
+        let Ok(tagged_file) = Probe::open(path).and_then(|p| p.read()) else {
+            return false;
+        };
+        tagged_file.tags().iter().any(|tag| {
+            tag.items().any(|item| {
+                let ItemKey::Unknown(cle) = item.key() else {
+                    return false;
+                };
+                CLES_GAPLESS.contains(&cle.to_uppercase().as_str())
+                    && item
+                        .value()
+                        .text()
+                        .is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            })
+        })
+    }
+
+    fn ilst(path: &Path) -> Option<lofty::mp4::Ilst> {
+        let mut file = File::open(path).ok()?;
+        Mp4File::read_from(&mut file, ParseOptions::new().read_properties(false))
+            .ok()?
+            .ilst()
+            .cloned()
+    }
+
+    /// Trame Xing/Info suivie de l'étiquette LAME (ou Lavc) contenant délai et remplissage sur 12 bits
+    fn lire_lame(path: &Path) -> Option<GaplessInfo> {
+        let mut file = File::open(path).ok()?;
+        let mut entete = [0u8; 10];
+        file.read_exact(&mut entete).ok()?;
+        let debut = if &entete[..3] == b"ID3" {
+            let taille = entete[6..10]
+                .iter()
+                .fold(0u64, |acc, b| (acc << 7) | u64::from(b & 0x7F));
+            let pied = if entete[5] & 0x10 != 0 { 10 } else { 0 };
+            10 + taille + pied
+        } else {
+            0
+        };
+        file.seek(SeekFrom::Start(debut)).ok()?;
+        let mut trame = vec![0u8; 4096];
+        let lus = file.read(&mut trame).ok()?;
+        let trame = &trame[..lus];
+
+        let sync = trame
+            .windows(2)
+            .position(|w| w[0] == 0xFF && w[1] & 0xE0 == 0xE0)?;
+        let mpeg1 = (trame.get(sync + 1)? >> 3) & 0x03 == 0x03;
+        let mono = (trame.get(sync + 3)? >> 6) & 0x03 == 0x03;
+        let side = match (mpeg1, mono) {
+            (true, false) => 32,
+            (true, true) | (false, false) => 17,
+            (false, true) => 9,
+        };
+        let xing = sync + 4 + side;
+        let balise = trame.get(xing..xing + 4)?;
+        if balise != b"Xing" && balise != b"Info" {
+            return None;
+        }
+
+        let lire_u32 = |pos: usize| -> Option<u32> {
+            Some(u32::from_be_bytes(
+                trame.get(pos..pos + 4)?.try_into().ok()?,
+            ))
+        };
+        let drapeaux = lire_u32(xing + 4)?;
+        let mut pos = xing + 8;
+        let mut trames = None;
+        if drapeaux & 0x1 != 0 {
+            trames = Some(u64::from(lire_u32(pos)?));
+            pos += 4;
+        }
+        pos += if drapeaux & 0x2 != 0 { 4 } else { 0 };
+        pos += if drapeaux & 0x4 != 0 { 100 } else { 0 };
+        pos += if drapeaux & 0x8 != 0 { 4 } else { 0 };
+
+        let encodeur = trame.get(pos..pos + 4)?;
+        if encodeur != b"LAME" && encodeur != b"Lavc" && encodeur != b"Lavf" {
+            return None;
+        }
+        let octets = trame.get(pos + 21..pos + 24)?;
+        let delay = (u64::from(octets[0]) << 4) | (u64::from(octets[1]) >> 4);
+        let padding = ((u64::from(octets[1]) & 0x0F) << 8) | u64::from(octets[2]);
+        let par_trame = if mpeg1 { 1152 } else { 576 };
+
+        Some(GaplessInfo {
+            delay,
+            padding,
+            total_frames: trames.map(|t| (t * par_trame).saturating_sub(delay + padding)),
+        })
+    }
+
+    pub fn charger_fondu(db: &Database) -> Result<f32, AppError> {
+        Ok(SettingsService::lire_ou(db, SETTING_CROSSFADE, "0")?
+            .parse()
+            .unwrap_or(0.0))
+    }
+
+    pub fn definir_fondu(db: &Database, secondes: f32) -> Result<(), AppError> {
+        SettingsService::ecrire(db, SETTING_CROSSFADE, &secondes.max(0.0).to_string())
+    }
+}
@@ -1,98 +1,86 @@
-use crate::models::AppError;
+use crate::models::changeset::{CoverMove, FileDeletion};
+use crate::models::{Album, AppError, JunkAction, JunkCategory, JunkEntry};
+use crate::services::cleaner_plan::FichierAlbum;
+use crate::services::{CleanerService, CleaningRuleService, CompilationService};
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::path::Path;
 
+lazy_static! {
+    // Année (ou plage d'années) en tête du titre : "(1999) ", "(1971-15) "
+    pub(crate) static ref RE_ANNEE_TITRE: Regex = Regex::new(r"^\(\d{4}(?:-\d{2})?\)\s*").unwrap();
+}
+
 pub struct InspectorService;
 
 impl InspectorService {
-    pub fn detecter_fichiers_inutiles(chemin_dossier: &str, album_info: Option<(&str, &str, &str)>) -> Result<Vec<String>, AppError> {
-        let mut junk_files = Vec::new();
-        let audio_ext = ["mp3", "flac", "ogg", "m4a", "wav"];
-        let playlist_ext = ["m3u", "m3u8"];
-
-        let path = Path::new(chemin_dossier);
+    /// Liste ce que le nettoyage fera dans l'album (récursivement), sans rien modifier.
+    /// Repose sur la même planification que apply_auto_correct pour que l'aperçu soit fidèle :
+    /// pochette et nettoyage portent sur les fichiers tels qu'ils seront après les renommages.
+    pub fn detecter_fichiers_inutiles(album: &Album) -> Result<Vec<JunkEntry>, AppError> {
+        let path = Path::new(album.path.trim_end_matches(['/', '\\']));
         if !path.exists() || !path.is_dir() {
             return Err(AppError::Validation("Dossier invalide".to_string()));
         }
 
-        for entry in std::fs::read_dir(path).map_err(|e| AppError::Io(e.to_string()))? {
-            let entry = entry.map_err(|e| AppError::Io(e.to_string()))?;
-            let path = entry.path();
-            if path.is_file() {
-                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
-
-                // Règle stricte : On garde uniquement les fichiers audio et "cover.jpg" (strictement minuscule)
-                let is_audio = if let Some(ext) = path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    audio_ext.contains(&ext_str.as_str())
-                } else {
-                    false
-                };
-
-                // Seul "cover.jpg" est autorisé. "Cover.jpg", "COVER.jpg" etc. sont considérés comme inutiles.
-                let is_cover = file_name == "cover.jpg";
-
-
-                // Autoriser la playlist si elle respecte le format "Artist - (Year) Album"
-                let is_valid_playlist = if let Some((artist, year, title)) = album_info {
-                    if let Some(ext) = path.extension() {
-                        let ext_str = ext.to_string_lossy().to_lowercase();
-                        if playlist_ext.contains(&ext_str.as_str()) {
-                             let stem = path.file_stem().unwrap_or_default().to_string_lossy();
-                             let expected = format!("{} - ({}) {}", artist.trim(), year, title.trim());
-                             
-                             // Normalisation robuste : espaces + quotes
-                             let normalize = |s: &str| {
-                                 s.replace('’', "'")
-                                  .replace('`', "'")
-                                  .split_whitespace()
-                                  .collect::<Vec<_>>()
-                                  .join(" ")
-                             };
-                             
-                             let stem_norm = normalize(&stem);
-                             let expected_norm = normalize(&expected);
-                             let title_norm = normalize(title); 
-                             let expected_simple = normalize(&format!("{} - {}", artist.trim(), title.trim()));
-
-                             // On accepte :
-                             // 1. Format strict : Artist - (Year) Title
-                             // 2. Format simple : Artist - Title
-                             // 3. Titre seul : Title
-                             stem_norm.eq_ignore_ascii_case(&expected_norm) || 
-                             stem_norm.eq_ignore_ascii_case(&expected_simple) ||
-                             stem_norm.eq_ignore_ascii_case(&title_norm)
-                        } else {
-                             false
-                        }
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                };
-
-                if !is_audio && !is_cover && !is_valid_playlist {
-                     if let Some(ext) = path.extension() {
-                          let ext_str = ext.to_string_lossy().to_lowercase();
-                          if playlist_ext.contains(&ext_str.as_str()) {
-                               // Si on est ici, c'est que is_valid_playlist a retourné FALSE
-                               // On n'ajoute RIEN aux junk_files si c'est une playlist qui échoue "juste" la vérification de nom
-                               // C'est un choix UX : une playlist mal nommée n'est pas "inutile" (junk), elle est juste mal nommée.
-                               // Le système de "Junk detection" vise les fichiers .txt, .nfo, images webp, etc.
-                               // Pour éviter de frustrer l'utilisateur avec des faux positifs, on ignore silencieusement les playlists.
-                               continue; 
-                          }
-                     }
+        let cleaner = CleanerService::new();
+        let regles = CleaningRuleService::actives();
+        let mut fichiers = cleaner.lister_fichiers(path);
+        cleaner.planifier_renommages(album, path, &mut fichiers);
+        let initiaux = fichiers.clone();
+
+        let mut junk = Vec::new();
+        let (moves, deletions) = cleaner.planifier_cover(&mut fichiers, &regles);
+        Self::ajouter(&mut junk, &initiaux, &moves, &deletions, Some(JunkCategory::Cover));
+        let (moves, deletions) = cleaner.planifier_nettoyage(&mut fichiers, &regles);
+        Self::ajouter(&mut junk, &initiaux, &moves, &deletions, None);
+        Ok(junk)
+    }
 
-                    if file_name.to_lowercase() == "cover.jpg" {
-                        junk_files.push(format!("{} (sera renommé en cover.jpg)", file_name));
-                    } else {
-                        junk_files.push(file_name.to_string());
-                    }
-                }
-            }
+    fn ajouter(
+        junk: &mut Vec<JunkEntry>,
+        initiaux: &[FichierAlbum],
+        moves: &[CoverMove],
+        deletions: &[FileDeletion],
+        category: Option<JunkCategory>,
+    ) {
+        let categorie = |chemin: &str| {
+            category.unwrap_or_else(|| {
+                let name = Path::new(chemin)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                JunkCategory::depuis_nom(&name)
+            })
+        };
+
+        for m in moves {
+            let size = initiaux
+                .iter()
+                .find(|f| f.chemin == Path::new(&m.from))
+                .map(|f| f.taille)
+                .unwrap_or(0);
+            junk.push(JunkEntry {
+                path: m.from.clone(),
+                size,
+                category: categorie(&m.from),
+                action: JunkAction::Rename,
+                target: Some(m.to.clone()),
+                reason: m.reason.clone(),
+                rule: m.rule.clone(),
+            });
+        }
+        for d in deletions {
+            junk.push(JunkEntry {
+                path: d.path.clone(),
+                size: d.size,
+                category: categorie(&d.path),
+                action: JunkAction::Delete,
+                target: None,
+                reason: d.reason.clone(),
+                rule: d.rule.clone(),
+            });
         }
-        Ok(junk_files)
     }
 
     pub fn find_cover_image(path: &Path) -> Option<String> {
@@ -128,6 +116,12 @@ impl InspectorService {
     }
 
     pub fn has_playlist(path: &Path) -> bool {
+        !Self::playlists(path).is_empty()
+    }
+
+    /// Noms (sans extension) des playlists à la racine de l'album
+    pub fn playlists(path: &Path) -> Vec<String> {
+        let mut noms = Vec::new();
         if let Ok(entries) = std::fs::read_dir(path) {
             for entry in entries.flatten() {
                 let p = entry.path();
@@ -135,12 +129,45 @@ impl InspectorService {
                     if let Some(ext) = p.extension() {
                         let ext_str = ext.to_string_lossy().to_lowercase();
                         if ["m3u", "m3u8", "pls"].contains(&ext_str.as_str()) {
-                            return true;
+                            noms.push(p.file_stem().unwrap_or_default().to_string_lossy().to_string());
                         }
                     }
                 }
             }
         }
-        false
+        noms
+    }
+
+    /// La playlist respecte-t-elle le format "Artiste - (Année) Titre" ?
+    /// On accepte aussi "Artiste - Titre" et "Titre" seul. Pour une compilation,
+    /// l'artiste attendu est le nom configuré pour "Various Artists".
+    pub fn playlist_conforme(album: &Album, nom: &str) -> bool {
+        // Année ou plage d'années (ex: 1971-15) pour une compilation
+        let year_str = match (album.year_min, album.year_max) {
+            (Some(min), Some(max)) if min > 0 && max > 0 && min != max => {
+                format!("{}-{:02}", min, max % 100)
+            }
+            _ => album.year.or(album.year_min).unwrap_or(0).to_string(),
+        };
+        let artist = CompilationService::artiste_album(album);
+        let title = RE_ANNEE_TITRE.replace(&album.title, "").to_string();
+
+        // Normalisation robuste : espaces + quotes
+        #[allow(clippy::collapsible_str_replace)]
+        let normalize = |s: &str| {
+            s.replace('’', "'")
+                .replace('`', "'")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let nom = normalize(nom);
+        [
+            format!("{} - ({}) {}", artist.trim(), year_str, title.trim()),
+            format!("{} - {}", artist.trim(), title.trim()),
+            title.trim().to_string(),
+        ]
+        .iter()
+        .any(|attendu| nom.eq_ignore_ascii_case(&normalize(attendu)))
     }
 }
@@ -0,0 +1,272 @@
+use super::pcm::Pcm;
+use super::IOService;
+use crate::db::Database;
+use crate::models::{Album, AppError, IntegrityProblem, IntegrityReport};
+use lofty::{AudioFile, ParseOptions, Probe};
+use rodio::{Decoder, Source};
+use rusqlite::{params, OptionalExtension};
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+
+// Écart toléré entre la durée annoncée par les métadonnées et le décodage réel
+const TOLERANCE_SEC: f64 = 0.5;
+
+/// Vérification d'intégrité par décodage complet, avec la même pile que la lecture
+pub struct IntegrityService;
+
+impl IntegrityService {
+    /// Vérifie chaque piste de l'album, en réutilisant les rapports déjà calculés pour un même contenu
+    pub fn verifier_album(db: &Database, album: &Album) -> Result<Vec<IntegrityReport>, AppError> {
+        let mut rapports = Vec::new();
+        for track in &album.tracks {
+            let path = Path::new(&track.path);
+            let hash = IOService::empreinte_fichier(path)?;
+
+            let rapport = match Self::lire_cache(db, &hash)? {
+                Some(mut rapport) => {
+                    // Le fichier a pu être renommé depuis la dernière vérification
+                    rapport.path = track.path.clone();
+                    rapport
+                }
+                None => {
+                    let rapport = Self::verifier_fichier(path, &hash);
+                    Self::ecrire_cache(db, &rapport)?;
+                    rapport
+                }
+            };
+            rapports.push(rapport);
+        }
+        Ok(rapports)
+    }
+
+    pub fn verifier_fichier(path: &Path, hash: &str) -> IntegrityReport {
+        let mut rapport = IntegrityReport {
+            path: path.to_string_lossy().to_string(),
+            hash: hash.to_string(),
+            decoded_frames: 0,
+            expected_frames: None,
+            md5_match: None,
+            problems: Vec::new(),
+        };
+
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "flac" => Self::verifier_flac(path, &mut rapport),
+            "wav" => {
+                Self::verifier_wav(path, &mut rapport);
+                Self::verifier_generique(path, &mut rapport);
+            }
+            _ if !Pcm::decodable(path) => Self::probleme(
+                &mut rapport,
+                "NOT_VERIFIABLE",
+                "Aucun décodeur intégré pour ce format (AAC, Opus) : non vérifiable".to_string(),
+            ),
+            _ => Self::verifier_generique(path, &mut rapport),
+        }
+        rapport
+    }
+
+    fn verifier_flac(path: &Path, rapport: &mut IntegrityReport) {
+        let mut reader = match claxon::FlacReader::open(path) {
+            Ok(reader) => reader,
+            Err(e) => {
+                return Self::probleme(rapport, "DECODE_ERROR", format!("FLAC illisible: {}", e))
+            }
+        };
+
+        let info = reader.streaminfo();
+        let channels = u64::from(info.channels.max(1));
+        let largeur = info.bits_per_sample.div_ceil(8) as usize;
+        rapport.expected_frames = info.samples;
+
+        // Le MD5 de STREAMINFO porte sur les échantillons entrelacés, en petit-boutiste signé
+        let mut context = md5::Context::new();
+        let mut samples = 0u64;
+        let mut interrompu = false;
+        for sample in reader.samples() {
+            match sample {
+                Ok(value) => {
+                    context.consume(&value.to_le_bytes()[..largeur]);
+                    samples += 1;
+                }
+                Err(claxon::Error::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+                    interrompu = true;
+                    Self::probleme(
+                        rapport,
+                        "TRUNCATED",
+                        "Fin de fichier inattendue".to_string(),
+                    );
+                    break;
+                }
+                Err(e) => {
+                    interrompu = true;
+                    Self::probleme(
+                        rapport,
+                        "DECODE_ERROR",
+                        format!("Erreur de décodage: {}", e),
+                    );
+                    break;
+                }
+            }
+        }
+        rapport.decoded_frames = samples / channels;
+
+        if interrompu {
+            return;
+        }
+        if let Some(attendu) = info.samples {
+            Self::comparer(rapport, attendu, 0);
+        }
+        if info.md5sum != [0u8; 16] {
+            let ok = context.finalize().0 == info.md5sum;
+            rapport.md5_match = Some(ok);
+            if !ok {
+                Self::probleme(
+                    rapport,
+                    "FLAC_MD5_MISMATCH",
+                    "Le MD5 des échantillons ne correspond pas à STREAMINFO".to_string(),
+                );
+            }
+        }
+    }
+
+    // rodio complète de silence un WAV tronqué : on compare donc la taille annoncée du bloc data au fichier
+    fn verifier_wav(path: &Path, rapport: &mut IntegrityReport) {
+        let Ok(mut file) = File::open(path) else {
+            return;
+        };
+        let longueur = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let mut pos = 12u64; // Après "RIFF", taille et "WAVE"
+        let mut entete = [0u8; 8];
+        while pos + 8 <= longueur {
+            if file.seek(SeekFrom::Start(pos)).is_err() || file.read_exact(&mut entete).is_err() {
+                return;
+            }
+            let taille = u64::from(u32::from_le_bytes([
+                entete[4], entete[5], entete[6], entete[7],
+            ]));
+            if &entete[..4] == b"data" {
+                let disponible = longueur - pos - 8;
+                if disponible < taille {
+                    Self::probleme(
+                        rapport,
+                        "TRUNCATED",
+                        format!("{} octets audio sur {} annoncés", disponible, taille),
+                    );
+                }
+                return;
+            }
+            pos += 8 + taille + taille % 2;
+        }
+    }
+
+    fn verifier_generique(path: &Path, rapport: &mut IntegrityReport) {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                return Self::probleme(
+                    rapport,
+                    "DECODE_ERROR",
+                    format!("Ouverture impossible: {}", e),
+                )
+            }
+        };
+        let decoder = match Decoder::new(BufReader::new(file)) {
+            Ok(decoder) => decoder,
+            Err(e) => {
+                return Self::probleme(
+                    rapport,
+                    "DECODE_ERROR",
+                    format!("Format non décodable: {}", e),
+                )
+            }
+        };
+
+        let channels = u64::from(decoder.channels().max(1));
+        let sample_rate = decoder.sample_rate();
+        rapport.decoded_frames = decoder.count() as u64 / channels;
+
+        if rapport.decoded_frames == 0 {
+            return Self::probleme(
+                rapport,
+                "DECODE_ERROR",
+                "Aucun échantillon décodé".to_string(),
+            );
+        }
+
+        // Les conteneurs compressés n'annoncent qu'une durée : on la convertit en trames
+        let duree = Probe::open(path)
+            .ok()
+            .map(|p| p.options(ParseOptions::new().read_properties(true)))
+            .and_then(|p| p.read().ok())
+            .map(|f| f.properties().duration());
+        if let Some(duree) = duree.filter(|d| !d.is_zero()) {
+            let attendu = (duree.as_secs_f64() * f64::from(sample_rate)).round() as u64;
+            rapport.expected_frames = Some(attendu);
+            let tolerance = (TOLERANCE_SEC * f64::from(sample_rate)) as u64;
+            // Un WAV tronqué est déjà signalé, inutile de le compter deux fois
+            if rapport.problems.is_empty() {
+                Self::comparer(rapport, attendu, tolerance);
+            }
+        }
+    }
+
+    fn comparer(rapport: &mut IntegrityReport, attendu: u64, tolerance: u64) {
+        let decode = rapport.decoded_frames;
+        if decode + tolerance < attendu {
+            Self::probleme(
+                rapport,
+                "TRUNCATED",
+                format!("{} trames décodées sur {} annoncées", decode, attendu),
+            );
+        } else if decode > attendu + tolerance {
+            Self::probleme(
+                rapport,
+                "SAMPLE_COUNT_MISMATCH",
+                format!("{} trames décodées pour {} annoncées", decode, attendu),
+            );
+        }
+    }
+
+    fn probleme(rapport: &mut IntegrityReport, code: &str, message: String) {
+        rapport.problems.push(IntegrityProblem {
+            code: code.to_string(),
+            message,
+        });
+    }
+
+    fn lire_cache(db: &Database, hash: &str) -> Result<Option<IntegrityReport>, AppError> {
+        let conn = db
+            .conn
+            .lock()
+            .map_err(|_| AppError::DatabaseError("Lock error".into()))?;
+
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT report FROM integrity_cache WHERE hash = ?1",
+                params![hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        // Un rapport illisible (ancien format) est simplement recalculé
+        Ok(json.and_then(|j| serde_json::from_str(&j).ok()))
+    }
+
+    fn ecrire_cache(db: &Database, rapport: &IntegrityReport) -> Result<(), AppError> {
+        let json = serde_json::to_string(rapport).map_err(|e| AppError::Unknown(e.to_string()))?;
+        let conn = db
+            .conn
+            .lock()
+            .map_err(|_| AppError::DatabaseError("Lock error".into()))?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO integrity_cache (hash, report) VALUES (?1, ?2)",
+            params![rapport.hash, json],
+        )?;
+        Ok(())
+    }
+}
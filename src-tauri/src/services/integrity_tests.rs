@@ -0,0 +1,85 @@
+use super::test_fixtures::{dossier_temp, ecrire_wav_annonce};
+use super::*;
+use crate::db::Database;
+use crate::models::{Album, Severity, Track};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// WAV PCM 16 bits mono, dont l'en-tête peut annoncer plus de trames qu'il n'en contient
+fn ecrire_wav(path: &Path, trames: u32, annoncees: u32) {
+    let echantillons: Vec<i32> = (0..trames).map(|i| (i % 100) as i32 * 100).collect();
+    ecrire_wav_annonce(path, 8000, 1, 16, &echantillons, annoncees as usize);
+}
+
+#[test]
+fn test_verification_integrite() {
+    let db = Database::open(":memory:").unwrap();
+    let dir = dossier_temp("integrity");
+    let saine = dir.join("01 - Saine.wav");
+    let tronquee = dir.join("02 - Tronquee.wav");
+    ecrire_wav(&saine, 8000, 8000);
+    ecrire_wav(&tronquee, 8000, 24000);
+
+    let mut album = Album::new(
+        dir.to_string_lossy().to_string(),
+        "Test".into(),
+        "Test".into(),
+    );
+    for path in [&saine, &tronquee] {
+        let nom = path.file_name().unwrap().to_string_lossy().to_string();
+        album
+            .tracks
+            .push(Track::new(path.to_string_lossy().to_string(), nom));
+    }
+
+    let rapports = IntegrityService::verifier_album(&db, &album).unwrap();
+    assert_eq!(rapports[0].decoded_frames, 8000);
+    assert!(rapports[0].problems.is_empty());
+    assert!(rapports[1].problems.iter().any(|p| p.code == "TRUNCATED"));
+
+    // Un fichier renommé garde son rapport, retrouvé par empreinte
+    let renommee = dir.join("02 - Renommee.wav");
+    fs::rename(&tronquee, &renommee).unwrap();
+    album.tracks[1].path = renommee.to_string_lossy().to_string();
+    let rapports = IntegrityService::verifier_album(&db, &album).unwrap();
+    assert_eq!(rapports[1].path, album.tracks[1].path);
+
+    ValidatorService::evaluer_avec(&mut album, validator_integrity::regles_integrite(&rapports));
+    assert!(album
+        .validation
+        .iter()
+        .any(|i| i.code == "TRUNCATED" && i.tracks == vec![album.tracks[1].path.clone()]));
+    assert!(ValidatorService::lister_regles()
+        .iter()
+        .any(|r| r.code == "FLAC_MD5_MISMATCH"));
+
+    // Une réévaluation sans nouvelle analyse applique le réglage courant aux constats conservés
+    let gravite = |album: &Album| {
+        album
+            .validation
+            .iter()
+            .find(|i| i.code == "TRUNCATED")
+            .map(|i| i.severity)
+    };
+    let config = HashMap::from([("TRUNCATED".to_string(), Some(Severity::Warning))]);
+    ValidatorService::evaluer_selon(&mut album, Vec::new(), &config);
+    assert_eq!(gravite(&album), Some(Severity::Warning));
+    ValidatorService::evaluer_selon(&mut album, Vec::new(), &HashMap::new());
+    assert_eq!(gravite(&album), Some(Severity::Error));
+    let config = HashMap::from([("TRUNCATED".to_string(), None)]);
+    ValidatorService::evaluer_selon(&mut album, Vec::new(), &config);
+    assert_eq!(gravite(&album), None);
+
+    // Sans décodeur AAC ni Opus : simple information, pas d'erreur de décodage
+    let aac = dir.join("03 - Aac.m4a");
+    let opus = dir.join("04 - Opus.ogg");
+    fs::write(&aac, b"....ftypM4A ").unwrap();
+    fs::write(&opus, b"OggS\0\x02OpusHead\x01\x02").unwrap();
+    for path in [&aac, &opus] {
+        let rapport = IntegrityService::verifier_fichier(path, "");
+        let codes: Vec<&str> = rapport.problems.iter().map(|p| p.code.as_str()).collect();
+        assert_eq!(codes, vec!["NOT_VERIFIABLE"]);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,5 +1,7 @@
 use crate::models::AppError;
-use std::fs;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
 
 pub struct IOService;
 
@@ -13,4 +15,51 @@ impl IOService {
         fs::rename(old_path, new_path)
             .map_err(|e| AppError::Io(format!("Erreur renommage dossier: {}", e)))
     }
+
+    /// Déplace un fichier ou un dossier, avec repli copie + suppression entre systèmes de fichiers
+    pub fn move_file(old_path: &Path, new_path: &Path) -> Result<(), AppError> {
+        if fs::rename(old_path, new_path).is_err() {
+            let erreur = |e: std::io::Error| AppError::Io(format!("Erreur déplacement: {}", e));
+            if old_path.is_dir() {
+                Self::copier_dossier(old_path, new_path).map_err(erreur)?;
+                fs::remove_dir_all(old_path).map_err(erreur)?;
+            } else {
+                fs::copy(old_path, new_path).map_err(erreur)?;
+                fs::remove_file(old_path).map_err(erreur)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn copier_dossier(from: &Path, to: &Path) -> std::io::Result<()> {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let cible = to.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                Self::copier_dossier(&entry.path(), &cible)?;
+            } else {
+                fs::copy(entry.path(), cible)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Empreinte MD5 du contenu d'un fichier, clé des caches d'analyse
+    pub fn empreinte_fichier(path: &Path) -> Result<String, AppError> {
+        let mut file =
+            File::open(path).map_err(|e| AppError::Io(format!("Erreur lecture fichier: {}", e)))?;
+        let mut context = md5::Context::new();
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let n = file
+                .read(&mut buffer)
+                .map_err(|e| AppError::Io(format!("Erreur lecture fichier: {}", e)))?;
+            if n == 0 {
+                break;
+            }
+            context.consume(&buffer[..n]);
+        }
+        Ok(format!("{:x}", context.finalize()))
+    }
 }
@@ -0,0 +1,235 @@
+use crate::db::Database;
+use crate::models::{AlbumLoudness, AppError, JournalOperation, Track, TrackLoudness};
+use crate::services::{AudioService, IOService, QuarantineService};
+use rusqlite::params;
+use std::path::Path;
+use std::sync::MutexGuard;
+
+const STATUS_EN_COURS: &str = "en_cours";
+const STATUS_TERMINEE: &str = "terminee";
+const STATUS_ANNULEE: &str = "annulee";
+// Annulation interrompue : les étapes déjà annulées sont marquées et sautées à la reprise
+const STATUS_PARTIELLE: &str = "annulation_partielle";
+
+pub(super) const STEP_RENAME: &str = "rename";
+pub(super) const STEP_TAGS: &str = "tags";
+pub(super) const STEP_DELETE: &str = "delete";
+pub(super) const STEP_GAIN: &str = "gain";
+
+/// Opération en cours : chaque action réussie est journalisée pour pouvoir être annulée
+pub struct Transaction<'a> {
+    db: &'a Database,
+    operation_id: i64,
+    seq: i64,
+}
+
+impl<'a> Transaction<'a> {
+    pub fn demarrer(db: &'a Database, kind: &str, album_path: &str) -> Result<Self, AppError> {
+        let conn = verrouiller(db)?;
+        conn.execute(
+            "INSERT INTO journal_operations (kind, album_path, status) VALUES (?1, ?2, ?3)",
+            params![kind, album_path, STATUS_EN_COURS],
+        )?;
+        let operation_id = conn.last_insert_rowid();
+
+        Ok(Self {
+            db,
+            operation_id,
+            seq: 0,
+        })
+    }
+
+    /// Renomme un fichier ou un dossier, sans jamais écraser une cible existante : le rename
+    /// du système le ferait en silence et l'annulation ne pourrait pas la restaurer
+    pub fn renommer(&mut self, from: &Path, to: &Path) -> Result<(), AppError> {
+        let from_str = from.to_string_lossy();
+        let to_str = to.to_string_lossy();
+        // Même fichier sous une autre casse (système de fichiers insensible) : renommage permis
+        if to.exists() && from.canonicalize().ok() != to.canonicalize().ok() {
+            return Err(AppError::Validation(format!(
+                "Renommage refusé, la cible existe déjà: {}",
+                to_str
+            )));
+        }
+        if from.is_dir() {
+            IOService::rename_folder(&from_str, &to_str)?;
+        } else {
+            IOService::rename_file(&from_str, &to_str)?;
+        }
+        self.enregistrer(STEP_RENAME, &from_str, Some(&to_str), None)
+    }
+
+    /// Écrit les tags d'une piste après avoir photographié les tags actuels
+    pub fn ecrire_tags(&mut self, audio: &AudioService, track: &Track) -> Result<(), AppError> {
+        let mut snapshot = audio.lire_metadonnees(&track.path)?;
+        snapshot.original_metadata = None;
+        let json =
+            serde_json::to_string(&snapshot).map_err(|e| AppError::Unknown(e.to_string()))?;
+
+        audio.ecrire_metadonnees(track)?;
+        self.enregistrer(STEP_TAGS, &track.path, None, Some(&json))
+    }
+
+    /// Écrit les tags de gain d'une piste après avoir photographié les gains actuels
+    pub fn ecrire_gain(
+        &mut self,
+        audio: &AudioService,
+        piste: &TrackLoudness,
+        album: &AlbumLoudness,
+    ) -> Result<(), AppError> {
+        let snapshot = audio.lire_gain(&piste.path)?;
+        let json =
+            serde_json::to_string(&snapshot).map_err(|e| AppError::Unknown(e.to_string()))?;
+
+        audio.ecrire_gain(piste, album)?;
+        self.enregistrer(STEP_GAIN, &piste.path, None, Some(&json))
+    }
+
+    /// Retire un fichier via la quarantaine (ou la corbeille), sauf suppression définitive
+    pub fn supprimer(&mut self, path: &Path, reason: &str) -> Result<(), AppError> {
+        let stored = QuarantineService::mettre_en_quarantaine(self.db, path, reason)?;
+        self.enregistrer(STEP_DELETE, &path.to_string_lossy(), stored.as_deref(), None)
+    }
+
+    pub fn valider(self) -> Result<(), AppError> {
+        JournalService::changer_statut(self.db, self.operation_id, STATUS_TERMINEE)
+    }
+
+    /// Annule toutes les actions déjà effectuées par cette transaction
+    pub fn annuler(self) -> Result<(), AppError> {
+        JournalService::annuler_operation(self.db, self.operation_id)
+    }
+
+    /// Annule après l'échec `erreur` et renvoie l'erreur à remonter : si l'annulation échoue
+    /// aussi, elle porte les deux causes pour prévenir que le dossier reste à moitié modifié
+    pub fn annuler_apres(self, erreur: AppError) -> AppError {
+        match self.annuler() {
+            Ok(()) => erreur,
+            Err(rollback_err) => AppError::Io(format!(
+                "{} ; annulation incomplète, le dossier est resté partiellement modifié : {}",
+                erreur, rollback_err
+            )),
+        }
+    }
+
+    fn enregistrer(
+        &mut self,
+        kind: &str,
+        path: &str,
+        target: Option<&str>,
+        snapshot: Option<&str>,
+    ) -> Result<(), AppError> {
+        self.seq += 1;
+        let conn = verrouiller(self.db)?;
+        conn.execute(
+            "INSERT INTO journal_steps (operation_id, seq, kind, path, target, snapshot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![self.operation_id, self.seq, kind, path, target, snapshot],
+        )?;
+        Ok(())
+    }
+}
+
+pub struct JournalService;
+
+impl JournalService {
+    pub fn lister(db: &Database) -> Result<Vec<JournalOperation>, AppError> {
+        Self::charger_operations(db, "1 = 1", 20)
+    }
+
+    /// Annule la dernière opération terminée, ou reprend une annulation interrompue
+    /// (une opération en cours n'est jamais rejouée)
+    pub fn annuler_derniere(db: &Database) -> Result<Option<JournalOperation>, AppError> {
+        let filtre = format!(
+            "o.status IN ('{}', '{}')",
+            STATUS_TERMINEE, STATUS_PARTIELLE
+        );
+        let derniere = Self::charger_operations(db, &filtre, 1)?.into_iter().next();
+
+        match derniere {
+            Some(operation) if !operation.undoable => Err(AppError::Validation(
+                "La dernière opération a supprimé des fichiers définitivement : annulation impossible"
+                    .into(),
+            )),
+            Some(mut operation) => {
+                Self::annuler_operation(db, operation.id)?;
+                operation.status = STATUS_ANNULEE.to_string();
+                Ok(Some(operation))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Rejoue à l'envers les étapes pas encore annulées d'une opération pour restaurer l'état initial
+    pub fn annuler_operation(db: &Database, operation_id: i64) -> Result<(), AppError> {
+        let steps = Self::charger_etapes(db, operation_id)?;
+        let audio_service = AudioService::new();
+        let mut erreurs = Vec::new();
+
+        for step in steps.iter().rev() {
+            match Self::annuler_etape(db, step, &audio_service) {
+                Ok(()) => Self::marquer_annulee(db, step.id)?,
+                Err(e) => erreurs.push(format!("{} ({}): {}", step.path, step.kind, e)),
+            }
+        }
+
+        if !erreurs.is_empty() {
+            Self::changer_statut(db, operation_id, STATUS_PARTIELLE)?;
+            return Err(AppError::Io(format!(
+                "Annulation incomplète: {}",
+                erreurs.join(", ")
+            )));
+        }
+
+        Self::changer_statut(db, operation_id, STATUS_ANNULEE)
+    }
+
+    fn charger_operations(
+        db: &Database,
+        filtre: &str,
+        limite: usize,
+    ) -> Result<Vec<JournalOperation>, AppError> {
+        let conn = verrouiller(db)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT o.id, o.kind, o.album_path, o.created_at, o.status,
+                    (SELECT COUNT(*) FROM journal_steps s WHERE s.operation_id = o.id),
+                    NOT EXISTS (SELECT 1 FROM journal_steps s WHERE s.operation_id = o.id
+                                AND s.kind = '{}' AND s.target IS NULL)
+             FROM journal_operations o WHERE {} ORDER BY o.id DESC LIMIT {}",
+            STEP_DELETE, filtre, limite
+        ))?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok(JournalOperation {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                album_path: row.get(2)?,
+                created_at: row.get(3)?,
+                status: row.get(4)?,
+                step_count: row.get::<_, i64>(5)? as usize,
+                undoable: row.get(6)?,
+            })
+        })?;
+
+        let mut operations = Vec::new();
+        for row in rows {
+            operations.push(row?);
+        }
+        Ok(operations)
+    }
+
+    fn changer_statut(db: &Database, operation_id: i64, status: &str) -> Result<(), AppError> {
+        let conn = verrouiller(db)?;
+        conn.execute(
+            "UPDATE journal_operations SET status = ?1 WHERE id = ?2",
+            params![status, operation_id],
+        )?;
+        Ok(())
+    }
+}
+
+pub(super) fn verrouiller(db: &Database) -> Result<MutexGuard<'_, rusqlite::Connection>, AppError> {
+    db.conn
+        .lock()
+        .map_err(|_| AppError::DatabaseError("Lock error".into()))
+}
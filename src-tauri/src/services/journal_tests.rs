@@ -0,0 +1,183 @@
+use super::journal::Transaction;
+use super::test_fixtures::dossier_temp;
+use super::*;
+use crate::db::Database;
+use crate::models::{AppError, DeletionMode};
+use std::fs;
+
+#[test]
+fn test_annuler_renommage() {
+    let db = Database::open(":memory:").unwrap();
+    let dir = std::env::temp_dir().join(format!("tagotomatik_journal_{}", std::process::id()));
+    fs::create_dir_all(dir.join("CD1")).unwrap();
+    let original = dir.join("CD1").join("track.mp3");
+    fs::write(&original, b"data").unwrap();
+
+    let mut tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    let renamed = dir.join("01 - Track.mp3");
+    tx.renommer(&original, &renamed).unwrap();
+    fs::remove_dir(dir.join("CD1")).unwrap();
+    tx.valider().unwrap();
+    assert!(renamed.exists());
+
+    let annulee = JournalService::annuler_derniere(&db).unwrap().unwrap();
+    assert_eq!(annulee.step_count, 1);
+    assert!(original.exists());
+    assert!(!renamed.exists());
+
+    // Plus rien à annuler
+    assert!(JournalService::annuler_derniere(&db).unwrap().is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_renommage_refuse_sur_cible_existante() {
+    let db = Database::open(":memory:").unwrap();
+    let dir = dossier_temp("journal_cible");
+    let (a, b) = (dir.join("a.flac"), dir.join("b.flac"));
+    fs::write(&a, b"a").unwrap();
+    fs::write(&b, b"b").unwrap();
+
+    let mut tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    assert!(tx.renommer(&a, &b).is_err());
+    tx.annuler().unwrap();
+    assert_eq!(fs::read(&a).unwrap(), b"a");
+    assert_eq!(fs::read(&b).unwrap(), b"b");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_annulation_interrompue_reprise() {
+    let db = Database::open(":memory:").unwrap();
+    let dir = dossier_temp("journal_reprise");
+    let (a, b) = (dir.join("a.flac"), dir.join("b.flac"));
+    fs::write(&a, b"a").unwrap();
+    fs::write(&b, b"b").unwrap();
+    let (a2, b2) = (dir.join("01 - A.flac"), dir.join("02 - B.flac"));
+
+    let mut tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    tx.renommer(&a, &a2).unwrap();
+    tx.renommer(&b, &b2).unwrap();
+    tx.valider().unwrap();
+
+    // Le premier renommage ne peut plus être défait : le second l'est, et reste marqué
+    let ailleurs = dir.join("deplace.flac");
+    fs::rename(&a2, &ailleurs).unwrap();
+    assert!(JournalService::annuler_derniere(&db).is_err());
+    assert!(b.exists());
+    assert_eq!(
+        JournalService::lister(&db).unwrap()[0].status,
+        "annulation_partielle"
+    );
+
+    // La reprise ne rejoue que l'étape restante
+    fs::rename(&ailleurs, &a2).unwrap();
+    let annulee = JournalService::annuler_derniere(&db).unwrap().unwrap();
+    assert_eq!(annulee.status, "annulee");
+    assert!(a.exists() && b.exists());
+
+    // Échec puis annulation impossible : l'erreur remontée porte les deux causes
+    let tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    let erreur = tx.annuler_apres(AppError::Audio("écriture".into()));
+    assert!(matches!(erreur, AppError::Audio(_)));
+    let mut tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    tx.renommer(&a, &a2).unwrap();
+    fs::remove_file(&a2).unwrap();
+    let erreur = tx
+        .annuler_apres(AppError::Audio("écriture".into()))
+        .to_string();
+    assert!(erreur.contains("écriture") && erreur.contains("partiellement modifié"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_quarantaine_et_annulation() {
+    let db = Database::open(":memory:").unwrap();
+    let dir = dossier_temp("quarantine");
+    let mut reglages = QuarantineService::reglages(&db).unwrap();
+    assert_eq!(reglages.deletion_mode, DeletionMode::Quarantine);
+    assert_eq!(reglages.retention_days, 30);
+    reglages.retention_days = 0;
+    assert!(QuarantineService::definir_reglages(&db, &reglages).is_err());
+    reglages.retention_days = 7;
+    reglages.quarantine_dir = dir.join("quarantine").to_string_lossy().to_string();
+    QuarantineService::definir_reglages(&db, &reglages).unwrap();
+    assert_eq!(QuarantineService::reglages(&db).unwrap(), reglages);
+    let junk = dir.join("info.nfo");
+    fs::write(&junk, b"junk").unwrap();
+
+    let mut tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    tx.supprimer(&junk, "Extension .nfo hors liste blanche").unwrap();
+    tx.valider().unwrap();
+    assert!(!junk.exists());
+
+    let entries = QuarantineService::lister(&db).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].size, 4);
+    assert_eq!(entries[0].reason, "Extension .nfo hors liste blanche");
+
+    JournalService::annuler_derniere(&db).unwrap();
+    assert!(junk.exists());
+    assert!(QuarantineService::lister(&db).unwrap().is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// FLAC mono minimal (une seconde de silence) pour écrire des tags
+fn ecrire_flac(path: &std::path::Path) {
+    let sortie = std::io::BufWriter::new(fs::File::create(path).unwrap());
+    let mut flac = flac_encoder::EncodeurFlac::new(sortie, 8000, 1, 16, 0).unwrap();
+    flac.ecrire(&[0; 8000]).unwrap();
+    flac.terminer().unwrap();
+}
+
+#[test]
+fn test_annuler_tags_retire_les_champs_ajoutes() {
+    let db = Database::open(":memory:").unwrap();
+    let dir = dossier_temp("journal_tags");
+    let path = dir.join("track.flac");
+    ecrire_flac(&path);
+    let audio = AudioService::new();
+    let mut track = audio.lire_metadonnees(&path.to_string_lossy()).unwrap();
+    track.title = "Avant".to_string();
+    audio.ecrire_metadonnees(&track).unwrap();
+
+    // L'opération ajoute un genre et une année, absents auparavant
+    track.title = "Après".to_string();
+    track.genre = Some("Jazz".to_string());
+    track.year = Some(2001);
+    let mut tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    tx.ecrire_tags(&audio, &track).unwrap();
+    tx.valider().unwrap();
+
+    JournalService::annuler_derniere(&db).unwrap().unwrap();
+    let relu = audio.lire_metadonnees(&path.to_string_lossy()).unwrap();
+    assert_eq!(relu.title, "Avant");
+    assert_eq!(relu.genre, None);
+    assert_eq!(relu.year, None);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_operation_en_cours_non_annulable() {
+    let db = Database::open(":memory:").unwrap();
+    let _tx = Transaction::demarrer(&db, "test", "/album").unwrap();
+    assert!(JournalService::annuler_derniere(&db).unwrap().is_none());
+    assert_eq!(JournalService::lister(&db).unwrap()[0].status, "en_cours");
+}
+
+#[test]
+fn test_suppression_definitive_non_annulable() {
+    let db = Database::open(":memory:").unwrap();
+    SettingsService::ecrire(&db, quarantine::SETTING_DELETION_MODE, "permanent").unwrap();
+    let dir = dossier_temp("permanent");
+    let junk = dir.join("info.nfo");
+    fs::write(&junk, b"junk").unwrap();
+
+    let mut tx = Transaction::demarrer(&db, "test", &dir.to_string_lossy()).unwrap();
+    tx.supprimer(&junk, "Extension .nfo hors liste blanche").unwrap();
+    tx.valider().unwrap();
+
+    assert!(!JournalService::lister(&db).unwrap()[0].undoable);
+    assert!(JournalService::annuler_derniere(&db).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
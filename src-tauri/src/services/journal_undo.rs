@@ -0,0 +1,104 @@
+use super::journal::{verrouiller, STEP_DELETE, STEP_GAIN, STEP_RENAME, STEP_TAGS};
+use super::JournalService;
+use crate::db::Database;
+use crate::models::{AppError, ReplayGainTags, Track};
+use crate::services::{AudioService, IOService, QuarantineService};
+use rusqlite::params;
+use std::fs;
+use std::path::Path;
+
+// Rejeu des étapes du journal, séparé de journal.rs pour limiter la taille du fichier.
+// Chaque étape annulée est marquée : une annulation interrompue reprend là où elle s'est arrêtée.
+
+pub(super) struct JournalStep {
+    pub(super) id: i64,
+    pub(super) kind: String,
+    pub(super) path: String,
+    target: Option<String>,
+    snapshot: Option<String>,
+}
+
+impl JournalService {
+    pub(super) fn annuler_etape(
+        db: &Database,
+        step: &JournalStep,
+        audio: &AudioService,
+    ) -> Result<(), AppError> {
+        let original = Path::new(&step.path);
+        match step.kind.as_str() {
+            STEP_RENAME => {
+                let current = step
+                    .target
+                    .as_deref()
+                    .ok_or_else(|| AppError::Unknown("Étape sans cible".into()))?;
+                // Le dossier d'origine a pu être supprimé s'il était devenu vide
+                if let Some(parent) = original.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                IOService::move_file(Path::new(current), original)
+            }
+            STEP_DELETE => {
+                let stored = step.target.as_deref().ok_or_else(|| {
+                    AppError::Io("Fichier supprimé définitivement, restauration impossible".into())
+                })?;
+                QuarantineService::restaurer_depuis(db, stored)
+            }
+            STEP_TAGS => {
+                let json = step
+                    .snapshot
+                    .as_deref()
+                    .ok_or_else(|| AppError::Unknown("Étape sans sauvegarde de tags".into()))?;
+                let track: Track =
+                    serde_json::from_str(json).map_err(|e| AppError::Unknown(e.to_string()))?;
+                audio.restaurer_metadonnees(&track)
+            }
+            STEP_GAIN => {
+                let json = step
+                    .snapshot
+                    .as_deref()
+                    .ok_or_else(|| AppError::Unknown("Étape sans sauvegarde de gains".into()))?;
+                let gains: ReplayGainTags =
+                    serde_json::from_str(json).map_err(|e| AppError::Unknown(e.to_string()))?;
+                audio.restaurer_gain(&step.path, &gains)
+            }
+            other => Err(AppError::Unknown(format!("Étape inconnue: {}", other))),
+        }
+    }
+
+    /// Étapes de l'opération qui n'ont pas encore été annulées, dans l'ordre d'exécution
+    pub(super) fn charger_etapes(
+        db: &Database,
+        operation_id: i64,
+    ) -> Result<Vec<JournalStep>, AppError> {
+        let conn = verrouiller(db)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, path, target, snapshot FROM journal_steps
+             WHERE operation_id = ?1 AND undone = 0 ORDER BY seq",
+        )?;
+
+        let rows = stmt.query_map(params![operation_id], |row| {
+            Ok(JournalStep {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                path: row.get(2)?,
+                target: row.get(3)?,
+                snapshot: row.get(4)?,
+            })
+        })?;
+
+        let mut steps = Vec::new();
+        for row in rows {
+            steps.push(row?);
+        }
+        Ok(steps)
+    }
+
+    pub(super) fn marquer_annulee(db: &Database, step_id: i64) -> Result<(), AppError> {
+        let conn = verrouiller(db)?;
+        conn.execute(
+            "UPDATE journal_steps SET undone = 1 WHERE id = ?1",
+            params![step_id],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use super::ffmpeg::FfmpegService;
+use super::journal::Transaction;
+use super::pcm::Pcm;
+use super::r128::{self, MesureR128};
+use super::AudioService;
+use crate::db::Database;
+use crate::models::{Album, AlbumLoudness, AppError, LoudnessProgress, TrackLoudness};
+use lofty::{AudioFile, Probe};
+use rodio::{Decoder, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Référence ReplayGain 2.0, alignée sur EBU R128 à +5 LU
+pub const REFERENCE_REPLAYGAIN_LUFS: f64 = -18.0;
+// Taille des paquets d'échantillons transmis à la mesure
+const PAQUET: usize = 8192;
+// Fréquence de décodage d'Opus, qui n'en connaît pas d'autre en interne
+const TAUX_FFMPEG: u32 = 48_000;
+
+/// Analyse de sonie EBU R128 et écriture des tags de gain
+pub struct LoudnessService;
+
+impl LoudnessService {
+    /// Mesure chaque piste puis l'album entier ; `progression` est appelé avant chaque piste.
+    /// Une piste illisible est signalée dans `errors` sans interrompre l'album.
+    pub fn analyser_album(
+        album: &Album,
+        mut progression: impl FnMut(LoudnessProgress),
+    ) -> Result<AlbumLoudness, AppError> {
+        let total = album.tracks.len();
+        let mut tracks = Vec::new();
+        let mut errors = Vec::new();
+        let mut blocs_album = Vec::new();
+
+        for (i, track) in album.tracks.iter().enumerate() {
+            progression(LoudnessProgress {
+                current: i + 1,
+                total,
+                path: track.path.clone(),
+            });
+            let mesure = match Self::mesurer(Path::new(&track.path)) {
+                Ok(mesure) => mesure,
+                Err(e) => {
+                    errors.push(format!("{}: {}", track.filename, e));
+                    continue;
+                }
+            };
+            let integrated_lufs = r128::sonie_integree(mesure.blocs());
+            blocs_album.extend_from_slice(mesure.blocs());
+            tracks.push(TrackLoudness {
+                path: track.path.clone(),
+                integrated_lufs,
+                true_peak: mesure.true_peak(),
+                track_gain_db: REFERENCE_REPLAYGAIN_LUFS - integrated_lufs,
+            });
+        }
+        if tracks.is_empty() {
+            return Err(AppError::Audio(format!(
+                "Aucune piste mesurable: {}",
+                errors.join(", ")
+            )));
+        }
+
+        let integrated_lufs = r128::sonie_integree(&blocs_album);
+        Ok(AlbumLoudness {
+            album_path: album.path.clone(),
+            integrated_lufs,
+            album_peak: tracks.iter().map(|t| t.true_peak).fold(0.0, f64::max),
+            album_gain_db: REFERENCE_REPLAYGAIN_LUFS - integrated_lufs,
+            tracks,
+            errors,
+            tags_written: false,
+        })
+    }
+
+    /// Écrit les gains des pistes mesurées dans une opération du journal, annulable d'un bloc
+    pub fn ecrire_tags(db: &Database, resultat: &mut AlbumLoudness) -> Result<(), AppError> {
+        let audio = AudioService::new();
+        let mut tx = Transaction::demarrer(db, "replaygain", &resultat.album_path)?;
+        for track in &resultat.tracks {
+            if let Err(e) = tx.ecrire_gain(&audio, track, resultat) {
+                return Err(tx.annuler_apres(e));
+            }
+        }
+        tx.valider()?;
+        resultat.tags_written = true;
+        Ok(())
+    }
+
+    /// Décode la piste avec la pile de lecture et la mesure en continu ; Opus et AAC, que
+    /// la pile de lecture ne décode pas, passent par ffmpeg
+    pub fn mesurer(path: &Path) -> Result<MesureR128, AppError> {
+        if !Pcm::decodable(path) {
+            return Self::mesurer_ffmpeg(path);
+        }
+        let file =
+            File::open(path).map_err(|e| AppError::Io(format!("Erreur lecture fichier: {}", e)))?;
+        let decoder = Decoder::new(BufReader::new(file))
+            .map_err(|e| AppError::Audio(format!("Format non décodable: {}", e)))?;
+
+        let channels = usize::from(decoder.channels());
+        let mut mesure = MesureR128::new(channels, decoder.sample_rate());
+        let mut paquet = Vec::with_capacity(PAQUET * channels);
+        for sample in decoder.convert_samples::<f32>() {
+            paquet.push(sample);
+            if paquet.len() == paquet.capacity() {
+                mesure.ajouter(&paquet);
+                paquet.clear();
+            }
+        }
+        mesure.ajouter(&paquet);
+        Ok(mesure)
+    }
+
+    fn mesurer_ffmpeg(path: &Path) -> Result<MesureR128, AppError> {
+        // Nombre de canaux d'origine : un mixage stéréo fausserait la pondération des canaux
+        let canaux = Probe::open(path)
+            .and_then(|probe| probe.read())
+            .ok()
+            .and_then(|fichier| fichier.properties().channels())
+            .map_or(2, u16::from);
+        let mut mesure = MesureR128::new(usize::from(canaux), TAUX_FFMPEG);
+        FfmpegService::decoder(
+            &FfmpegService::detecter(),
+            &path.to_string_lossy(),
+            canaux,
+            TAUX_FFMPEG,
+            |echantillons| mesure.ajouter(echantillons),
+        )
+        .map_err(AppError::Audio)?;
+        Ok(mesure)
+    }
+}
@@ -0,0 +1,203 @@
+use super::audio_gain::replaygain_en_r128;
+use super::test_fixtures::{dossier_temp, ecrire_wav, pcm16};
+use super::*;
+use crate::db::Database;
+use crate::models::{
+    Album, AlbumLoudness, ReplayGainSettings, ReplayGainTags, Track, TrackLoudness,
+};
+use lofty::{ItemKey, Probe, TaggedFileExt};
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// WAV PCM 16 bits stéréo 48 kHz : sinus 997 Hz sur les deux canaux
+fn ecrire_sinus(path: &Path, amplitude: f64, secondes: u32) {
+    let taux = 48_000u32;
+    let echantillons: Vec<i32> = (0..taux * secondes)
+        .flat_map(|n| {
+            let v = amplitude * (2.0 * PI * 997.0 * f64::from(n) / f64::from(taux)).sin();
+            [pcm16(v); 2]
+        })
+        .collect();
+    ecrire_wav(path, taux, 2, 16, &echantillons);
+}
+
+#[test]
+fn test_sonie_r128_et_gain_album() {
+    let dir = dossier_temp("loudness");
+    // Un sinus de -23 dBFS crête sur les deux canaux mesure -23 LUFS (EBU Tech 3341)
+    let fort = dir.join("01 - Fort.wav");
+    let faible = dir.join("02 - Faible.wav");
+    ecrire_sinus(&fort, 10f64.powf(-23.0 / 20.0), 3);
+    ecrire_sinus(&faible, 10f64.powf(-43.0 / 20.0), 3);
+
+    let mut album = Album::new(
+        dir.to_string_lossy().to_string(),
+        "Test".into(),
+        "Test".into(),
+    );
+    for path in [&fort, &faible] {
+        let nom = path.file_name().unwrap().to_string_lossy().to_string();
+        album
+            .tracks
+            .push(Track::new(path.to_string_lossy().to_string(), nom));
+    }
+
+    let mut etapes = Vec::new();
+    let resultat = LoudnessService::analyser_album(&album, |p| etapes.push(p.current)).unwrap();
+    assert_eq!(etapes, vec![1, 2]);
+
+    let piste = &resultat.tracks[0];
+    assert!((piste.integrated_lufs + 23.0).abs() < 0.2, "{:?}", piste);
+    assert!((piste.track_gain_db - 5.0).abs() < 0.2);
+    assert!((piste.true_peak - 10f64.powf(-23.0 / 20.0)).abs() < 0.002);
+    assert!((resultat.tracks[1].integrated_lufs + 43.0).abs() < 0.2);
+
+    // La piste faible passe sous la porte relative (-10 LU) de l'album : seule la forte compte
+    assert!(
+        (resultat.integrated_lufs + 23.0).abs() < 0.2,
+        "{:?}",
+        resultat
+    );
+    assert_eq!(resultat.album_peak, piste.true_peak);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_piste_illisible_et_gains_journalises() {
+    let db = Database::open(":memory:").unwrap();
+    let dir = dossier_temp("loudness_journal");
+    let piste = dir.join("01 - Piste.wav");
+    let illisible = dir.join("02 - Illisible.wav");
+    ecrire_sinus(&piste, 0.1, 1);
+    fs::write(&illisible, b"pas un fichier audio").unwrap();
+
+    let mut album = Album::new(
+        dir.to_string_lossy().to_string(),
+        "Test".into(),
+        "Test".into(),
+    );
+    for path in [&piste, &illisible] {
+        let nom = path.file_name().unwrap().to_string_lossy().to_string();
+        album
+            .tracks
+            .push(Track::new(path.to_string_lossy().to_string(), nom));
+    }
+
+    // La piste illisible est signalée, l'album est mesuré sans elle
+    let mut resultat = LoudnessService::analyser_album(&album, |_| {}).unwrap();
+    assert_eq!(resultat.tracks.len(), 1);
+    assert_eq!(resultat.errors.len(), 1);
+    assert!(resultat.errors[0].starts_with("02 - Illisible.wav"));
+
+    // Les gains écrits passent par le journal et s'annulent comme les autres tags
+    LoudnessService::ecrire_tags(&db, &mut resultat).unwrap();
+    assert!(resultat.tags_written);
+    let chemin = piste.to_string_lossy().to_string();
+    let audio = AudioService::new();
+    assert!(audio.lire_gain(&chemin).unwrap().track_gain_db.is_some());
+    JournalService::annuler_derniere(&db).unwrap().unwrap();
+    assert_eq!(audio.lire_gain(&chemin).unwrap(), ReplayGainTags::default());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_gains_r128_opus() {
+    // Q7.8 relatif à -23 LUFS : +5 dB ReplayGain (-18 LUFS) valent 0
+    assert_eq!(replaygain_en_r128(5.0), 0);
+    assert_eq!(replaygain_en_r128(-1.0), -1536);
+    assert_eq!(replaygain_en_r128(200.0), i16::MAX);
+
+    let dir = dossier_temp("loudness_opus");
+    let wav = dir.join("source.wav");
+    let opus = dir.join("01 - Piste.opus");
+    ecrire_sinus(&wav, 10f64.powf(-23.0 / 20.0), 3);
+    let info = FfmpegService::detecter();
+    let args = ["-codec:a", "libopus", "-b:a", "192k"].map(String::from);
+    let encode = FfmpegService::executer(
+        &info,
+        &wav.to_string_lossy(),
+        &args,
+        &opus.to_string_lossy(),
+    );
+    if let Err(e) = encode {
+        // Sans ffmpeg, ni encodage ni mesure d'Opus : l'erreur le dit
+        assert!(e.contains("ffmpeg"), "{}", e);
+        fs::copy(&wav, &opus).unwrap();
+        let mesure = LoudnessService::mesurer(&opus);
+        assert!(matches!(mesure, Err(e) if e.to_string().contains("ffmpeg")));
+        fs::remove_dir_all(&dir).unwrap();
+        return;
+    }
+
+    let mesure = LoudnessService::mesurer(&opus).unwrap();
+    let lufs = r128::sonie_integree(mesure.blocs());
+    assert!((lufs + 23.0).abs() < 0.5, "{}", lufs);
+
+    let chemin = opus.to_string_lossy().to_string();
+    let piste = TrackLoudness {
+        path: chemin.clone(),
+        integrated_lufs: -23.0,
+        true_peak: 0.07,
+        track_gain_db: 5.0,
+    };
+    let album = AlbumLoudness {
+        album_path: dir.to_string_lossy().to_string(),
+        integrated_lufs: -22.0,
+        album_peak: 0.07,
+        album_gain_db: 4.0,
+        tracks: vec![],
+        errors: vec![],
+        tags_written: false,
+    };
+    let audio = AudioService::new();
+    audio.ecrire_gain(&piste, &album).unwrap();
+
+    let fichier = Probe::open(&opus).unwrap().read().unwrap();
+    let tag = fichier.primary_tag().unwrap();
+    let brut = |nom: &str| {
+        tag.get_string(&ItemKey::Unknown(nom.to_string()))
+            .map(String::from)
+    };
+    assert_eq!(brut("R128_TRACK_GAIN").as_deref(), Some("0"));
+    assert_eq!(brut("R128_ALBUM_GAIN").as_deref(), Some("-256"));
+    assert!(tag.get_string(&ItemKey::ReplayGainTrackGain).is_none());
+    let relus = audio.lire_gain(&chemin).unwrap();
+    assert_eq!(relus.track_gain_db, Some(5.0));
+    assert_eq!(relus.album_gain_db, Some(4.0));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_facteur_replaygain() {
+    let tags = ReplayGainTags {
+        track_gain_db: Some(-6.0),
+        track_peak: Some(0.5),
+        album_gain_db: Some(6.0),
+        album_peak: Some(0.9),
+    };
+    let reglages = ReplayGainSettings::default();
+    let db = |f: f32| 20.0 * f.log10();
+
+    // Mode auto : gain de piste hors album, gain d'album limité par son pic depuis un album
+    assert!((db(ReplayGainService::facteur(&tags, &reglages, false)) + 6.0).abs() < 0.01);
+    let album = ReplayGainService::facteur(&tags, &reglages, true);
+    assert!((album - 1.0 / 0.9).abs() < 1e-4);
+
+    let sans_protection = ReplayGainSettings {
+        prevent_clipping: false,
+        preamp_db: 2.0,
+        ..reglages.clone()
+    };
+    assert!((db(ReplayGainService::facteur(&tags, &sans_protection, true)) - 8.0).abs() < 0.01);
+
+    // Sans tags : volume inchangé par défaut, gain de repli choisi sinon, sans préampli
+    let sans_tags = ReplayGainTags::default();
+    assert_eq!(ReplayGainService::facteur(&sans_tags, &reglages, true), 1.0);
+    let avec_repli = ReplayGainSettings {
+        fallback_db: -6.0,
+        ..reglages.clone()
+    };
+    let repli = ReplayGainService::facteur(&sans_tags, &avec_repli, true);
+    assert!((db(repli) + 6.0).abs() < 0.01);
+}
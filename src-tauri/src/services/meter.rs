@@ -0,0 +1,203 @@
+use crate::models::{MeterFrame, METER_BANDS};
+use rodio::Source;
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TAILLE_FFT: usize = 2048;
+// Trames accumulées par la prise avant de verrouiller l'analyseur
+const TRAMES_PAR_LOT: usize = 256;
+const PLANCHER_DB: f32 = -90.0;
+
+/// Mesures accumulées entre deux relevés
+#[derive(Default)]
+struct Accumulateur {
+    channels: usize,
+    sample_rate: u32,
+    peak: Vec<f32>,
+    carres: Vec<f64>,
+    trames: u64,
+    mono: VecDeque<f32>, // Dernières TAILLE_FFT trames, canaux mélangés
+}
+
+/// Vu-mètre et spectre partagés entre la chaîne audio et le thread du lecteur ; inactif par défaut
+#[derive(Default)]
+pub struct Analyseur {
+    actif: AtomicBool,
+    mesures: Mutex<Accumulateur>,
+    fft: Mutex<Option<Arc<dyn Fft<f32>>>>,
+}
+
+impl Analyseur {
+    pub fn activer(&self, actif: bool) {
+        self.actif.store(actif, Ordering::Relaxed);
+        if let Ok(mut m) = self.mesures.lock() {
+            *m = Accumulateur::default();
+        }
+    }
+
+    pub fn est_actif(&self) -> bool {
+        self.actif.load(Ordering::Relaxed)
+    }
+
+    fn deposer(&self, channels: usize, sample_rate: u32, lot: &[f32]) {
+        let Ok(mut m) = self.mesures.lock() else {
+            return;
+        };
+        if m.channels != channels || m.sample_rate != sample_rate {
+            *m = Accumulateur {
+                channels,
+                sample_rate,
+                peak: vec![0.0; channels],
+                carres: vec![0.0; channels],
+                ..Accumulateur::default()
+            };
+        }
+        for trame in lot.chunks_exact(channels) {
+            for (canal, &x) in trame.iter().enumerate() {
+                m.peak[canal] = m.peak[canal].max(x.abs());
+                m.carres[canal] += f64::from(x * x);
+            }
+            if m.mono.len() == TAILLE_FFT {
+                m.mono.pop_front();
+            }
+            m.mono
+                .push_back(trame.iter().sum::<f32>() / channels as f32);
+        }
+        m.trames += (lot.len() / channels) as u64;
+    }
+
+    /// Crête et RMS depuis le relevé précédent, spectre des dernières trames ; None sans nouvel échantillon
+    pub fn relever(&self) -> Option<MeterFrame> {
+        let (peak, carres, trames, sample_rate, mono) = {
+            let mut m = self.mesures.lock().ok()?;
+            if m.trames == 0 {
+                return None;
+            }
+            let trames = std::mem::take(&mut m.trames);
+            let peak = m.peak.iter_mut().map(std::mem::take).collect::<Vec<_>>();
+            let carres = m.carres.iter_mut().map(std::mem::take).collect::<Vec<_>>();
+            (peak, carres, trames, m.sample_rate, m.mono.clone())
+        };
+
+        Some(MeterFrame {
+            peak_db: peak.iter().map(|&p| en_db(p)).collect(),
+            rms_db: carres
+                .iter()
+                .map(|&c| en_db((c / trames as f64).sqrt() as f32))
+                .collect(),
+            bands_db: self.spectre(&mono, sample_rate),
+        })
+    }
+
+    fn spectre(&self, mono: &VecDeque<f32>, sample_rate: u32) -> Vec<f32> {
+        if mono.len() < TAILLE_FFT || sample_rate == 0 {
+            return vec![PLANCHER_DB; METER_BANDS];
+        }
+        let fft = {
+            let Ok(mut plan) = self.fft.lock() else {
+                return vec![PLANCHER_DB; METER_BANDS];
+            };
+            plan.get_or_insert_with(|| FftPlanner::new().plan_fft_forward(TAILLE_FFT))
+                .clone()
+        };
+        // Fenêtre de Hann ; un sinus d'amplitude A donne |X| = A·N/4 à son pic
+        let mut tampon: Vec<Complex<f32>> = mono
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let w = 0.5 - 0.5 * (2.0 * PI * i as f32 / (TAILLE_FFT - 1) as f32).cos();
+                Complex::new(x * w, 0.0)
+            })
+            .collect();
+        fft.process(&mut tampon);
+
+        let resolution = sample_rate as f32 / TAILLE_FFT as f32;
+        let haut = 20000f32.min(sample_rate as f32 / 2.0);
+        (0..METER_BANDS)
+            .map(|b| {
+                let bord = |k: usize| 20.0 * (haut / 20.0).powf(k as f32 / METER_BANDS as f32);
+                let debut = ((bord(b) / resolution) as usize).max(1);
+                let fin = ((bord(b + 1) / resolution) as usize).clamp(debut, TAILLE_FFT / 2 - 1);
+                let max = tampon[debut..=fin]
+                    .iter()
+                    .map(|c| c.norm())
+                    .fold(0.0f32, f32::max);
+                en_db(max * 4.0 / TAILLE_FFT as f32)
+            })
+            .collect()
+    }
+}
+
+fn en_db(lineaire: f32) -> f32 {
+    let db = (20.0 * lineaire.max(1e-9).log10()).max(PLANCHER_DB);
+    (db * 10.0).round() / 10.0
+}
+
+/// Prise dans la chaîne de lecture : transmet les échantillons tels quels et alimente l'analyseur s'il est actif
+pub struct Prise<S> {
+    source: S,
+    analyseur: Arc<Analyseur>,
+    lot: Vec<f32>,
+    canal: usize, // Position dans la trame de l'échantillon suivant
+}
+
+impl<S: Source<Item = f32>> Prise<S> {
+    pub fn new(source: S, analyseur: Arc<Analyseur>) -> Self {
+        Self {
+            lot: Vec::with_capacity(TRAMES_PAR_LOT * usize::from(source.channels().max(1))),
+            source,
+            analyseur,
+            canal: 0,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Prise<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let x = self.source.next()?;
+        let channels = usize::from(self.source.channels().max(1));
+        let debut_trame = self.canal == 0;
+        self.canal = (self.canal + 1) % channels;
+        // Un lot n'est commencé qu'en début de trame, et seulement si quelqu'un écoute
+        if !self.lot.is_empty() || (debut_trame && self.analyseur.est_actif()) {
+            self.lot.push(x);
+            if self.lot.len() == self.lot.capacity() {
+                self.analyseur
+                    .deposer(channels, self.source.sample_rate(), &self.lot);
+                self.lot.clear();
+            }
+        }
+        Some(x)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Prise<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), rodio::source::SeekError> {
+        self.lot.clear();
+        self.canal = 0;
+        self.source.try_seek(pos)
+    }
+}
@@ -0,0 +1,41 @@
+use super::meter::{Analyseur, Prise};
+use crate::models::METER_BANDS;
+use rodio::source::{SineWave, Source};
+use std::sync::Arc;
+
+#[test]
+fn test_vu_metre_et_spectre() {
+    let analyseur = Arc::new(Analyseur::default());
+    let sinus = || {
+        SineWave::new(1000.0)
+            .amplify(0.5)
+            .take_duration(std::time::Duration::from_millis(250))
+    };
+
+    // Coupé : la prise laisse passer le signal sans rien mesurer
+    let sorti: Vec<f32> = Prise::new(sinus(), analyseur.clone()).collect();
+    assert_eq!(sorti, sinus().collect::<Vec<f32>>());
+    assert!(analyseur.relever().is_none());
+
+    analyseur.activer(true);
+    Prise::new(sinus(), analyseur.clone()).for_each(drop);
+    let releve = analyseur.relever().unwrap();
+    assert_eq!(releve.peak_db.len(), 1);
+    // Sinus à -6 dBFS crête : RMS 3 dB plus bas
+    assert!((releve.peak_db[0] + 6.0).abs() < 0.2);
+    assert!((releve.rms_db[0] + 9.0).abs() < 0.2);
+
+    // La bande la plus forte contient 1 kHz et se lit à -6 dB
+    assert_eq!(releve.bands_db.len(), METER_BANDS);
+    let (bande, niveau) = releve
+        .bands_db
+        .iter()
+        .enumerate()
+        .fold((0, f32::MIN), |m, (i, &v)| if v > m.1 { (i, v) } else { m });
+    let bord = |k: usize| 20.0 * 1000f32.powf(k as f32 / METER_BANDS as f32);
+    assert!(bord(bande) <= 1000.0 && 1000.0 < bord(bande + 1));
+    assert!((niveau + 6.0).abs() < 1.0);
+
+    // Relevé consommé : rien de neuf tant qu'aucun échantillon ne passe
+    assert!(analyseur.relever().is_none());
+}
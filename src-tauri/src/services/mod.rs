@@ -1,30 +1,127 @@
 pub mod audio;
+pub mod audio_gain;
+pub mod biquad;
 pub mod cleaner;
+pub mod cleaner_plan;
+pub mod cleaning_rules;
+pub mod compilation;
+pub mod consistency;
 pub mod converter;
 pub mod cover;
 pub mod dictionaries;
+pub mod eq_import;
+pub mod eq_presets;
 pub mod equalizer;
 pub mod exception;
+pub mod ffmpeg;
+pub mod flac_encoder;
+pub mod flac_subframe;
+pub mod gapless;
 pub mod inspector;
+pub mod integrity;
 pub mod io;
+pub mod journal;
+mod journal_undo;
+pub mod loudness;
+pub mod meter;
 pub mod musicbrainz;
+pub mod numbering;
+pub mod planner;
 pub mod player;
+pub mod player_engine;
+pub mod player_events;
+pub mod player_output;
+pub mod player_queue;
+pub mod player_source;
+pub mod player_seek;
+pub mod player_transition;
+pub mod player_transport;
+pub mod pcm;
 pub mod playlist;
 pub mod processor;
+pub mod quarantine;
+pub mod r128;
+pub mod render;
 pub mod renamer;
+pub mod replaygain;
 pub mod scanner;
+pub mod settings;
+pub mod silence;
+pub mod spectral;
+pub mod spectrogram;
+pub mod time_stretch;
 pub mod validator;
+pub mod validator_consistency;
+pub mod validator_integrity;
+pub mod validator_numbering;
+pub mod validator_rules;
+pub mod validator_silence;
+pub mod validator_spectral;
+pub mod wav_trim;
+pub mod waveform;
 
+#[cfg(test)]
+mod converter_tests;
+#[cfg(test)]
+mod equalizer_tests;
+#[cfg(test)]
+mod integrity_tests;
+#[cfg(test)]
+mod journal_tests;
+#[cfg(test)]
+mod loudness_tests;
+#[cfg(test)]
+mod meter_tests;
+#[cfg(test)]
+mod planner_tests;
+#[cfg(test)]
+mod player_output_tests;
+#[cfg(test)]
+mod player_queue_tests;
+#[cfg(test)]
+mod player_transport_tests;
 #[cfg(test)]
 mod processor_tests;
+#[cfg(test)]
+mod render_tests;
+#[cfg(test)]
+mod silence_tests;
+#[cfg(test)]
+mod spectral_tests;
+#[cfg(test)]
+mod test_fixtures;
+#[cfg(test)]
+mod validator_tests;
+#[cfg(test)]
+mod waveform_tests;
 
 pub use audio::AudioService;
 pub use cleaner::CleanerService;
+pub use cleaning_rules::CleaningRuleService;
+pub use compilation::CompilationService;
+pub use consistency::ConsistencyService;
+pub use eq_import::EqImportService;
+pub use eq_presets::EqPresetService;
 pub use exception::ExceptionService;
+pub use ffmpeg::FfmpegService;
+pub use gapless::GaplessService;
 pub use inspector::InspectorService;
+pub use integrity::IntegrityService;
 pub use io::IOService;
+pub use journal::JournalService;
+pub use loudness::LoudnessService;
 pub use musicbrainz::MusicBrainzService;
+pub use numbering::NumberingService;
+pub use planner::PlannerService;
+pub use player_output::OutputService;
 pub use processor::MetadataProcessorService;
+pub use quarantine::QuarantineService;
 pub use renamer::RenamerService;
+pub use render::RenderService;
+pub use replaygain::ReplayGainService;
 pub use scanner::ScannerService;
+pub use settings::SettingsService;
+pub use silence::SilenceService;
+pub use spectral::SpectralService;
 pub use validator::ValidatorService;
+pub use waveform::WaveformService;
@@ -0,0 +1,101 @@
+use crate::models::{Album, RenumberMode, Track};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+lazy_static! {
+    // Préfixe numérique d'un nom de fichier : "01 - ", "101. ", "3_"...
+    static ref RE_PREFIXE: Regex = Regex::new(r"^(\d{1,3})(\s*[-._]\s*|\s+)").unwrap();
+}
+
+pub struct NumberingService;
+
+impl NumberingService {
+    pub fn prefixe_fichier(filename: &str) -> Option<u32> {
+        RE_PREFIXE
+            .captures(filename)
+            .and_then(|c| c[1].parse().ok())
+    }
+
+    /// Le préfixe du fichier correspond-il au tag ? Accepte "NN" et "DNN" (disque + piste).
+    /// Un fichier sans préfixe ou une piste sans numéro ne sont pas en désaccord.
+    pub fn prefixe_correspond(track: &Track) -> bool {
+        let (Some(prefixe), Some(numero)) =
+            (Self::prefixe_fichier(&track.filename), track.track_number)
+        else {
+            return true;
+        };
+        prefixe == numero || prefixe == track.disc_number.unwrap_or(1) * 100 + numero
+    }
+
+    /// Pistes regroupées par disque (sans numéro de disque = disque 1)
+    pub fn par_disque(tracks: &[Track]) -> BTreeMap<u32, Vec<&Track>> {
+        let mut disques: BTreeMap<u32, Vec<&Track>> = BTreeMap::new();
+        for track in tracks {
+            disques
+                .entry(track.disc_number.unwrap_or(1))
+                .or_default()
+                .push(track);
+        }
+        disques
+    }
+
+    /// Renumérote les pistes 1..n par disque, met à jour le total et le préfixe des fichiers
+    pub fn renumeroter(album: &mut Album, mode: RenumberMode) {
+        let mut ordre: Vec<usize> = (0..album.tracks.len()).collect();
+        // Tri naturel : "2 - x" avant "10 - x", le nom départage les fichiers sans numéro
+        let cle_fichier = |t: &Track| {
+            (
+                Self::prefixe_fichier(&t.filename).unwrap_or(u32::MAX),
+                t.filename.to_lowercase(),
+            )
+        };
+        ordre.sort_by(|&a, &b| {
+            let (ta, tb) = (&album.tracks[a], &album.tracks[b]);
+            let disque = ta
+                .disc_number
+                .unwrap_or(1)
+                .cmp(&tb.disc_number.unwrap_or(1));
+            let suite = match mode {
+                RenumberMode::FilenameOrder => cle_fichier(ta).cmp(&cle_fichier(tb)),
+                RenumberMode::DiscTrackOrder => {
+                    let numero = |t: &Track| t.track_number.filter(|&n| n > 0).unwrap_or(u32::MAX);
+                    numero(ta)
+                        .cmp(&numero(tb))
+                        .then_with(|| cle_fichier(ta).cmp(&cle_fichier(tb)))
+                }
+            };
+            disque.then(suite)
+        });
+
+        let mut totaux: BTreeMap<u32, u32> = BTreeMap::new();
+        for track in &album.tracks {
+            *totaux.entry(track.disc_number.unwrap_or(1)).or_default() += 1;
+        }
+
+        let mut compteurs: BTreeMap<u32, u32> = BTreeMap::new();
+        for index in ordre {
+            let track = &mut album.tracks[index];
+            let disque = track.disc_number.unwrap_or(1);
+            let numero = compteurs.entry(disque).or_default();
+            *numero += 1;
+            let numero = *numero;
+            let total = totaux[&disque];
+
+            let filename = match RE_PREFIXE.find(&track.filename) {
+                Some(m) => format!("{:02} - {}", numero, &track.filename[m.end()..]),
+                None => format!("{:02} - {}", numero, track.filename),
+            };
+
+            if track.track_number != Some(numero)
+                || track.track_total != Some(total)
+                || track.filename != filename
+            {
+                track.track_number = Some(numero);
+                track.track_total = Some(total);
+                track.filename = filename;
+                track.is_modified = true;
+            }
+        }
+    }
+}
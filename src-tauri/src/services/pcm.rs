@@ -0,0 +1,155 @@
+use crate::models::AppError;
+use rodio::{Decoder, Source};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+enum Entree {
+    Flac(claxon::FlacReader<File>),
+    Wav(hound::WavIntoSamples<BufReader<File>, i32>),
+    Decodee(Box<Decoder<BufReader<File>>>), // Autres formats : échantillons 16 bits du décodeur rodio
+}
+
+/// Échantillons entiers d'un fichier, à leur résolution d'origine quand le format la conserve
+pub struct Pcm {
+    pub rate: u32,
+    pub channels: u16,
+    pub bits: u32,
+    entree: Entree,
+    attente: Vec<i32>, // Échantillons entrelacés décodés mais pas encore lus
+    lus: usize,
+}
+
+impl Pcm {
+    pub fn ouvrir(path: &Path) -> Result<Self, AppError> {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let (rate, channels, bits, entree) = match extension.as_str() {
+            "flac" => {
+                let reader = claxon::FlacReader::open(path)
+                    .map_err(|e| AppError::Audio(format!("FLAC illisible : {}", e)))?;
+                let info = reader.streaminfo();
+                (
+                    info.sample_rate,
+                    info.channels as u16,
+                    info.bits_per_sample,
+                    Entree::Flac(reader),
+                )
+            }
+            // Les WAV flottants passent par le décodeur générique
+            "wav" if Self::wav_entier(path) => {
+                let reader = hound::WavReader::open(path)
+                    .map_err(|e| AppError::Audio(format!("WAV illisible : {}", e)))?;
+                let spec = reader.spec();
+                (
+                    spec.sample_rate,
+                    spec.channels,
+                    u32::from(spec.bits_per_sample),
+                    Entree::Wav(reader.into_samples()),
+                )
+            }
+            _ => {
+                let decoder = Decoder::new(BufReader::new(File::open(path)?))
+                    .map_err(|e| AppError::Audio(format!("Décodage impossible : {}", e)))?;
+                (
+                    decoder.sample_rate(),
+                    decoder.channels(),
+                    16,
+                    Entree::Decodee(Box::new(decoder)),
+                )
+            }
+        };
+        Ok(Self {
+            rate,
+            channels: channels.max(1),
+            bits,
+            entree,
+            attente: Vec::new(),
+            lus: 0,
+        })
+    }
+
+    /// rodio n'a de décodeur ni AAC ni Opus : ces fichiers ne sont pas décodés nativement
+    pub fn decodable(path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "m4a" | "mp4" | "aac" | "opus" => false,
+            "ogg" | "oga" => !Self::est_opus(path),
+            _ => true,
+        }
+    }
+
+    // Un flux Ogg Opus s'annonce par "OpusHead" dans le premier paquet
+    fn est_opus(path: &Path) -> bool {
+        let mut entete = [0u8; 128];
+        let lus = File::open(path)
+            .and_then(|mut f| f.read(&mut entete))
+            .unwrap_or(0);
+        entete[..lus].windows(8).any(|w| w == b"OpusHead")
+    }
+
+    fn wav_entier(path: &Path) -> bool {
+        hound::WavReader::open(path)
+            .is_ok_and(|r| r.spec().sample_format == hound::SampleFormat::Int)
+    }
+
+    /// Remplit `tampon` d'au plus `trames` trames entrelacées ; 0 en fin de fichier
+    pub fn lire(&mut self, tampon: &mut Vec<i32>, trames: usize) -> Result<usize, AppError> {
+        let channels = usize::from(self.channels);
+        tampon.clear();
+        while tampon.len() < trames * channels {
+            if self.lus < self.attente.len() {
+                let n = (trames * channels - tampon.len()).min(self.attente.len() - self.lus);
+                tampon.extend_from_slice(&self.attente[self.lus..self.lus + n]);
+                self.lus += n;
+                continue;
+            }
+            if !self.decoder()? {
+                break;
+            }
+        }
+        // Une trame incomplète en fin de fichier est abandonnée
+        tampon.truncate(tampon.len() - tampon.len() % channels);
+        Ok(tampon.len() / channels)
+    }
+
+    /// Décode le bloc suivant dans `attente` ; faux en fin de fichier
+    fn decoder(&mut self) -> Result<bool, AppError> {
+        self.attente.clear();
+        self.lus = 0;
+        match &mut self.entree {
+            Entree::Flac(reader) => {
+                let bloc = reader
+                    .blocks()
+                    .read_next_or_eof(Vec::new())
+                    .map_err(|e| AppError::Audio(format!("Erreur de décodage : {}", e)))?;
+                let Some(bloc) = bloc else {
+                    return Ok(false);
+                };
+                for i in 0..bloc.duration() as usize {
+                    for c in 0..bloc.channels() {
+                        self.attente.push(bloc.channel(c)[i]);
+                    }
+                }
+            }
+            Entree::Wav(samples) => {
+                for sample in samples.by_ref().take(4096) {
+                    self.attente.push(
+                        sample
+                            .map_err(|e| AppError::Audio(format!("Erreur de lecture : {}", e)))?,
+                    );
+                }
+            }
+            Entree::Decodee(decoder) => {
+                self.attente
+                    .extend(decoder.by_ref().take(4096).map(i32::from));
+            }
+        }
+        Ok(!self.attente.is_empty())
+    }
+}
@@ -0,0 +1,258 @@
+use crate::models::changeset::{FieldChange, FolderRename, TagDiff, TagField};
+use crate::models::{Album, AppError, ChangeSet, Track};
+use crate::services::inspector::RE_ANNEE_TITRE;
+use crate::services::{
+    AudioService, CleanerService, CleaningRuleService, CompilationService, RenamerService,
+};
+use std::path::{Component, Path};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+pub struct PlannerService;
+
+impl PlannerService {
+    /// Calcule le plan de correction d'un album sans rien modifier sur le disque
+    pub fn planifier(album: &Album) -> Result<ChangeSet, AppError> {
+        let album_path = album.path.trim_end_matches(['/', '\\']).to_string();
+        let root = Path::new(&album_path);
+        if !root.is_dir() {
+            return Err(AppError::Validation("Dossier invalide".to_string()));
+        }
+
+        let audio_service = AudioService::new();
+        let cleaner = CleanerService::new();
+        let mut plan = ChangeSet::new(album_path.clone(), Self::empreinte_dossier(root)?);
+
+        // 1. Dossier
+        plan.folder_rename = Self::planifier_dossier(album, root);
+
+        // 2. Fichiers (aplatissement + renommage) et 3. Tags, sur le nom final de chaque piste
+        let mut fichiers = cleaner.lister_fichiers(root);
+        plan.file_renames = cleaner.planifier_renommages(album, root, &mut fichiers);
+        for track in &album.tracks {
+            let Ok(relative) = Path::new(&track.path).strip_prefix(root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().to_string();
+            let file = plan
+                .file_renames
+                .iter()
+                .find(|r| r.from == relative)
+                .map_or(relative, |r| r.to.clone());
+
+            let sur_disque = audio_service.lire_metadonnees(&track.path)?;
+            let changes = Self::comparer_tags(&sur_disque, track);
+            if !changes.is_empty() {
+                plan.tag_diffs.push(TagDiff {
+                    file,
+                    changes,
+                    reason: "Métadonnées corrigées".to_string(),
+                });
+            }
+        }
+
+        // 4. Pochette puis 5. Nettoyage, sur l'état simulé après renommages
+        let regles = CleaningRuleService::actives();
+        let (moves, deletions) = cleaner.planifier_cover(&mut fichiers, &regles);
+        plan.cover_moves.extend(moves);
+        plan.deletions.extend(deletions);
+
+        let (moves, deletions) = cleaner.planifier_nettoyage(&mut fichiers, &regles);
+        plan.cover_moves.extend(moves);
+        plan.deletions.extend(deletions);
+
+        Ok(plan)
+    }
+
+    /// Vérifie un plan reçu du client avant exécution : il doit être identique au plan
+    /// recalculé côté serveur et tous ses chemins doivent rester dans le dossier de l'album
+    pub fn verifier_plan(album: &Album, plan: &ChangeSet) -> Result<(), AppError> {
+        if Self::planifier(album)? != *plan {
+            return Err(AppError::Validation(
+                "Le plan ne correspond plus à l'album, relancez l'aperçu".to_string(),
+            ));
+        }
+
+        let root = Path::new(&plan.album_path)
+            .canonicalize()
+            .map_err(|e| AppError::Io(format!("Dossier de l'album introuvable: {}", e)))?;
+        // Le dossier renommé reste à côté de l'ancien, sous un simple nom
+        if let Some(folder) = &plan.folder_rename {
+            let cible = Path::new(&folder.to);
+            let parent = cible.parent().and_then(|p| p.canonicalize().ok());
+            if cible.file_name().is_none() || parent.as_deref() != root.parent() {
+                return Err(AppError::Validation(format!(
+                    "Renommage de dossier hors du dossier parent refusé: {}",
+                    folder.to
+                )));
+            }
+        }
+
+        // Les chemins relatifs visent le contenu du dossier, qui ne change pas avec son nom
+        let chemins = plan
+            .file_renames
+            .iter()
+            .flat_map(|r| [&r.from, &r.to])
+            .chain(plan.cover_moves.iter().flat_map(|m| [&m.from, &m.to]))
+            .chain(plan.deletions.iter().map(|d| &d.path))
+            .chain(plan.tag_diffs.iter().map(|d| &d.file));
+        for chemin in chemins {
+            if !Self::dans_dossier(&root, chemin) {
+                return Err(AppError::Validation(format!(
+                    "Chemin hors de l'album refusé: {}",
+                    chemin
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Chemin relatif sans `..` ni racine, dont la partie existante ne sort pas de l'album
+    /// (un lien symbolique vers l'extérieur est refusé)
+    fn dans_dossier(root: &Path, relatif: &str) -> bool {
+        let relatif = Path::new(relatif);
+        if relatif.as_os_str().is_empty()
+            || !relatif
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return false;
+        }
+        root.join(relatif)
+            .ancestors()
+            .find(|a| a.exists())
+            .and_then(|a| a.canonicalize().ok())
+            .is_some_and(|c| c.starts_with(root))
+    }
+
+    fn planifier_dossier(album: &Album, root: &Path) -> Option<FolderRename> {
+        let years: Vec<u32> = album
+            .tracks
+            .iter()
+            .filter_map(|t| t.year)
+            .filter(|&y| y > 0)
+            .collect();
+        let year_min = years.iter().min().copied();
+        let year_max = years.iter().max().copied();
+
+        // Use the first track to determine Album Artist (heuristic), "Various Artists" for compilations
+        let first_track = album.tracks.first()?;
+        let artist = CompilationService::artiste_album(album);
+
+        // Strip the year prefix if present (to avoid double year like "(2023) (2023) Title")
+        let title_clean = RE_ANNEE_TITRE.replace(&album.title, "").to_string();
+
+        // Fallback to track album if album title is empty
+        let title = if title_clean.trim().is_empty() {
+            &first_track.album
+        } else {
+            &title_clean
+        };
+
+        let new_folder_name =
+            RenamerService::new().format_folder_name(&artist, title, year_min, year_max);
+        let new_path = root.parent()?.join(&new_folder_name);
+
+        // Only rename if different and target doesn't exist
+        if new_path == root || new_path.exists() {
+            return None;
+        }
+
+        Some(FolderRename {
+            from: root.to_string_lossy().to_string(),
+            to: new_path.to_string_lossy().to_string(),
+            reason: format!("Format \"(Année) Titre\" : {}", new_folder_name),
+        })
+    }
+
+    /// Empreinte du contenu du dossier (chemins, tailles, dates de modification)
+    pub fn empreinte_dossier(root: &Path) -> Result<String, AppError> {
+        let mut contenu = String::new();
+        for entry in WalkDir::new(root).sort_by_file_name() {
+            let entry = entry.map_err(|e| AppError::Io(e.to_string()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let metadata = entry.metadata().map_err(|e| AppError::Io(e.to_string()))?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            contenu.push_str(&format!(
+                "{}|{}|{}\n",
+                relative.to_string_lossy(),
+                metadata.len(),
+                modified
+            ));
+        }
+        Ok(format!("{:x}", md5::compute(contenu)))
+    }
+
+    /// Liste les champs de tag qui diffèrent entre l'état sur disque et l'état souhaité
+    pub fn comparer_tags(avant: &Track, apres: &Track) -> Vec<FieldChange> {
+        let champs = [
+            TagField::Title,
+            TagField::Artist,
+            TagField::AlbumArtist,
+            TagField::Album,
+            TagField::Year,
+            TagField::TrackNumber,
+            TagField::TrackTotal,
+            TagField::DiscNumber,
+            TagField::Genre,
+            TagField::Compilation,
+        ];
+
+        champs
+            .into_iter()
+            .map(|field| {
+                (
+                    field,
+                    Self::valeur_champ(avant, field),
+                    Self::valeur_champ(apres, field),
+                )
+            })
+            // ecrire_metadonnees ne sait pas effacer un champ optionnel : on ignore les retraits
+            .filter(|(_, old, new)| new.is_some() && old != new)
+            .map(|(field, old, new)| FieldChange { field, old, new })
+            .collect()
+    }
+
+    /// Valeur d'un champ de tag sous forme de texte (None si absent)
+    pub fn valeur_champ(track: &Track, field: TagField) -> Option<String> {
+        match field {
+            TagField::Title => Some(track.title.clone()),
+            TagField::Artist => Some(track.artist.clone()),
+            TagField::AlbumArtist => Some(track.album_artist.clone()),
+            TagField::Album => Some(track.album.clone()),
+            TagField::Year => track.year.map(|y| y.to_string()),
+            TagField::TrackNumber => track.track_number.map(|n| n.to_string()),
+            TagField::TrackTotal => track.track_total.map(|n| n.to_string()),
+            TagField::DiscNumber => track.disc_number.map(|n| n.to_string()),
+            TagField::Genre => track.genre.clone(),
+            TagField::Compilation => Some(if track.compilation { "1" } else { "0" }.to_string()),
+        }
+    }
+
+    /// Applique les modifications planifiées à une piste
+    pub fn appliquer_changements(track: &mut Track, changes: &[FieldChange]) {
+        for change in changes {
+            let value = change.new.clone().unwrap_or_default();
+            match change.field {
+                TagField::Title => track.title = value,
+                TagField::Artist => track.artist = value,
+                TagField::AlbumArtist => track.album_artist = value,
+                TagField::Album => track.album = value,
+                TagField::Year => track.year = value.parse().ok(),
+                TagField::TrackNumber => track.track_number = value.parse().ok(),
+                TagField::TrackTotal => track.track_total = value.parse().ok(),
+                TagField::DiscNumber => track.disc_number = value.parse().ok(),
+                TagField::Genre => track.genre = change.new.clone(),
+                TagField::Compilation => track.compilation = value == "1",
+            }
+        }
+    }
+}
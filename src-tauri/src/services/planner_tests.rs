@@ -0,0 +1,261 @@
+use super::*;
+use crate::models::changeset::TagField;
+use crate::models::{Album, CleaningRule, JunkAction, JunkCategory, RuleAction, Track};
+use crate::services::cleaner_plan::FichierAlbum;
+use std::path::{Path, PathBuf};
+
+fn fichier(chemin: &str, taille: u64) -> FichierAlbum {
+    FichierAlbum {
+        chemin: PathBuf::from(chemin),
+        taille,
+    }
+}
+
+#[test]
+fn test_comparer_et_appliquer_tags() {
+    let mut avant = Track::new("/a/01 - Song.mp3".to_string(), "01 - Song.mp3".to_string());
+    avant.title = "SONG".to_string();
+    avant.year = Some(1999);
+
+    let mut apres = avant.clone();
+    apres.title = "Song".to_string();
+    apres.year = Some(2001);
+    apres.genre = Some("Rock".to_string());
+
+    let changes = PlannerService::comparer_tags(&avant, &apres);
+    let fields: Vec<TagField> = changes.iter().map(|c| c.field).collect();
+    assert_eq!(fields, vec![TagField::Title, TagField::Year, TagField::Genre]);
+
+    PlannerService::appliquer_changements(&mut avant, &changes);
+    assert_eq!(avant.title, "Song");
+    assert_eq!(avant.year, Some(2001));
+    assert_eq!(avant.genre.as_deref(), Some("Rock"));
+}
+
+#[test]
+fn test_planifier_cover_et_nettoyage() {
+    let cleaner = CleanerService::new();
+    let mut fichiers = vec![
+        fichier("01 - Song.flac", 1000),
+        fichier("Folder.jpg", 50),
+        fichier("info.nfo", 10),
+        fichier("scans/back.jpg", 80),
+    ];
+
+    let (moves, deletions) = cleaner.planifier_cover(&mut fichiers, &[]);
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].from, "Folder.jpg");
+    assert_eq!(moves[0].to, "cover.jpg");
+    assert!(deletions.is_empty());
+
+    let (moves, deletions) = cleaner.planifier_nettoyage(&mut fichiers, &[]);
+    assert!(moves.is_empty());
+    let deleted: Vec<&str> = deletions.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(deleted, vec!["info.nfo", "scans/back.jpg"]);
+    assert_eq!(fichiers.len(), 2);
+}
+
+fn regle(pattern: Option<&str>, extension: Option<&str>, action: RuleAction) -> CleaningRule {
+    CleaningRule {
+        id: None,
+        pattern: pattern.map(String::from),
+        extension: extension.map(String::from),
+        min_size: None,
+        max_size: None,
+        action,
+        rename_to: None,
+    }
+}
+
+#[test]
+fn test_regles_de_nettoyage() {
+    let cleaner = CleanerService::new();
+    let mut lyrics = regle(Some("*.txt"), None, RuleAction::Rename);
+    lyrics.rename_to = Some("lyrics.lrc".to_string());
+    let mut gros_log = regle(None, Some("log"), RuleAction::Delete);
+    gros_log.min_size = Some(1000);
+    let regles = vec![
+        gros_log,
+        regle(None, Some(".LOG"), RuleAction::Keep),
+        regle(Some("scans/"), None, RuleAction::Keep),
+        regle(Some("back.jpg"), None, RuleAction::Keep),
+        lyrics,
+    ];
+    let mut fichiers = vec![
+        fichier("01 - Song.flac", 1000),
+        fichier("EAC.log", 10),
+        fichier("back.jpg", 90),
+        fichier("debug.log", 5000),
+        fichier("paroles.txt", 5),
+        fichier("scans/Booklet 01.png", 80),
+    ];
+
+    // back.jpg est protégé : pas de pochette inventée à partir de lui
+    let (moves, _) = cleaner.planifier_cover(&mut fichiers, &regles);
+    assert!(moves.is_empty());
+
+    let (moves, deletions) = cleaner.planifier_nettoyage(&mut fichiers, &regles);
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].to, "lyrics.lrc");
+    assert_eq!(deletions.len(), 1);
+    assert_eq!(deletions[0].path, "debug.log");
+    assert!(deletions[0].rule.contains(">= 1000 o"));
+    assert_eq!(fichiers.len(), 5);
+}
+
+#[test]
+fn test_regles_dangereuses_refusees() {
+    let mut regles = Vec::new();
+    let mut creer = |mut rule: CleaningRule| {
+        CleaningRuleService::valider(&mut rule)?;
+        regles.push(rule);
+        Ok::<_, crate::models::AppError>(())
+    };
+
+    for nom in ["../lyrics.lrc", "scans/lyrics.lrc", "..", "a\\b.lrc"] {
+        let mut rule = regle(Some("*.txt"), None, RuleAction::Rename);
+        rule.rename_to = Some(nom.to_string());
+        assert!(creer(rule).is_err(), "{}", nom);
+    }
+    for (pattern, extension) in [
+        (Some("*"), None),
+        (Some("*.FL?C"), None),
+        (Some("scans/"), None),
+        (None, Some(".Mp3")),
+        (None, None),
+    ] {
+        let mut rule = regle(pattern, extension, RuleAction::Delete);
+        rule.min_size = Some(1);
+        assert!(creer(rule).is_err(), "{:?} {:?}", pattern, extension);
+    }
+
+    // Suppressions ciblées et renommage simple restent permis
+    assert!(creer(regle(Some("*.txt"), None, RuleAction::Delete)).is_ok());
+    assert!(creer(regle(Some("scans/"), Some("jpg"), RuleAction::Delete)).is_ok());
+    let mut lyrics = regle(Some("*.lrc"), None, RuleAction::Rename);
+    lyrics.rename_to = Some("lyrics.lrc".to_string());
+    assert!(creer(lyrics).is_ok());
+    let verdict = CleaningRuleService::evaluer(&regles, Path::new("Scans/Back.JPG"), 10);
+    assert_eq!(verdict.action, RuleAction::Delete);
+}
+
+#[test]
+fn test_conflit_de_renommage_sans_suppression() {
+    let cleaner = CleanerService::new();
+    let mut lyrics = regle(Some("*.txt"), None, RuleAction::Rename);
+    lyrics.rename_to = Some("lyrics.lrc".to_string());
+    let mut fichiers = vec![fichier("a.txt", 5), fichier("b.txt", 6)];
+
+    // Deux fichiers pour un même nom : le second reste en place
+    let (moves, deletions) = cleaner.planifier_nettoyage(&mut fichiers, &[lyrics]);
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].to, "lyrics.lrc");
+    assert!(deletions.is_empty());
+    assert_eq!(fichiers.len(), 2);
+}
+
+#[test]
+fn test_renommages_sans_ecrasement() {
+    let cleaner = CleanerService::new();
+    let mut album = Album::new("/a".into(), "Titre".into(), "Artiste".into());
+    for (path, filename) in [
+        ("/a/CD1/x.flac", "01 - Song.flac"),
+        ("/a/CD2/x.flac", "01 - Song.flac"),
+        ("/a/piste.flac", "Notes.FLAC"),
+    ] {
+        album.tracks.push(Track::new(path.into(), filename.into()));
+    }
+    let mut fichiers = vec![
+        fichier("CD1/x.flac", 10),
+        fichier("CD2/x.flac", 10),
+        fichier("notes.flac", 10), // N'appartient à aucune piste : il reste en place
+        fichier("piste.flac", 10),
+    ];
+
+    let renames = cleaner.planifier_renommages(&album, Path::new("/a"), &mut fichiers);
+    let cibles: Vec<&str> = renames.iter().map(|r| r.to.as_str()).collect();
+    assert_eq!(
+        cibles,
+        vec!["01 - Song.flac", "01 - Song (2).flac", "Notes (2).FLAC"]
+    );
+    assert!(renames[2].reason.contains("déjà pris"));
+    assert!(fichiers.iter().any(|f| f.chemin == Path::new("notes.flac")));
+}
+
+#[test]
+fn test_pochette_choisie_avant_suppression() {
+    let cleaner = CleanerService::new();
+    let regles = vec![regle(Some("scan.jpg"), None, RuleAction::Delete)];
+    let mut fichiers = vec![fichier("01 - Song.flac", 1000), fichier("scan.jpg", 90)];
+
+    // Seule image de l'album : elle devient la pochette au lieu d'être supprimée
+    let (moves, _) = cleaner.planifier_cover(&mut fichiers, &regles);
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].from, "scan.jpg");
+    let (_, deletions) = cleaner.planifier_nettoyage(&mut fichiers, &regles);
+    assert!(deletions.is_empty());
+}
+
+#[test]
+fn test_detecter_fichiers_inutiles_recursif() {
+    let dir = std::env::temp_dir().join(format!("tagotomatik_junk_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("CD1")).unwrap();
+    for name in ["CD1/x.flac", "Cover.JPG", "album.m3u", "CD1/info.nfo"] {
+        std::fs::write(dir.join(name), b"data").unwrap();
+    }
+    let mut album = Album::new(dir.to_string_lossy().to_string(), "T".into(), "A".into());
+    let piste = dir.join("CD1/x.flac").to_string_lossy().to_string();
+    album
+        .tracks
+        .push(Track::new(piste, "01 - Song.flac".to_string()));
+
+    // Comme dans le plan, la piste de CD1 est d'abord renommée à la racine
+    let junk = InspectorService::detecter_fichiers_inutiles(&album).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(junk.len(), 2);
+    assert_eq!(junk[0].path, "Cover.JPG");
+    assert_eq!(junk[0].action, JunkAction::Rename);
+    assert_eq!(junk[0].target.as_deref(), Some("cover.jpg"));
+    assert_eq!(junk[1].path, "CD1/info.nfo");
+    assert_eq!(junk[1].category, JunkCategory::Text);
+    assert_eq!(junk[1].size, 4);
+}
+
+#[test]
+fn test_verifier_plan_refuse_plan_modifie() {
+    let dir = std::env::temp_dir().join(format!("tagotomatik_plan_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("info.nfo"), b"data").unwrap();
+    std::fs::write(dir.with_extension("txt"), b"hors album").unwrap();
+    let album = Album::new(
+        dir.to_string_lossy().to_string(),
+        "Titre".to_string(),
+        "Artiste".to_string(),
+    );
+
+    let plan = PlannerService::planifier(&album).unwrap();
+    assert_eq!(plan.deletions.len(), 1);
+    assert!(PlannerService::verifier_plan(&album, &plan).is_ok());
+
+    // Suppression détournée vers un fichier voisin de l'album
+    let mut forge = plan.clone();
+    forge.deletions[0].path = format!(
+        "../{}",
+        dir.with_extension("txt")
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+    );
+    assert!(PlannerService::verifier_plan(&album, &forge).is_err());
+    forge.deletions[0].path = dir.with_extension("txt").to_string_lossy().to_string();
+    assert!(PlannerService::verifier_plan(&album, &forge).is_err());
+
+    // Plan obsolète ou complété par le client
+    let mut forge = plan.clone();
+    forge.deletions.clear();
+    assert!(PlannerService::verifier_plan(&album, &forge).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    std::fs::remove_file(dir.with_extension("txt")).unwrap();
+}
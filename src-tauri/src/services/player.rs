@@ -1,29 +1,56 @@
-use rodio::{Decoder, OutputStream, Sink, Source};
-use std::fs::File;
-use std::io::BufReader;
-use std::sync::mpsc::{channel, Sender};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
+use tauri::AppHandle;
 
 // EQ Structs migrated to separate file equalizer.rs to reduce file size
-use super::equalizer::{EqParams, EqualizerSource};
-
+use super::equalizer::EqParams;
+use super::meter::Analyseur;
+use super::player_engine::PlayerEngine;
+use super::player_output::Sortie;
+use super::player_queue::PlayerQueue;
+use crate::models::{
+    AbLoop, EqSettings, OutputSettings, PlayerStateInfo, QueueEntry, QueueState, RepeatMode,
+    ReplayGainSettings, SeekResult,
+};
+
+// Délai d'attente d'une commande avant de relever la position et de vérifier la fin de piste
+const INTERVALLE_SURVEILLANCE: Duration = Duration::from_millis(200);
+// Ouvrir un périphérique (réveil du serveur audio) ou décoder jusqu'à une position prend un moment
+const DELAI_REPONSE: Duration = Duration::from_secs(10);
 
 pub enum PlayerCommand {
-    Play(String),
+    Play(String, bool),                      // Chemin, lecture lancée depuis un album
+    PlayQueue(Vec<QueueEntry>, usize, bool), // Pistes, indice de départ, depuis un album
+    Enqueue(Vec<QueueEntry>),
+    ClearQueue,
+    Next,
+    Previous,
+    SetShuffle(bool),
+    SetRepeat(RepeatMode),
     Pause,
     Resume,
     Stop,
     SetVolume(f32),
     Seek(f32),
-    SetEq(f32, f32, f32), // Bass, Mid, Treble
+    SeekExact(f64, Sender<Result<SeekResult, String>>), // Réponse : position réellement atteinte
+    SetSpeed(f32),                                      // Vitesse de lecture, hauteur conservée
+    SetLoop(Option<(f64, f64)>, Sender<Result<Option<AbLoop>, String>>), // Bornes A-B en secondes
+    SetEq(EqSettings),
+    SetReplayGain(ReplayGainSettings),
+    SetCrossfade(f32), // Durée du fondu enchaîné en secondes, 0 pour un enchaînement sans blanc
+    SetOutput(OutputSettings, Sender<Result<String, String>>), // Réponse : nom de la sortie ouverte
+    Attach(AppHandle), // Pour émettre les événements vers le frontend
 }
 
 pub struct AudioPlayerState {
     tx: Mutex<Sender<PlayerCommand>>,
     #[allow(dead_code)]
     eq_params: Arc<Mutex<EqParams>>,
+    analyseur: Arc<Analyseur>,
+    queue: Arc<Mutex<PlayerQueue>>,
+    etat: Arc<Mutex<PlayerStateInfo>>,
 }
 
 impl Default for AudioPlayerState {
@@ -32,142 +59,172 @@ impl Default for AudioPlayerState {
     }
 }
 
-
 impl AudioPlayerState {
     pub fn new() -> Self {
         let (tx, rx) = channel();
         let eq_params = Arc::new(Mutex::new(EqParams::default()));
+        let queue = Arc::new(Mutex::new(PlayerQueue::default()));
         let eq_params_thread = eq_params.clone();
+        let analyseur = Arc::new(Analyseur::default());
+        let analyseur_thread = analyseur.clone();
+        let queue_thread = queue.clone();
+        let etat = Arc::new(Mutex::new(PlayerStateInfo::default()));
+        let etat_thread = etat.clone();
 
         thread::spawn(move || {
-            // Initialize audio output in this thread
-            let stream_result = OutputStream::try_default();
-            if let Err(e) = &stream_result {
-                eprintln!("Failed to get output stream: {}", e);
-                return;
-            }
-            let (_stream, stream_handle) = stream_result.unwrap();
-
-            // Keep track of the current sink
-            let mut sink = Sink::try_new(&stream_handle).ok();
-
-            while let Ok(cmd) = rx.recv() {
-                match cmd {
-                    PlayerCommand::Play(path) => {
-                        // Stop previous track
-                        if let Some(s) = &sink {
-                            s.stop();
-                        }
-
-                        // Create new sink for new track
-                        sink = Sink::try_new(&stream_handle).ok();
-
-                        if let Some(s) = &sink {
-                            match File::open(&path) {
-                                Ok(file) => {
-                                    let reader = BufReader::new(file);
-                                    match Decoder::new(reader) {
-                                        Ok(source) => {
-                                            // WRAP SOURCE WITH EQ
-                                            let convert_source = source.convert_samples::<f32>();
-                                            let eq_source = EqualizerSource::new(convert_source, eq_params_thread.clone());
-                                            
-                                            s.append(eq_source);
-                                            s.play();
-                                        }
-                                        Err(e) => eprintln!("Error decoding file {}: {}", path, e),
-                                    }
-                                }
-                                Err(e) => eprintln!("Error opening file {}: {}", path, e),
-                            }
-                        }
-                    }
-                    PlayerCommand::Pause => {
-                        if let Some(s) = &sink {
-                            s.pause();
-                        }
-                    }
-                    PlayerCommand::Resume => {
-                        if let Some(s) = &sink {
-                            s.play();
-                        }
-                    }
-                    PlayerCommand::Stop => {
-                        if let Some(s) = &sink {
-                            s.stop();
-                        }
-                        sink = None; // Clear sink
-                    }
-                    PlayerCommand::SetVolume(vol) => {
-                        if let Some(s) = &sink {
-                            s.set_volume(vol);
-                        }
-                    }
-                    PlayerCommand::Seek(seconds) => {
-                        if let Some(s) = &sink {
-                            if let Err(e) = s.try_seek(Duration::from_secs_f32(seconds)) {
-                                eprintln!("Error seeking to {}s: {}", seconds, e);
-                            }
-                        }
-                    }
-                    PlayerCommand::SetEq(b, m, t) => {
-                        if let Ok(mut p) = eq_params_thread.lock() {
-                            p.bass_gain = b;
-                            p.mid_gain = m;
-                            p.treble_gain = t;
-                        }
-                    }
+            // Initialize audio output in this thread ; sans carte son, le lecteur tourne à vide
+            let sortie = match Sortie::ouvrir_ou_repli(&OutputSettings::default()) {
+                Ok(sortie) => sortie,
+                Err(e) => {
+                    eprintln!("Failed to start null output: {}", e);
+                    return;
+                }
+            };
+            let mut engine = PlayerEngine::new(
+                sortie,
+                eq_params_thread,
+                analyseur_thread,
+                queue_thread,
+                etat_thread,
+            );
+
+            loop {
+                match rx.recv_timeout(engine.intervalle(INTERVALLE_SURVEILLANCE)) {
+                    Ok(cmd) => engine.executer(cmd),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
                 }
+                engine.surveiller();
             }
         });
 
-        Self { 
+        Self {
             tx: Mutex::new(tx),
-            eq_params 
+            eq_params,
+            analyseur,
+            queue,
+            etat,
+        }
+    }
+
+    fn envoyer(&self, cmd: PlayerCommand) {
+        if let Ok(tx) = self.tx.lock() {
+            let _ = tx.send(cmd);
         }
     }
 
-    pub fn play(&self, path: &str) -> Result<(), String> {
+    pub fn attacher(&self, app: AppHandle) {
+        self.envoyer(PlayerCommand::Attach(app));
+    }
+
+    pub fn play(&self, path: &str, from_album: bool) -> Result<(), String> {
         self.tx
             .lock()
             .map_err(|_| "Failed to lock player state".to_string())?
-            .send(PlayerCommand::Play(path.to_string()))
+            .send(PlayerCommand::Play(path.to_string(), from_album))
             .map_err(|e| e.to_string())
     }
 
+    pub fn play_queue(&self, entries: Vec<QueueEntry>, start: usize, from_album: bool) {
+        self.envoyer(PlayerCommand::PlayQueue(entries, start, from_album));
+    }
+
+    pub fn enqueue(&self, entries: Vec<QueueEntry>) {
+        self.envoyer(PlayerCommand::Enqueue(entries));
+    }
+
+    pub fn clear_queue(&self) {
+        self.envoyer(PlayerCommand::ClearQueue);
+    }
+
+    pub fn next(&self) {
+        self.envoyer(PlayerCommand::Next);
+    }
+
+    pub fn previous(&self) {
+        self.envoyer(PlayerCommand::Previous);
+    }
+
+    pub fn set_shuffle(&self, shuffle: bool) {
+        self.envoyer(PlayerCommand::SetShuffle(shuffle));
+    }
+
+    pub fn set_repeat(&self, repeat: RepeatMode) {
+        self.envoyer(PlayerCommand::SetRepeat(repeat));
+    }
+
+    pub fn queue_state(&self) -> QueueState {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner()).etat()
+    }
+
+    pub fn state(&self) -> PlayerStateInfo {
+        self.etat.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     pub fn pause(&self) {
-        if let Ok(tx) = self.tx.lock() {
-            let _ = tx.send(PlayerCommand::Pause);
-        }
+        self.envoyer(PlayerCommand::Pause);
     }
 
     pub fn resume(&self) {
-        if let Ok(tx) = self.tx.lock() {
-            let _ = tx.send(PlayerCommand::Resume);
-        }
+        self.envoyer(PlayerCommand::Resume);
     }
 
     pub fn stop(&self) {
-        if let Ok(tx) = self.tx.lock() {
-            let _ = tx.send(PlayerCommand::Stop);
-        }
+        self.envoyer(PlayerCommand::Stop);
     }
 
     pub fn set_volume(&self, volume: f32) {
-        if let Ok(tx) = self.tx.lock() {
-            let _ = tx.send(PlayerCommand::SetVolume(volume));
-        }
+        self.envoyer(PlayerCommand::SetVolume(volume));
     }
 
     pub fn seek(&self, time: f32) {
-        if let Ok(tx) = self.tx.lock() {
-            let _ = tx.send(PlayerCommand::Seek(time));
-        }
+        self.envoyer(PlayerCommand::Seek(time));
     }
 
-    pub fn set_eq(&self, bass: f32, mid: f32, treble: f32) {
-        if let Ok(tx) = self.tx.lock() {
-            let _ = tx.send(PlayerCommand::SetEq(bass, mid, treble));
-        }
+    pub fn set_equalizer(&self, settings: EqSettings) {
+        self.envoyer(PlayerCommand::SetEq(settings));
+    }
+
+    pub fn set_replaygain(&self, settings: ReplayGainSettings) {
+        self.envoyer(PlayerCommand::SetReplayGain(settings));
+    }
+
+    /// Le vu-mètre ne coûte rien tant qu'il est coupé : la prise ne copie aucun échantillon
+    pub fn set_meter(&self, actif: bool) {
+        self.analyseur.activer(actif);
+    }
+
+    /// Ouvre la sortie demandée et y bascule la lecture ; l'ancienne sortie reste active en cas d'échec
+    pub fn set_output(&self, reglages: OutputSettings) -> Result<String, String> {
+        let (tx, rx) = channel();
+        self.envoyer(PlayerCommand::SetOutput(reglages, tx));
+        Self::attendre(rx)
+    }
+
+    /// Positionne à la trame près ; la lecture reprend dans l'état où elle était
+    pub fn seek_exact(&self, secondes: f64) -> Result<SeekResult, String> {
+        let (tx, rx) = channel();
+        self.envoyer(PlayerCommand::SeekExact(secondes, tx));
+        Self::attendre(rx)
+    }
+
+    pub fn set_speed(&self, vitesse: f32) {
+        self.envoyer(PlayerCommand::SetSpeed(vitesse));
+    }
+
+    /// `None` retire la boucle ; sinon renvoie les bornes retenues (B ramené à la fin de piste)
+    pub fn set_loop(&self, plage: Option<(f64, f64)>) -> Result<Option<AbLoop>, String> {
+        let (tx, rx) = channel();
+        self.envoyer(PlayerCommand::SetLoop(plage, tx));
+        Self::attendre(rx)
+    }
+
+    fn attendre<T>(rx: Receiver<Result<T, String>>) -> Result<T, String> {
+        rx.recv_timeout(DELAI_REPONSE)
+            .map_err(|_| "Le lecteur audio ne répond pas".to_string())?
+    }
+
+    pub fn set_crossfade(&self, secondes: f32) {
+        self.envoyer(PlayerCommand::SetCrossfade(secondes));
     }
 }
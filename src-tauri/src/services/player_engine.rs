@@ -0,0 +1,299 @@
+use super::equalizer::EqParams;
+use super::meter::Analyseur;
+use super::player::PlayerCommand;
+use super::player_events::PlayerReporter;
+use super::player_output::Sortie;
+use super::player_queue::PlayerQueue;
+use super::player_source::{self, PisteChargee};
+use super::player_transition::Transition;
+use super::player_transport::Transport;
+use crate::models::{
+    PlaybackStatus, PlayerStateInfo, QueueEntry, ReplayGainSettings, TrackChanged,
+};
+use rodio::Sink;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Cadence des événements "player-meter" quand le vu-mètre est actif
+const INTERVALLE_VU_METRE: Duration = Duration::from_millis(33);
+
+/// État du thread audio : sink courant, piste préchargée ou en fondu, et file de lecture
+pub struct PlayerEngine {
+    pub(super) sortie: Sortie,
+    pub(super) sink: Option<Sink>,
+    pub(super) eq_params: Arc<Mutex<EqParams>>,
+    pub(super) analyseur: Arc<Analyseur>,
+    dernier_releve: Instant,
+    pub(super) queue: Arc<Mutex<PlayerQueue>>,
+    pub(super) reporter: PlayerReporter,
+    pub(super) replaygain: ReplayGainSettings,
+    pub(super) courante: Option<PisteChargee>,
+    // Piste suivante déjà ajoutée au sink courant (enchaînement sans blanc)
+    pub(super) suivante: Option<(usize, QueueEntry, PisteChargee)>,
+    // Sink de la piste précédente pendant un fondu enchaîné
+    pub(super) sortant: Option<(Sink, Instant)>,
+    // Décision de transition pour la piste en cours : None tant que la fin n'est pas proche
+    pub(super) transition: Option<Transition>,
+    pub(super) crossfade: Duration,
+    pub(super) volume: f32,
+    pub(super) vitesse: f32,
+    pub(super) en_lecture: bool, // Une piste de la file est chargée : sa fin déclenche la suivante
+}
+
+impl PlayerEngine {
+    pub fn new(
+        sortie: Sortie,
+        eq_params: Arc<Mutex<EqParams>>,
+        analyseur: Arc<Analyseur>,
+        queue: Arc<Mutex<PlayerQueue>>,
+        etat: Arc<Mutex<PlayerStateInfo>>,
+    ) -> Self {
+        let reporter = PlayerReporter::new(etat);
+        reporter.volume(1.0);
+        reporter.vitesse(1.0);
+        reporter.sortie(sortie.nom(), sortie.repli());
+        Self {
+            sortie,
+            sink: None,
+            eq_params,
+            analyseur,
+            dernier_releve: Instant::now(),
+            queue,
+            reporter,
+            replaygain: ReplayGainSettings::default(),
+            courante: None,
+            suivante: None,
+            sortant: None,
+            transition: None,
+            crossfade: Duration::ZERO,
+            volume: 1.0,
+            vitesse: 1.0,
+            en_lecture: false,
+        }
+    }
+
+    pub fn executer(&mut self, cmd: PlayerCommand) {
+        match cmd {
+            PlayerCommand::Play(path, album) => {
+                self.avec_file(|q| q.remplacer(vec![QueueEntry::depuis_chemin(&path)], 0, album));
+                self.lancer_courant(false);
+            }
+            PlayerCommand::PlayQueue(entries, depart, album) => {
+                self.avec_file(|q| q.remplacer(entries, depart, album));
+                self.lancer_courant(false);
+            }
+            PlayerCommand::Enqueue(entries) => {
+                if self.avec_file(|q| q.ajouter(entries)) {
+                    self.lancer_courant(false);
+                }
+            }
+            PlayerCommand::ClearQueue => self.avec_file(PlayerQueue::vider),
+            PlayerCommand::Next => {
+                if self.avec_file(|q| q.suivant(false).is_some()) {
+                    self.lancer_courant(false);
+                }
+            }
+            PlayerCommand::Previous => {
+                if self.avec_file(|q| q.precedent().is_some()) {
+                    self.lancer_courant(false);
+                }
+            }
+            PlayerCommand::SetShuffle(shuffle) => self.avec_file(|q| q.definir_aleatoire(shuffle)),
+            PlayerCommand::SetRepeat(repeat) => self.avec_file(|q| q.definir_repetition(repeat)),
+            PlayerCommand::Pause => {
+                self.arreter_fondu();
+                if let Some(s) = &self.sink {
+                    s.pause();
+                    self.reporter.changer_statut(PlaybackStatus::Paused);
+                }
+            }
+            PlayerCommand::Resume => {
+                if let Some(s) = &self.sink {
+                    s.play();
+                    if self.en_lecture {
+                        self.reporter.changer_statut(PlaybackStatus::Playing);
+                    }
+                }
+            }
+            PlayerCommand::Stop => {
+                self.arreter_fondu();
+                if let Some(s) = &self.sink {
+                    s.stop();
+                }
+                self.sink = None; // Clear sink
+                self.courante = None;
+                self.suivante = None;
+                self.transition = None;
+                self.en_lecture = false;
+                self.reporter.changer_statut(PlaybackStatus::Stopped);
+            }
+            PlayerCommand::SetVolume(vol) => {
+                self.volume = vol;
+                self.reporter.volume(vol);
+                if let Some(s) = &self.sink {
+                    s.set_volume(vol);
+                }
+            }
+            PlayerCommand::Seek(seconds) => {
+                if let Err(e) = self.repositionner(f64::from(seconds)) {
+                    eprintln!("Error seeking to {}s: {}", seconds, e);
+                }
+            }
+            PlayerCommand::SeekExact(seconds, reponse) => {
+                let _ = reponse.send(self.repositionner(seconds));
+            }
+            PlayerCommand::SetSpeed(vitesse) => self.definir_vitesse(vitesse),
+            PlayerCommand::SetLoop(plage, reponse) => {
+                let _ = reponse.send(self.definir_boucle(plage));
+            }
+            PlayerCommand::SetEq(settings) => {
+                if let Ok(mut p) = self.eq_params.lock() {
+                    p.appliquer(settings);
+                }
+            }
+            PlayerCommand::SetReplayGain(reglages) => {
+                self.replaygain = reglages;
+                self.recalculer_gain();
+            }
+            PlayerCommand::SetCrossfade(secondes) => {
+                self.crossfade = Duration::from_secs_f32(secondes.max(0.0));
+            }
+            PlayerCommand::SetOutput(reglages, reponse) => {
+                let _ = reponse.send(self.changer_sortie(&reglages));
+            }
+            PlayerCommand::Attach(app) => self.reporter.attacher(app),
+        }
+    }
+
+    /// Appelé après chaque commande ou délai d'attente : fondu, position, préchargement et fin de piste
+    pub fn surveiller(&mut self) {
+        self.fondre();
+        self.relever_vu_metre();
+        if !self.en_lecture {
+            return;
+        }
+        let Some(sink) = &self.sink else {
+            return;
+        };
+
+        // La piste préchargée a pris le relais dans le même sink
+        if self.suivante.is_some() && sink.len() <= 1 {
+            self.promouvoir();
+            return;
+        }
+        if !sink.empty() {
+            let position = match &self.courante {
+                Some(piste) => piste.transport.temps(),
+                None => sink.get_pos(),
+            };
+            if self.reporter.statut() == PlaybackStatus::Playing {
+                self.reporter.position(position);
+            }
+            if self.suivante.is_none() {
+                self.precharger(position);
+            }
+            return;
+        }
+
+        // Rien de préchargé (durée inconnue, échec) : on enchaîne après coup
+        if self.avec_file(|q| q.suivant(true).is_some()) {
+            self.lancer_courant(true);
+        } else {
+            self.en_lecture = false;
+            self.reporter.changer_statut(PlaybackStatus::Ended);
+        }
+    }
+
+    /// Délai d'attente du thread : resserré pendant un fondu (rampe de volume) ou quand le vu-mètre est actif
+    pub fn intervalle(&self, defaut: Duration) -> Duration {
+        if self.sortant.is_some() {
+            Duration::from_millis(20)
+        } else if self.en_lecture && self.analyseur.est_actif() {
+            INTERVALLE_VU_METRE
+        } else {
+            defaut
+        }
+    }
+
+    fn relever_vu_metre(&mut self) {
+        if !self.analyseur.est_actif() || self.dernier_releve.elapsed() < INTERVALLE_VU_METRE {
+            return;
+        }
+        self.dernier_releve = Instant::now();
+        if let Some(releve) = self.analyseur.relever() {
+            self.reporter.emettre("player-meter", releve);
+        }
+    }
+
+    pub(super) fn avec_file<T>(&self, action: impl FnOnce(&mut PlayerQueue) -> T) -> T {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        action(&mut queue)
+    }
+
+    fn lancer_courant(&mut self, auto_advance: bool) {
+        let courant = self.avec_file(|q| {
+            q.courant()
+                .map(|(index, entry)| (index, entry.clone(), q.depuis_album()))
+        });
+        let Some((index, entry, album)) = courant else {
+            return;
+        };
+
+        let transport = Arc::new(Transport::new(self.vitesse));
+        self.lancer(&entry.path, album, transport);
+        self.signaler_piste(index, entry, auto_advance);
+    }
+
+    pub(super) fn signaler_piste(&self, index: usize, entry: QueueEntry, auto_advance: bool) {
+        self.reporter.emettre(
+            "player-track-changed",
+            TrackChanged {
+                index,
+                entry,
+                auto_advance,
+            },
+        );
+    }
+
+    /// Lance la piste depuis la position du transport (zéro pour une nouvelle piste)
+    pub(super) fn lancer(&mut self, path: &str, album: bool, transport: Arc<Transport>) {
+        // Stop previous track (et la suivante déjà ajoutée au sink)
+        if let Some(s) = &self.sink {
+            s.stop();
+        }
+        self.arreter_fondu();
+        self.suivante = None;
+        self.transition = None;
+        self.courante = None;
+
+        // Create new sink for new track
+        self.sink = Some(self.sortie.nouveau_sink());
+        self.en_lecture = false;
+        self.reporter.piste(path, None);
+        let Some(s) = &self.sink else {
+            return;
+        };
+        s.set_volume(self.volume);
+
+        match player_source::charger(
+            path,
+            &self.eq_params,
+            &self.replaygain,
+            album,
+            Some(&self.analyseur),
+            &transport,
+        ) {
+            Ok((piste, source)) => {
+                self.reporter.piste(path, piste.duree);
+                self.reporter.position(transport.temps());
+                self.reporter.boucle(transport.plage());
+                s.append(source);
+                s.play();
+                self.courante = Some(piste);
+                self.en_lecture = true;
+                self.reporter.changer_statut(PlaybackStatus::Playing);
+            }
+            Err(message) => self.reporter.erreur(path, message),
+        }
+    }
+}
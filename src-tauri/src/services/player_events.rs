@@ -0,0 +1,142 @@
+use crate::models::{AbLoop, PlaybackStatus, PlayerError, PlayerStateInfo};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+// Cadence des événements "player-position" pendant la lecture
+const INTERVALLE_POSITION: Duration = Duration::from_millis(500);
+
+/// Publication de l'état du lecteur : événements Tauri et instantané partagé pour get_player_state
+pub struct PlayerReporter {
+    app: Option<AppHandle>,
+    etat: Arc<Mutex<PlayerStateInfo>>,
+    dernier_tick: Instant,
+}
+
+#[derive(Serialize, Clone)]
+struct Position {
+    position_sec: f64,
+    duration_sec: Option<f64>,
+}
+
+impl PlayerReporter {
+    pub fn new(etat: Arc<Mutex<PlayerStateInfo>>) -> Self {
+        Self {
+            app: None,
+            etat,
+            dernier_tick: Instant::now(),
+        }
+    }
+
+    pub fn attacher(&mut self, app: AppHandle) {
+        self.app = Some(app);
+        self.signaler_repli();
+    }
+
+    pub fn emettre<T: Serialize + Clone>(&self, event: &str, payload: T) {
+        if let Some(app) = &self.app {
+            let _ = app.emit(event, payload);
+        }
+    }
+
+    pub fn statut(&self) -> PlaybackStatus {
+        self.lire().status
+    }
+
+    /// Nouvelle piste chargée : la position repart de zéro
+    pub fn piste(&self, path: &str, duration: Option<Duration>) {
+        self.modifier(|e| {
+            e.path = Some(path.to_string());
+            e.duration_sec = duration.map(|d| d.as_secs_f64());
+            e.position_sec = 0.0;
+            e.ab_loop = None;
+            e.error = None;
+        });
+    }
+
+    pub fn changer_statut(&self, status: PlaybackStatus) {
+        let precedent = self.statut();
+        if precedent == status {
+            return;
+        }
+        self.modifier(|e| e.status = status);
+        self.emettre("player-state", self.lire());
+        // Chaque démarrage (hors reprise après pause) rappelle que rien n'est audible
+        if status == PlaybackStatus::Playing && precedent != PlaybackStatus::Paused {
+            self.signaler_repli();
+        }
+    }
+
+    /// Sortie ouverte ; `repli` : raison pour laquelle la lecture se fait sur la sortie nulle
+    pub fn sortie(&self, nom: &str, repli: Option<&str>) {
+        self.modifier(|e| {
+            e.output = nom.to_string();
+            e.output_error = repli.map(str::to_string);
+        });
+        self.signaler_repli();
+    }
+
+    fn signaler_repli(&self) {
+        let etat = self.lire();
+        if let Some(message) = etat.output_error {
+            self.emettre(
+                "player-error",
+                PlayerError {
+                    path: etat.path.unwrap_or_default(),
+                    message,
+                },
+            );
+        }
+    }
+
+    pub fn volume(&self, volume: f32) {
+        self.modifier(|e| e.volume = volume);
+    }
+
+    pub fn vitesse(&self, speed: f32) {
+        self.modifier(|e| e.speed = speed);
+    }
+
+    pub fn boucle(&self, ab_loop: Option<AbLoop>) {
+        self.modifier(|e| e.ab_loop = ab_loop);
+    }
+
+    /// Erreur d'ouverture ou de décodage : signalée à part et reflétée dans l'état
+    pub fn erreur(&self, path: &str, message: String) {
+        eprintln!("{}: {}", path, message);
+        self.modifier(|e| e.error = Some(message.clone()));
+        self.emettre(
+            "player-error",
+            PlayerError {
+                path: path.to_string(),
+                message,
+            },
+        );
+        self.changer_statut(PlaybackStatus::Error);
+    }
+
+    /// Met à jour la position ; l'événement n'est émis qu'à la cadence prévue
+    pub fn position(&mut self, position: Duration) {
+        self.modifier(|e| e.position_sec = position.as_secs_f64());
+        if self.dernier_tick.elapsed() >= INTERVALLE_POSITION {
+            self.dernier_tick = Instant::now();
+            let etat = self.lire();
+            self.emettre(
+                "player-position",
+                Position {
+                    position_sec: etat.position_sec,
+                    duration_sec: etat.duration_sec,
+                },
+            );
+        }
+    }
+
+    fn lire(&self) -> PlayerStateInfo {
+        self.etat.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn modifier(&self, action: impl FnOnce(&mut PlayerStateInfo)) {
+        action(&mut self.etat.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+}
@@ -0,0 +1,263 @@
+use super::player_engine::PlayerEngine;
+use super::render::ecrire_entete;
+use super::SettingsService;
+use crate::db::Database;
+use crate::models::{AppError, OutputDevice, OutputSettings, RenderFormat};
+use rodio::cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rodio::cpal::{self, BufferSize, FromSample, SizedSample, SupportedBufferSize};
+use rodio::dynamic_mixer::{self, DynamicMixer, DynamicMixerController};
+use rodio::Sink;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SETTING_OUTPUT: &str = "player.output";
+pub const NOM_SORTIE_NULLE: &str = "Sortie nulle";
+// Format de la sortie nulle et cadence à laquelle elle consomme les échantillons
+const NULLE_RATE: u32 = 44_100;
+const NULLE_CHANNELS: u16 = 2;
+const NULLE_PERIODE: Duration = Duration::from_millis(10);
+
+/// Sortie audio du lecteur : un mélangeur rodio lu par un flux cpal, ou par un fil au rythme réel
+pub struct Sortie {
+    nom: String,
+    mixer: Arc<DynamicMixerController<f32>>,
+    _stream: Option<cpal::Stream>,
+    arret: Arc<AtomicBool>, // Arrête le fil de la sortie nulle
+    repli: Option<String>,  // Sortie nulle prise faute de pouvoir ouvrir celle demandée
+}
+
+impl Sortie {
+    pub fn ouvrir(reglages: &OutputSettings) -> Result<Self, AppError> {
+        if reglages.null_sink {
+            return Self::nulle(reglages.capture_path.as_deref());
+        }
+        let host = cpal::default_host();
+        let device = match &reglages.device {
+            Some(nom) => host
+                .output_devices()
+                .map_err(audio)?
+                .find(|d| d.name().ok().as_deref() == Some(nom.as_str()))
+                .ok_or_else(|| AppError::Audio(format!("Périphérique introuvable : {}", nom)))?,
+            None => host
+                .default_output_device()
+                .ok_or_else(|| AppError::Audio("Aucun périphérique de sortie audio".into()))?,
+        };
+        let nom = device.name().map_err(audio)?;
+
+        let defaut = device.default_output_config().map_err(audio)?;
+        let mut config = defaut.config();
+        if let Some(trames) = reglages.buffer_frames {
+            if let SupportedBufferSize::Range { min, max } = defaut.buffer_size() {
+                if !(*min..=*max).contains(&trames) {
+                    return Err(AppError::Validation(format!(
+                        "Tampon de {} trames hors des limites du périphérique ({} - {})",
+                        trames, min, max
+                    )));
+                }
+            }
+            config.buffer_size = BufferSize::Fixed(trames);
+        }
+
+        let (mixer, source) = dynamic_mixer::mixer::<f32>(config.channels, config.sample_rate.0);
+        let stream = match defaut.sample_format() {
+            cpal::SampleFormat::F32 => construire::<f32>(&device, &config, source),
+            cpal::SampleFormat::I16 => construire::<i16>(&device, &config, source),
+            cpal::SampleFormat::U16 => construire::<u16>(&device, &config, source),
+            cpal::SampleFormat::I32 => construire::<i32>(&device, &config, source),
+            format => {
+                return Err(AppError::Audio(format!(
+                    "Format d'échantillon non géré : {}",
+                    format
+                )))
+            }
+        }
+        .map_err(audio)?;
+        stream.play().map_err(audio)?;
+
+        Ok(Self {
+            nom,
+            mixer,
+            _stream: Some(stream),
+            arret: Arc::new(AtomicBool::new(false)),
+            repli: None,
+        })
+    }
+
+    /// Consomme le mélange au rythme réel sans carte son, en l'écrivant éventuellement dans un WAV
+    pub fn nulle(capture: Option<&str>) -> Result<Self, AppError> {
+        let mut fichier = match capture {
+            Some(path) => {
+                let mut w = BufWriter::new(File::create(path)?);
+                ecrire_entete(&mut w, RenderFormat::Float32, NULLE_CHANNELS, NULLE_RATE, 0)?;
+                Some(w)
+            }
+            None => None,
+        };
+        let (mixer, mut source) = dynamic_mixer::mixer::<f32>(NULLE_CHANNELS, NULLE_RATE);
+        let arret = Arc::new(AtomicBool::new(false));
+        let arret_fil = arret.clone();
+
+        thread::spawn(move || {
+            let par_periode = (NULLE_RATE as usize * usize::from(NULLE_CHANNELS))
+                * NULLE_PERIODE.as_millis() as usize
+                / 1000;
+            let mut echeance = Instant::now();
+            let mut octets = 0u64;
+            while !arret_fil.load(Ordering::Relaxed) {
+                for _ in 0..par_periode {
+                    let x = source.next().unwrap_or(0.0);
+                    if let Some(w) = &mut fichier {
+                        if w.write_all(&x.to_le_bytes()).is_ok() {
+                            octets += 4;
+                        }
+                    }
+                }
+                echeance += NULLE_PERIODE;
+                thread::sleep(echeance.saturating_duration_since(Instant::now()));
+            }
+            if let Some(mut w) = fichier {
+                let taille = u32::try_from(octets).unwrap_or(u32::MAX - 44);
+                let _ = w.seek(SeekFrom::Start(0)).and_then(|_| {
+                    ecrire_entete(
+                        &mut w,
+                        RenderFormat::Float32,
+                        NULLE_CHANNELS,
+                        NULLE_RATE,
+                        taille,
+                    )
+                });
+                let _ = w.flush();
+            }
+        });
+
+        Ok(Self {
+            nom: NOM_SORTIE_NULLE.to_string(),
+            mixer,
+            _stream: None,
+            arret,
+            repli: None,
+        })
+    }
+
+    /// Sortie demandée, ou sortie nulle si elle ne peut pas s'ouvrir (pas de carte son) : la raison
+    /// est gardée pour que le lecteur signale qu'il joue en silence
+    pub fn ouvrir_ou_repli(reglages: &OutputSettings) -> Result<Self, AppError> {
+        Self::ouvrir(reglages).or_else(|e| {
+            eprintln!("Failed to get output stream: {}", e);
+            let mut nulle = Self::nulle(None)?;
+            nulle.repli = Some(format!("{} : lecture sur la sortie nulle, sans son", e));
+            Ok(nulle)
+        })
+    }
+
+    pub fn nom(&self) -> &str {
+        &self.nom
+    }
+
+    pub fn repli(&self) -> Option<&str> {
+        self.repli.as_deref()
+    }
+
+    /// Sink branché sur le mélangeur de cette sortie
+    pub fn nouveau_sink(&self) -> Sink {
+        let (sink, file) = Sink::new_idle();
+        self.mixer.add(file);
+        sink
+    }
+}
+
+impl Drop for Sortie {
+    fn drop(&mut self) {
+        self.arret.store(true, Ordering::Relaxed);
+    }
+}
+
+impl PlayerEngine {
+    /// Bascule sur une autre sortie ; la piste en cours reprend au même endroit, en pause si elle l'était
+    pub(super) fn changer_sortie(&mut self, reglages: &OutputSettings) -> Result<String, String> {
+        let nouvelle = Sortie::ouvrir(reglages).map_err(|e| e.to_string())?;
+        let reprise = match &self.courante {
+            Some(piste) if self.en_lecture => Some((piste.path.clone(), piste.transport.clone())),
+            _ => None,
+        };
+        self.arreter_fondu();
+        self.sortie = nouvelle;
+        self.reporter.sortie(self.sortie.nom(), self.sortie.repli());
+
+        // Le sink de l'ancienne sortie sert encore à savoir si la lecture était en pause
+        match reprise {
+            Some((path, transport)) => self.reprendre(&path, transport),
+            None => {
+                if let Some(sink) = self.sink.take() {
+                    sink.stop();
+                }
+            }
+        }
+        Ok(self.sortie.nom().to_string())
+    }
+}
+
+fn construire<T: SizedSample + FromSample<f32>>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut source: DynamicMixer<f32>,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
+    device.build_output_stream::<T, _, _>(
+        config,
+        move |data: &mut [T], _| {
+            for d in data.iter_mut() {
+                *d = T::from_sample(source.next().unwrap_or(0.0));
+            }
+        },
+        |err| eprintln!("an error occurred on output stream: {}", err),
+        None,
+    )
+}
+
+fn audio(e: impl std::fmt::Display) -> AppError {
+    AppError::Audio(e.to_string())
+}
+
+/// Périphériques de sortie et réglage persistant de la sortie du lecteur
+pub struct OutputService;
+
+impl OutputService {
+    pub fn lister() -> Result<Vec<OutputDevice>, AppError> {
+        let host = cpal::default_host();
+        let defaut = host.default_output_device().and_then(|d| d.name().ok());
+        let devices = host.output_devices().map_err(audio)?;
+        Ok(devices
+            .filter_map(|device| {
+                let name = device.name().ok()?;
+                let config = device.default_output_config().ok();
+                let (buffer_min, buffer_max) = match config.as_ref().map(|c| c.buffer_size()) {
+                    Some(SupportedBufferSize::Range { min, max }) => (Some(*min), Some(*max)),
+                    _ => (None, None),
+                };
+                Some(OutputDevice {
+                    is_default: defaut.as_deref() == Some(name.as_str()),
+                    name,
+                    sample_rate: config.as_ref().map(|c| c.sample_rate().0),
+                    channels: config.as_ref().map(|c| c.channels()),
+                    buffer_min,
+                    buffer_max,
+                })
+            })
+            .collect())
+    }
+
+    pub fn charger(db: &Database) -> Result<OutputSettings, AppError> {
+        Ok(SettingsService::lire(db, SETTING_OUTPUT)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn enregistrer(db: &Database, reglages: &OutputSettings) -> Result<(), AppError> {
+        let json = serde_json::to_string(reglages).map_err(|e| AppError::Unknown(e.to_string()))?;
+        SettingsService::ecrire(db, SETTING_OUTPUT, &json)
+    }
+}
@@ -0,0 +1,82 @@
+use super::player_events::PlayerReporter;
+use super::player_output::{OutputService, Sortie, NOM_SORTIE_NULLE};
+use super::test_fixtures::dossier_temp;
+use crate::db::Database;
+use crate::models::{OutputSettings, PlayerStateInfo};
+use rodio::buffer::SamplesBuffer;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_sortie_nulle_au_rythme_reel() {
+    let dir = dossier_temp("output");
+    let capture = dir.join("capture.wav");
+
+    let reglages = OutputSettings {
+        null_sink: true,
+        capture_path: Some(capture.to_string_lossy().to_string()),
+        ..OutputSettings::default()
+    };
+    let sortie = Sortie::ouvrir(&reglages).unwrap();
+    assert_eq!(sortie.nom(), NOM_SORTIE_NULLE);
+
+    // 200 ms de signal : le sink se vide au rythme de la lecture, pas instantanément
+    let debut = Instant::now();
+    let sink = sortie.nouveau_sink();
+    sink.append(SamplesBuffer::new(2, 44_100, vec![0.25f32; 2 * 8_820]));
+    while !sink.empty() && debut.elapsed() < Duration::from_secs(3) {
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(sink.empty());
+    assert!(debut.elapsed() >= Duration::from_millis(150));
+    assert!(sink.get_pos() >= Duration::from_millis(190));
+
+    // À la fermeture, la capture reçoit son en-tête définitif
+    drop(sortie);
+    thread::sleep(Duration::from_millis(100));
+    let wav = fs::read(&capture).unwrap();
+    let taille = u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]) as usize;
+    assert_eq!(taille, wav.len() - 44);
+    let echantillons: Vec<f32> = wav[44..]
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+    // La file de rodio peut étirer le début du mélange, jamais le raccourcir
+    assert!(echantillons.iter().filter(|&&x| x == 0.25).count() >= 2 * 8_820);
+
+    let db = Database::open(":memory:").unwrap();
+    assert_eq!(
+        OutputService::charger(&db).unwrap(),
+        OutputSettings::default()
+    );
+    OutputService::enregistrer(&db, &reglages).unwrap();
+    assert_eq!(OutputService::charger(&db).unwrap(), reglages);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_repli_sur_sortie_nulle_signale() {
+    let reglages = OutputSettings {
+        device: Some("Périphérique qui n'existe pas".into()),
+        ..OutputSettings::default()
+    };
+    let sortie = Sortie::ouvrir_ou_repli(&reglages).unwrap();
+    assert_eq!(sortie.nom(), NOM_SORTIE_NULLE);
+    assert!(sortie.repli().unwrap().contains("sortie nulle"));
+
+    // L'état du lecteur expose la sortie active et la raison du repli
+    let etat = Arc::new(Mutex::new(PlayerStateInfo::default()));
+    let reporter = PlayerReporter::new(etat.clone());
+    reporter.sortie(sortie.nom(), sortie.repli());
+    let instantane = etat.lock().unwrap().clone();
+    assert_eq!(instantane.output, NOM_SORTIE_NULLE);
+    assert_eq!(instantane.output_error.as_deref(), sortie.repli());
+
+    let normale = Sortie::nulle(None).unwrap();
+    assert!(normale.repli().is_none());
+    reporter.sortie(normale.nom(), normale.repli());
+    assert!(etat.lock().unwrap().output_error.is_none());
+}
@@ -0,0 +1,163 @@
+use crate::models::{QueueEntry, QueueState, RepeatMode};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File de lecture : ordre de lecture distinct de l'ordre des pistes pour le mode aléatoire
+#[derive(Default)]
+pub struct PlayerQueue {
+    entries: Vec<QueueEntry>,
+    ordre: Vec<usize>,
+    position: Option<usize>, // Indice dans ordre
+    shuffle: bool,
+    repeat: RepeatMode,
+    album: bool, // File lancée depuis un album (mode ReplayGain automatique)
+}
+
+impl PlayerQueue {
+    /// Remplace la file et se place sur `depart` ; en aléatoire, cette piste reste la première jouée
+    pub fn remplacer(&mut self, entries: Vec<QueueEntry>, depart: usize, album: bool) {
+        self.entries = entries;
+        self.album = album;
+        self.ordre = (0..self.entries.len()).collect();
+        self.position = (depart < self.entries.len()).then_some(depart);
+        if self.shuffle {
+            self.melanger();
+        }
+    }
+
+    /// Ajoute en fin de file ; sur une file vide, la première piste ajoutée devient la piste en cours.
+    /// Renvoie vrai dans ce cas, pour que le lecteur la lance.
+    pub fn ajouter(&mut self, entries: Vec<QueueEntry>) -> bool {
+        let debut = self.entries.len();
+        self.entries.extend(entries);
+        self.ordre.extend(debut..self.entries.len());
+        if self.position.is_some() || debut == self.entries.len() {
+            return false;
+        }
+        // Position dans l'ordre de lecture : les nouvelles pistes sont en fin d'ordre
+        self.position = Some(self.ordre.len() - (self.entries.len() - debut));
+        true
+    }
+
+    pub fn vider(&mut self) {
+        *self = Self {
+            shuffle: self.shuffle,
+            repeat: self.repeat,
+            ..Self::default()
+        };
+    }
+
+    pub fn courant(&self) -> Option<(usize, &QueueEntry)> {
+        let index = self.ordre[self.position?];
+        Some((index, &self.entries[index]))
+    }
+
+    pub fn depuis_album(&self) -> bool {
+        self.album
+    }
+
+    /// Avance d'une piste ; `auto` distingue la fin de piste (répétition d'une piste) du bouton suivant
+    pub fn suivant(&mut self, auto: bool) -> Option<(usize, &QueueEntry)> {
+        let position = self.position?;
+        if auto && self.repeat == RepeatMode::One {
+            return self.courant();
+        }
+        if position + 1 < self.ordre.len() {
+            self.position = Some(position + 1);
+        } else if self.repeat == RepeatMode::All {
+            if self.shuffle {
+                // Nouveau tirage complet : la dernière piste jouée n'est pas épinglée en tête
+                self.position = None;
+                self.melanger();
+            }
+            self.position = Some(0);
+        } else {
+            return None;
+        }
+        self.courant()
+    }
+
+    /// Piste qui suivra en fin de piste, sans avancer (préchargement)
+    pub fn prochain(&self) -> Option<(usize, &QueueEntry)> {
+        let position = self.position?;
+        let suivante = if self.repeat == RepeatMode::One {
+            position
+        } else if position + 1 < self.ordre.len() {
+            position + 1
+        } else if self.repeat == RepeatMode::All {
+            0
+        } else {
+            return None;
+        };
+        let index = self.ordre[suivante];
+        Some((index, &self.entries[index]))
+    }
+
+    /// Fait de `index` la piste en cours à la position actuelle (piste préchargée après un nouveau tirage)
+    pub fn placer(&mut self, index: usize) {
+        let (Some(position), Some(k)) = (self.position, self.ordre.iter().position(|&i| i == index))
+        else {
+            return;
+        };
+        self.ordre.swap(position, k);
+    }
+
+    /// Recule d'une piste, ou reste sur la première (qui est alors relancée)
+    pub fn precedent(&mut self) -> Option<(usize, &QueueEntry)> {
+        let position = self.position?;
+        if position > 0 {
+            self.position = Some(position - 1);
+        } else if self.repeat == RepeatMode::All {
+            self.position = Some(self.ordre.len() - 1);
+        }
+        self.courant()
+    }
+
+    pub fn definir_aleatoire(&mut self, shuffle: bool) {
+        self.shuffle = shuffle;
+        if shuffle {
+            self.melanger();
+        } else {
+            let courant = self.courant().map(|(index, _)| index);
+            self.ordre = (0..self.entries.len()).collect();
+            self.position = courant;
+        }
+    }
+
+    pub fn definir_repetition(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
+    pub fn etat(&self) -> QueueState {
+        QueueState {
+            entries: self.entries.clone(),
+            order: self.ordre.clone(),
+            current: self.courant().map(|(index, _)| index),
+            shuffle: self.shuffle,
+            repeat: self.repeat,
+        }
+    }
+
+    // Fisher-Yates ; la piste en cours passe en tête pour ne pas être rejouée
+    fn melanger(&mut self) {
+        let courant = self.courant().map(|(index, _)| index);
+        let mut reste: Vec<usize> = (0..self.entries.len())
+            .filter(|i| Some(*i) != courant)
+            .collect();
+
+        let mut graine = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15)
+            | 1;
+        for i in (1..reste.len()).rev() {
+            // xorshift64, suffisant pour mélanger une file
+            graine ^= graine << 13;
+            graine ^= graine >> 7;
+            graine ^= graine << 17;
+            reste.swap(i, (graine % (i as u64 + 1)) as usize);
+        }
+
+        self.ordre = courant.into_iter().chain(reste).collect();
+        self.position = courant.map(|_| 0);
+    }
+}
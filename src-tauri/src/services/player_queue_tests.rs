@@ -0,0 +1,86 @@
+use super::player_queue::PlayerQueue;
+use crate::models::{QueueEntry, RepeatMode};
+
+fn file(n: usize) -> Vec<QueueEntry> {
+    (1..=n)
+        .map(|i| QueueEntry::depuis_chemin(&format!("/music/{:02}.flac", i)))
+        .collect()
+}
+
+fn courant(queue: &PlayerQueue) -> Option<usize> {
+    queue.courant().map(|(index, _)| index)
+}
+
+#[test]
+fn test_file_de_lecture() {
+    let mut queue = PlayerQueue::default();
+    queue.remplacer(file(3), 1, true);
+    assert_eq!(courant(&queue), Some(1));
+    assert!(queue.depuis_album());
+
+    // Fin de file sans répétition : plus rien à jouer
+    assert_eq!(queue.suivant(true).map(|(i, _)| i), Some(2));
+    assert!(queue.suivant(true).is_none());
+
+    queue.definir_repetition(RepeatMode::One);
+    assert_eq!(queue.suivant(true).map(|(i, _)| i), Some(2));
+    queue.definir_repetition(RepeatMode::All);
+    assert_eq!(queue.suivant(false).map(|(i, _)| i), Some(0));
+    assert_eq!(queue.precedent().map(|(i, _)| i), Some(2));
+
+    assert!(!queue.ajouter(file(1)));
+    assert_eq!(queue.etat().entries.len(), 4);
+    assert_eq!(queue.suivant(false).map(|(i, _)| i), Some(3));
+
+    // Aléatoire : la piste en cours reste en tête, toutes les pistes restent présentes
+    queue.definir_aleatoire(true);
+    let etat = queue.etat();
+    assert_eq!(etat.order[0], 3);
+    let mut ordre = etat.order.clone();
+    ordre.sort();
+    assert_eq!(ordre, vec![0, 1, 2, 3]);
+    queue.definir_aleatoire(false);
+    assert_eq!(courant(&queue), Some(3));
+
+    queue.vider();
+    assert!(queue.courant().is_none());
+    assert_eq!(queue.etat().repeat, RepeatMode::All);
+}
+
+#[test]
+fn test_ajout_sur_file_vide() {
+    // Sans piste en cours, la première piste ajoutée devient la piste courante
+    let mut queue = PlayerQueue::default();
+    assert!(queue.ajouter(file(2)));
+    assert_eq!(courant(&queue), Some(0));
+    assert_eq!(queue.suivant(false).map(|(i, _)| i), Some(1));
+
+    // Après vidage, même en aléatoire : la suite reste atteignable
+    queue.vider();
+    queue.definir_aleatoire(true);
+    assert!(!queue.ajouter(Vec::new()));
+    assert!(queue.ajouter(file(3)));
+    assert!(courant(&queue).is_some());
+    assert!(queue.suivant(false).is_some());
+}
+
+#[test]
+fn test_prochaine_piste_prechargee() {
+    let mut queue = PlayerQueue::default();
+    queue.remplacer(file(3), 0, true);
+
+    // Le préchargement consulte la suivante sans avancer
+    assert_eq!(queue.prochain().map(|(i, _)| i), Some(1));
+    assert_eq!(courant(&queue), Some(0));
+    queue.suivant(true);
+    queue.suivant(true);
+    assert!(queue.prochain().is_none());
+    queue.definir_repetition(RepeatMode::All);
+    assert_eq!(queue.prochain().map(|(i, _)| i), Some(0));
+
+    // Après un nouveau tirage, la piste préchargée reprend la position courante
+    queue.definir_aleatoire(true);
+    queue.suivant(true);
+    queue.placer(2);
+    assert_eq!(courant(&queue), Some(2));
+}
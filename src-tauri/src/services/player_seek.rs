@@ -0,0 +1,91 @@
+use super::player_engine::PlayerEngine;
+use super::player_transport::{Boucle, Transport, BOUCLE_MAX_SEC, VITESSE_MAX, VITESSE_MIN};
+use crate::models::{AbLoop, PlaybackStatus, SeekResult};
+use std::sync::Arc;
+
+impl PlayerEngine {
+    /// Relance la piste courante à la position demandée, décodée jusqu'à la trame exacte
+    pub(super) fn repositionner(&mut self, secondes: f64) -> Result<SeekResult, String> {
+        let Some(piste) = &self.courante else {
+            return Err("Aucune piste en cours".into());
+        };
+        let (path, transport) = (piste.path.clone(), piste.transport.clone());
+        transport.placer(transport.trame(secondes));
+        self.reprendre(&path, transport.clone());
+        if self.courante.is_none() {
+            return Err(format!("Impossible de relire {}", path));
+        }
+        let frame = transport.position();
+        Ok(SeekResult {
+            position_sec: transport.secondes(frame),
+            frame,
+        })
+    }
+
+    /// Reconstruit la chaîne de lecture sur un transport existant, en gardant l'état de pause
+    pub(super) fn reprendre(&mut self, path: &str, transport: Arc<Transport>) {
+        let en_pause = self.sink.as_ref().is_some_and(|s| s.is_paused());
+        let album = self.avec_file(|q| q.depuis_album());
+        self.lancer(path, album, transport);
+        if let Some(sink) = self.sink.as_ref().filter(|_| en_pause && self.en_lecture) {
+            sink.pause();
+            self.reporter.changer_statut(PlaybackStatus::Paused);
+        }
+    }
+
+    pub(super) fn recalculer_gain(&mut self) {
+        let album = self.avec_file(|q| q.depuis_album());
+        let pistes = self
+            .courante
+            .iter()
+            .chain(self.suivante.iter().map(|(_, _, p)| p));
+        for piste in pistes {
+            piste.recalculer_gain(&self.replaygain, album);
+        }
+    }
+
+    /// S'applique aussitôt à la piste courante comme à celle déjà préchargée
+    pub(super) fn definir_vitesse(&mut self, vitesse: f32) {
+        self.vitesse = vitesse.clamp(VITESSE_MIN, VITESSE_MAX);
+        let pistes = self
+            .courante
+            .iter()
+            .chain(self.suivante.iter().map(|(_, _, p)| p));
+        for piste in pistes {
+            piste.transport.definir_vitesse(self.vitesse);
+        }
+        self.reporter.vitesse(self.vitesse);
+    }
+
+    /// Pose ou retire la boucle A-B ; si la lecture a déjà dépassé B, elle repart de A
+    pub(super) fn definir_boucle(
+        &mut self,
+        plage: Option<(f64, f64)>,
+    ) -> Result<Option<AbLoop>, String> {
+        let Some(piste) = &self.courante else {
+            return Err("Aucune piste en cours".into());
+        };
+        let (path, transport) = (piste.path.clone(), piste.transport.clone());
+        let Some((debut, fin)) = plage else {
+            transport.definir_boucle(None);
+            self.reporter.boucle(None);
+            return Ok(None);
+        };
+        if !(debut >= 0.0 && fin > debut) {
+            return Err("La fin de la boucle doit suivre son début".into());
+        }
+        if fin - debut > BOUCLE_MAX_SEC {
+            return Err(format!("Boucle limitée à {} secondes", BOUCLE_MAX_SEC));
+        }
+
+        let boucle = Boucle::lire(&path, transport.trame(debut), transport.trame(fin))?;
+        let (a, b) = (boucle.debut, boucle.fin);
+        transport.definir_boucle(Some(boucle));
+        if transport.position() >= b {
+            self.repositionner(transport.secondes(a))?;
+        }
+        let plage = transport.plage();
+        self.reporter.boucle(plage);
+        Ok(plage)
+    }
+}
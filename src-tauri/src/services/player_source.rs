@@ -0,0 +1,103 @@
+use super::equalizer::{EqParams, EqualizerSource};
+use super::gapless::GaplessService;
+use super::meter::{Analyseur, Prise};
+use super::player_transport::{sauter, Lecteur, Transport};
+use super::time_stretch::Etirement;
+use super::{AudioService, ReplayGainService};
+use crate::models::{ReplayGainSettings, ReplayGainTags};
+use rodio::{Decoder, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Intervalle de relecture du gain, pour qu'un changement de réglage s'entende sans relancer la piste
+const RAFRAICHISSEMENT_GAIN: Duration = Duration::from_millis(100);
+
+pub type SourceLecture = Box<dyn Source<Item = f32> + Send>;
+
+/// Piste chargée dans un sink, avec son propre facteur ReplayGain (deux pistes peuvent coexister)
+pub struct PisteChargee {
+    pub path: String,
+    pub tags: ReplayGainTags,
+    pub facteur: Arc<Mutex<f32>>,
+    pub duree: Option<Duration>,
+    pub transport: Arc<Transport>,
+}
+
+impl PisteChargee {
+    pub fn recalculer_gain(&self, reglages: &ReplayGainSettings, album: bool) {
+        if let Ok(mut f) = self.facteur.lock() {
+            *f = ReplayGainService::facteur(&self.tags, reglages, album);
+        }
+    }
+}
+
+/// Décodage, avec la durée exacte de la piste quand elle est connue
+pub fn ouvrir(path: &str) -> Result<(SourceLecture, Option<Duration>), String> {
+    let file = File::open(path).map_err(|e| format!("Error opening file: {}", e))?;
+    let source = Decoder::new(BufReader::new(file))
+        .map_err(|e| format!("Error decoding file: {}", e))?
+        .convert_samples::<f32>();
+
+    let gapless = GaplessService::lire(Path::new(path));
+    let rate = source.sample_rate();
+    // Durée exacte des en-têtes gapless, sinon celle du décodeur, sinon celle des métadonnées
+    let duree = gapless
+        .as_ref()
+        .and_then(|g| g.duree(rate))
+        .or_else(|| source.total_duration())
+        .or_else(|| {
+            AudioService::new()
+                .lire_metadonnees(path)
+                .ok()
+                .map(|t| Duration::from_secs(t.duration_sec))
+        });
+    Ok((Box::new(source), duree))
+}
+
+/// Chaîne de lecture : décodage → boucle A-B → vitesse → ReplayGain → EQ → prise du vu-mètre
+///
+/// La lecture part de la position du transport, atteinte en décodant jusqu'à la trame exacte ;
+/// la position est ramenée à la fin de la piste si elle la dépasse.
+pub fn charger(
+    path: &str,
+    eq_params: &Arc<Mutex<EqParams>>,
+    reglages: &ReplayGainSettings,
+    album: bool,
+    analyseur: Option<&Arc<Analyseur>>,
+    transport: &Arc<Transport>,
+) -> Result<(PisteChargee, SourceLecture), String> {
+    let (mut source, duree) = ouvrir(path)?;
+    transport.definir_rate(source.sample_rate());
+    let depart = transport.position();
+    transport.placer(sauter(&mut source, depart));
+    let source = Etirement::new(Lecteur::new(source, transport.clone()), transport.clone());
+
+    let piste = PisteChargee {
+        path: path.to_string(),
+        tags: AudioService::new().lire_gain(path).unwrap_or_default(),
+        facteur: Arc::new(Mutex::new(1.0)),
+        duree,
+        transport: transport.clone(),
+    };
+    piste.recalculer_gain(reglages, album);
+
+    // WRAP SOURCE WITH REPLAYGAIN, THEN EQ
+    let facteur_source = piste.facteur.clone();
+    let initial = piste.facteur.lock().map(|f| *f).unwrap_or(1.0);
+    let convert_source =
+        source
+            .amplify(initial)
+            .periodic_access(RAFRAICHISSEMENT_GAIN, move |src| {
+                if let Ok(f) = facteur_source.lock() {
+                    src.set_factor(*f);
+                }
+            });
+    let eq_source = EqualizerSource::new(convert_source, eq_params.clone());
+    match analyseur {
+        Some(analyseur) => Ok((piste, Box::new(Prise::new(eq_source, analyseur.clone())))),
+        None => Ok((piste, Box::new(eq_source))),
+    }
+}
@@ -0,0 +1,139 @@
+use super::gapless::GaplessService;
+use super::player_engine::PlayerEngine;
+use super::player_source;
+use super::player_transport::Transport;
+use rodio::Source;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Marge avant la fin de piste pour décoder la suivante sans risquer un blanc
+const PRECHARGEMENT: Duration = Duration::from_secs(5);
+
+/// Manière d'enchaîner la piste en cours avec la suivante, décidée une fois par piste
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    Enchainement, // Ajout au même sink, sans blanc
+    Fondu,        // Second sink en fondu entrant pendant que le premier s'éteint
+    Abandon,      // Préchargement impossible : la fin de piste relancera la suivante
+}
+
+impl PlayerEngine {
+    /// Fait de la piste préchargée la piste courante une fois qu'elle joue dans le sink
+    pub(super) fn promouvoir(&mut self) {
+        let Some((index, entry, piste)) = self.suivante.take() else {
+            return;
+        };
+        self.avec_file(|q| {
+            q.suivant(true);
+            q.placer(index);
+        });
+        self.reporter.piste(&piste.path, piste.duree);
+        self.courante = Some(piste);
+        self.transition = None;
+        self.signaler_piste(index, entry, true);
+    }
+
+    /// Prépare l'enchaînement quand la fin de la piste courante approche
+    pub(super) fn precharger(&mut self, position: Duration) {
+        if self.transition == Some(Transition::Abandon) {
+            return;
+        }
+        let Some(courante) = &self.courante else {
+            return;
+        };
+        // Une boucle A-B retient la lecture : la fin de piste n'approche pas
+        if courante.transport.boucle().is_some() {
+            return;
+        }
+        // Temps réel restant, la vitesse de lecture comprise
+        let vitesse = courante.transport.vitesse();
+        let Some(restant) = courante
+            .duree
+            .map(|d| d.saturating_sub(position).div_f32(vitesse))
+        else {
+            return;
+        };
+        if restant > PRECHARGEMENT.max(self.crossfade) {
+            return;
+        }
+        let Some((index, entry)) = self.avec_file(|q| q.prochain().map(|(i, e)| (i, e.clone())))
+        else {
+            return;
+        };
+
+        let transition = *self.transition.get_or_insert_with(|| {
+            // Un album marqué gapless s'enchaîne toujours sans fondu
+            let gapless = GaplessService::est_album_gapless(Path::new(&courante.path))
+                || GaplessService::est_album_gapless(Path::new(&entry.path));
+            if self.crossfade.is_zero() || gapless {
+                Transition::Enchainement
+            } else {
+                Transition::Fondu
+            }
+        });
+        if transition == Transition::Fondu && restant > self.crossfade {
+            return;
+        }
+
+        let album = self.avec_file(|q| q.depuis_album());
+        let (piste, source) = match player_source::charger(
+            &entry.path,
+            &self.eq_params,
+            &self.replaygain,
+            album,
+            Some(&self.analyseur),
+            &Arc::new(Transport::new(self.vitesse)),
+        ) {
+            Ok(chargee) => chargee,
+            Err(_) => {
+                self.transition = Some(Transition::Abandon);
+                return;
+            }
+        };
+
+        match transition {
+            Transition::Fondu => {
+                let sink = self.sortie.nouveau_sink();
+                sink.set_volume(self.volume);
+                sink.append(source.fade_in(self.crossfade));
+                sink.play();
+                self.arreter_fondu();
+                if let Some(ancien) = self.sink.replace(sink) {
+                    self.sortant = Some((ancien, Instant::now()));
+                }
+                self.suivante = Some((index, entry, piste));
+                self.promouvoir();
+            }
+            _ => {
+                if let Some(s) = &self.sink {
+                    s.append(source);
+                    self.suivante = Some((index, entry, piste));
+                }
+            }
+        }
+    }
+
+    /// Baisse progressivement le volume de la piste sortante, puis l'arrête
+    pub(super) fn fondre(&mut self) {
+        let Some((sink, debut)) = &self.sortant else {
+            return;
+        };
+        let progression = if self.crossfade.is_zero() {
+            1.0
+        } else {
+            debut.elapsed().as_secs_f32() / self.crossfade.as_secs_f32()
+        };
+        if progression >= 1.0 || sink.empty() {
+            self.arreter_fondu();
+        } else {
+            sink.set_volume(self.volume * (1.0 - progression));
+        }
+    }
+
+    pub(super) fn arreter_fondu(&mut self) {
+        if let Some((sink, _)) = self.sortant.take() {
+            sink.stop();
+        }
+    }
+}
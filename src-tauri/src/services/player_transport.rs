@@ -0,0 +1,232 @@
+use super::player_source::{self, SourceLecture};
+use crate::models::AbLoop;
+use rodio::source::SeekError;
+use rodio::Source;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const VITESSE_MIN: f32 = 0.25;
+pub const VITESSE_MAX: f32 = 4.0;
+// La section bouclée est gardée décodée en mémoire : au-delà, elle coûterait trop cher
+pub const BOUCLE_MAX_SEC: f64 = 120.0;
+
+/// État partagé entre le moteur et la chaîne de lecture d'une piste : vitesse, position et boucle A-B
+pub struct Transport {
+    vitesse: AtomicU32, // Bits d'un f32
+    rate: AtomicU32,
+    position: AtomicU64, // Trames de la piste déjà lues
+    boucle: Mutex<Option<Arc<Boucle>>>,
+    version: AtomicU32, // Incrémentée à chaque changement de boucle
+}
+
+impl Transport {
+    pub fn new(vitesse: f32) -> Self {
+        Self {
+            vitesse: AtomicU32::new(vitesse.to_bits()),
+            rate: AtomicU32::new(0),
+            position: AtomicU64::new(0),
+            boucle: Mutex::new(None),
+            version: AtomicU32::new(0),
+        }
+    }
+
+    pub fn vitesse(&self) -> f32 {
+        f32::from_bits(self.vitesse.load(Ordering::Relaxed))
+    }
+
+    pub fn definir_vitesse(&self, vitesse: f32) {
+        let vitesse = vitesse.clamp(VITESSE_MIN, VITESSE_MAX);
+        self.vitesse.store(vitesse.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn rate(&self) -> u32 {
+        self.rate.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn definir_rate(&self, rate: u32) {
+        self.rate.store(rate, Ordering::Relaxed);
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    /// Position de départ de la prochaine chaîne construite sur ce transport
+    pub fn placer(&self, trame: u64) {
+        self.position.store(trame, Ordering::Relaxed);
+    }
+
+    pub fn temps(&self) -> Duration {
+        Duration::from_secs_f64(self.secondes(self.position()))
+    }
+
+    pub fn secondes(&self, trame: u64) -> f64 {
+        trame as f64 / f64::from(self.rate().max(1))
+    }
+
+    pub fn trame(&self, secondes: f64) -> u64 {
+        (secondes.max(0.0) * f64::from(self.rate())).round() as u64
+    }
+
+    pub fn boucle(&self) -> Option<Arc<Boucle>> {
+        self.boucle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn definir_boucle(&self, boucle: Option<Boucle>) {
+        *self.boucle.lock().unwrap_or_else(|e| e.into_inner()) = boucle.map(Arc::new);
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn plage(&self) -> Option<AbLoop> {
+        self.boucle().map(|b| AbLoop {
+            start_sec: self.secondes(b.debut),
+            end_sec: self.secondes(b.fin),
+        })
+    }
+}
+
+/// Section A-B décodée d'avance, rejouée sans blanc tant que la boucle est active
+pub struct Boucle {
+    pub debut: u64,
+    pub fin: u64,
+    echantillons: Vec<f32>,
+}
+
+impl Boucle {
+    /// La fin est ramenée à la dernière trame décodée si B dépasse la fin du fichier
+    pub fn lire(path: &str, debut: u64, fin: u64) -> Result<Self, String> {
+        let (mut source, _) = player_source::ouvrir(path)?;
+        let channels = usize::from(source.channels().max(1));
+        if sauter(&mut source, debut) < debut {
+            return Err("Début de boucle au-delà de la fin de la piste".into());
+        }
+        let echantillons: Vec<f32> = source
+            .take(fin.saturating_sub(debut) as usize * channels)
+            .collect();
+        let trames = (echantillons.len() / channels) as u64;
+        if trames == 0 {
+            return Err("Boucle vide".into());
+        }
+        Ok(Self {
+            debut,
+            fin: debut + trames,
+            echantillons,
+        })
+    }
+}
+
+/// Avance la source de `trames` trames ; renvoie le nombre de trames réellement sautées
+pub fn sauter(source: &mut SourceLecture, trames: u64) -> u64 {
+    let channels = u64::from(source.channels().max(1));
+    let mut lus = 0u64;
+    while lus < trames * channels && source.next().is_some() {
+        lus += 1;
+    }
+    lus / channels
+}
+
+/// Tête de la chaîne de lecture : compte les trames lues et rejoue la boucle A-B quand B est atteint
+pub struct Lecteur<S> {
+    source: S,
+    transport: Arc<Transport>,
+    channels: usize,
+    canal: usize,
+    position: u64,
+    version: u32,
+    boucle: Option<Arc<Boucle>>,
+    en_boucle: Option<(Arc<Boucle>, usize)>, // Boucle rejouée et indice du prochain échantillon
+}
+
+impl<S: Source<Item = f32>> Lecteur<S> {
+    pub fn new(source: S, transport: Arc<Transport>) -> Self {
+        Self {
+            channels: usize::from(source.channels().max(1)),
+            position: transport.position(),
+            version: transport.version.load(Ordering::Relaxed).wrapping_sub(1),
+            source,
+            transport,
+            canal: 0,
+            boucle: None,
+            en_boucle: None,
+        }
+    }
+
+    fn debut_trame(&mut self) {
+        let version = self.transport.version.load(Ordering::Relaxed);
+        if version != self.version {
+            self.version = version;
+            self.boucle = self.transport.boucle();
+        }
+        // Fin d'un passage : la source est restée à la fin de la boucle
+        if let Some((boucle, i)) = &self.en_boucle {
+            if *i >= boucle.echantillons.len() {
+                self.position = boucle.fin;
+                self.en_boucle = None;
+            }
+        }
+        if self.en_boucle.is_none() {
+            if let Some(boucle) = self.boucle.as_ref().filter(|b| b.fin == self.position) {
+                self.position = boucle.debut;
+                self.en_boucle = Some((boucle.clone(), 0));
+            }
+        }
+        self.transport.placer(self.position);
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Lecteur<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.canal == 0 {
+            self.debut_trame();
+        }
+        let x = match &mut self.en_boucle {
+            Some((boucle, i)) => {
+                *i += 1;
+                boucle.echantillons[*i - 1]
+            }
+            None => self.source.next()?,
+        };
+        self.canal += 1;
+        if self.canal == self.channels {
+            self.canal = 0;
+            self.position += 1;
+        }
+        Some(x)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Lecteur<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        // En boucle, la source interne ne dit plus rien de ce qui suit
+        match self.boucle {
+            Some(_) => None,
+            None => self.source.current_frame_len(),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.source.try_seek(pos)?;
+        self.position = (pos.as_secs_f64() * f64::from(self.source.sample_rate())) as u64;
+        self.canal = 0;
+        self.en_boucle = None;
+        Ok(())
+    }
+}
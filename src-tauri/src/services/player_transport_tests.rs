@@ -0,0 +1,103 @@
+use super::player_source;
+use super::player_transport::{sauter, Boucle, Lecteur, Transport};
+use super::test_fixtures::{dossier_temp, ecrire_wav};
+use super::time_stretch::Etirement;
+use rodio::buffer::SamplesBuffer;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+// WAV PCM 16 bits mono à 8 kHz : l'échantillon de la trame i vaut i * 4, on retrouve donc sa position
+fn ecrire_rampe(path: &Path, trames: u32) {
+    let echantillons: Vec<i32> = (0..trames).map(|i| i as i32 * 4).collect();
+    ecrire_wav(path, 8000, 1, 16, &echantillons);
+}
+
+fn trames(source: &mut impl Iterator<Item = f32>, n: usize) -> Vec<u32> {
+    source
+        .take(n)
+        .map(|x| (x * 32768.0 / 4.0).round() as u32)
+        .collect()
+}
+
+#[test]
+fn test_position_exacte_et_boucle_ab() {
+    let dir = dossier_temp("transport");
+    let path = dir.join("rampe.wav");
+    ecrire_rampe(&path, 8000);
+    let path = path.to_string_lossy().to_string();
+
+    // Positionnement : on repart exactement de la trame demandée, bornée à la fin de piste
+    let (mut source, _) = player_source::ouvrir(&path).unwrap();
+    assert_eq!(sauter(&mut source, 1000), 1000);
+    assert_eq!(trames(&mut source, 1), vec![1000]);
+    let (mut source, _) = player_source::ouvrir(&path).unwrap();
+    assert_eq!(sauter(&mut source, 9000), 8000);
+
+    // Boucle 2000-2100 posée avant B : la lecture continue puis reboucle sans blanc
+    let transport = Arc::new(Transport::new(1.0));
+    let (mut source, _) = player_source::ouvrir(&path).unwrap();
+    transport.placer(sauter(&mut source, 1990));
+    transport.definir_boucle(Some(Boucle::lire(&path, 2000, 2100).unwrap()));
+    let mut lecteur = Lecteur::new(source, transport.clone());
+    assert_eq!(trames(&mut lecteur, 110), (1990..2100).collect::<Vec<_>>());
+    assert_eq!(trames(&mut lecteur, 100), (2000..2100).collect::<Vec<_>>());
+    assert_eq!(trames(&mut lecteur, 5), (2000..2005).collect::<Vec<_>>());
+    assert_eq!(transport.position(), 2004);
+
+    // Boucle retirée : le passage en cours se termine et la piste reprend après B
+    transport.definir_boucle(None);
+    assert_eq!(trames(&mut lecteur, 97), (2005..2102).collect::<Vec<_>>());
+
+    // B au-delà de la fin du fichier : ramené à la dernière trame
+    let boucle = Boucle::lire(&path, 7900, 9000).unwrap();
+    assert_eq!((boucle.debut, boucle.fin), (7900, 8000));
+    assert!(Boucle::lire(&path, 9000, 9100).is_err());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+// Nombre de passages par zéro par seconde : deux fois la fréquence d'une sinusoïde
+fn passages_par_zero(echantillons: &[f32], rate: f32) -> f32 {
+    let passages = echantillons
+        .windows(2)
+        .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+        .count();
+    passages as f32 * rate / echantillons.len() as f32
+}
+
+#[test]
+fn test_vitesse_sans_changement_de_hauteur() {
+    let rate = 44_100;
+    let sinus: Vec<f32> = (0..rate)
+        .map(|i| (2.0 * PI * 440.0 * i as f32 / rate as f32).sin() * 0.5)
+        .collect();
+    let etirer = |vitesse: f32| -> Vec<f32> {
+        let transport = Arc::new(Transport::new(vitesse));
+        Etirement::new(SamplesBuffer::new(1, rate, sinus.clone()), transport).collect()
+    };
+
+    // Vitesse normale : la source passe intacte
+    assert_eq!(etirer(1.0), sinus);
+
+    for vitesse in [0.5f32, 2.0] {
+        let sortie = etirer(vitesse);
+        let attendu = rate as f32 / vitesse;
+        assert!(
+            (sortie.len() as f32 - attendu).abs() < 4096.0,
+            "{} : {} échantillons",
+            vitesse,
+            sortie.len()
+        );
+        // Hors des bords, la hauteur reste celle de la source
+        let milieu = &sortie[4096..sortie.len() - 4096];
+        let frequence = passages_par_zero(milieu, rate as f32) / 2.0;
+        assert!(
+            (frequence - 440.0).abs() < 10.0,
+            "{} : {} Hz",
+            vitesse,
+            frequence
+        );
+    }
+}
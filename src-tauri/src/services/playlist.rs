@@ -35,6 +35,7 @@ impl PlaylistService {
                 if ext == "m3u" || ext == "m3u8" {
                     let name = path.file_stem().unwrap().to_string_lossy().to_string();
                     // Compter les lignes non vides et non commentaires
+                    #[allow(clippy::lines_filter_map_ok)]
                     let count = if let Ok(file) = File::open(&path) {
                         BufReader::new(file)
                             .lines()
@@ -128,6 +129,7 @@ impl PlaylistService {
 
         // Header
         match extension.as_str() {
+            #[allow(clippy::collapsible_match)]
             "m3u" | "m3u8" => {
                 if options.use_extended_info {
                     writeln!(file, "#EXTM3U").map_err(|e| e.to_string())?;
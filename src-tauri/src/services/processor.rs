@@ -119,6 +119,7 @@ impl MetadataProcessorService {
         if let Some(g) = &track.genre {
             let clean_g = self.nettoyer_chaine(g);
             // Handle ID3v1 numeric genres (simple check)
+            #[allow(clippy::if_same_then_else)]
             let normalized = if clean_g.starts_with('(') && clean_g.ends_with(')') {
                 // Try to parse number inside
                 let inner = &clean_g[1..clean_g.len() - 1];
@@ -45,7 +45,10 @@ fn test_nettoyer_track_parentheses() {
         album: "Album Title (Deluxe Edition)".to_string(),
         year: None,
         track_number: None,
+        track_total: None,
+        disc_number: None,
         genre: None,
+        compilation: false,
         duration_sec: 0,
         format: "".to_string(),
         bit_rate: None,
@@ -121,6 +124,7 @@ fn test_format_track_filename() {
 }
 
 #[test]
+#[allow(clippy::vec_init_then_push)]
 fn test_appliquer_exceptions_regex() {
     let processor = MetadataProcessorService::new();
     let mut track = Track {
@@ -132,7 +136,10 @@ fn test_appliquer_exceptions_regex() {
         album: "Album".to_string(),
         year: None,
         track_number: None,
+        track_total: None,
+        disc_number: None,
         genre: None,
+        compilation: false,
         duration_sec: 0,
         format: "".to_string(),
         bit_rate: None,
@@ -0,0 +1,268 @@
+use crate::db::Database;
+use crate::models::{AppError, DeletionMode, QuarantineEntry, QuarantineSettings};
+use crate::services::{IOService, SettingsService};
+use rusqlite::params;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Quarantaine à côté de la base (hors de src-tauri)
+const QUARANTINE_DIR: &str = "../tagotomatik_quarantine";
+
+pub const SETTING_QUARANTINE_DIR: &str = "cleaner.quarantine_dir";
+pub const SETTING_DELETION_MODE: &str = "cleaner.deletion_mode";
+pub const SETTING_RETENTION_DAYS: &str = "cleaner.retention_days";
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+pub struct QuarantineService;
+
+impl QuarantineService {
+    pub fn mode_suppression(db: &Database) -> Result<DeletionMode, AppError> {
+        let value = SettingsService::lire_ou(db, SETTING_DELETION_MODE, "quarantine")?;
+        Ok(DeletionMode::from_setting(&value))
+    }
+
+    pub fn reglages(db: &Database) -> Result<QuarantineSettings, AppError> {
+        Ok(QuarantineSettings {
+            deletion_mode: Self::mode_suppression(db)?,
+            quarantine_dir: SettingsService::lire_ou(db, SETTING_QUARANTINE_DIR, QUARANTINE_DIR)?,
+            retention_days: Self::retention(db)?,
+        })
+    }
+
+    /// Enregistre les réglages après avoir vérifié que le dossier de quarantaine est utilisable
+    pub fn definir_reglages(db: &Database, reglages: &QuarantineSettings) -> Result<(), AppError> {
+        if reglages.retention_days == 0 {
+            return Err(AppError::Validation(
+                "La durée de rétention doit être d'au moins un jour".into(),
+            ));
+        }
+        let dir = reglages.quarantine_dir.trim();
+        if dir.is_empty() {
+            return Err(AppError::Validation(
+                "Le dossier de quarantaine est obligatoire".into(),
+            ));
+        }
+        fs::create_dir_all(dir).map_err(|e| {
+            AppError::Validation(format!(
+                "Dossier de quarantaine inutilisable ({}): {}",
+                dir, e
+            ))
+        })?;
+
+        let mode = reglages.deletion_mode.as_setting();
+        SettingsService::ecrire(db, SETTING_DELETION_MODE, mode)?;
+        SettingsService::ecrire(db, SETTING_QUARANTINE_DIR, dir)?;
+        let retention = reglages.retention_days.to_string();
+        SettingsService::ecrire(db, SETTING_RETENTION_DAYS, &retention)
+    }
+
+    /// Retire un fichier de l'album selon le mode choisi.
+    /// Renvoie l'emplacement de stockage, ou None si le fichier a été supprimé définitivement.
+    pub fn mettre_en_quarantaine(
+        db: &Database,
+        path: &Path,
+        reason: &str,
+    ) -> Result<Option<String>, AppError> {
+        let size = fs::metadata(path)?.len();
+
+        let (stored, in_trash) = match Self::mode_suppression(db)? {
+            DeletionMode::Permanent => {
+                fs::remove_file(path)?;
+                return Ok(None);
+            }
+            #[cfg(target_os = "linux")]
+            DeletionMode::Trash => (Self::envoyer_corbeille(db, path)?, true),
+            _ => (Self::deplacer_quarantaine(db, path)?, false),
+        };
+
+        let stored_str = stored.to_string_lossy().to_string();
+        let conn = verrouiller(db)?;
+        conn.execute(
+            "INSERT INTO quarantine (original_path, stored_path, size, reason, in_trash)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![path.to_string_lossy(), stored_str, size, reason, in_trash],
+        )?;
+
+        Ok(Some(stored_str))
+    }
+
+    /// Liste les fichiers en quarantaine, sans rien purger (voir `purger`)
+    pub fn lister(db: &Database) -> Result<Vec<QuarantineEntry>, AppError> {
+        let conn = verrouiller(db)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, original_path, stored_path, size, reason, deleted_at, in_trash
+             FROM quarantine ORDER BY id DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(QuarantineEntry {
+                id: row.get(0)?,
+                original_path: row.get(1)?,
+                stored_path: row.get(2)?,
+                size: row.get(3)?,
+                reason: row.get(4)?,
+                deleted_at: row.get(5)?,
+                in_trash: row.get(6)?,
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+        Ok(entries)
+    }
+
+    /// Remet un fichier à son emplacement d'origine
+    pub fn restaurer(db: &Database, id: i64) -> Result<QuarantineEntry, AppError> {
+        let entry = Self::lister(db)?
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| AppError::Validation("Fichier absent de la quarantaine".into()))?;
+
+        let original = Path::new(&entry.original_path);
+        if original.exists() {
+            return Err(AppError::Validation(format!(
+                "Un fichier existe déjà à l'emplacement d'origine: {}",
+                entry.original_path
+            )));
+        }
+        if let Some(parent) = original.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        IOService::move_file(Path::new(&entry.stored_path), original)?;
+
+        if entry.in_trash {
+            // Retirer la fiche .trashinfo associée
+            let stored = Path::new(&entry.stored_path);
+            if let (Some(files_dir), Some(name)) = (stored.parent(), stored.file_name()) {
+                let info = files_dir
+                    .with_file_name("info")
+                    .join(format!("{}.trashinfo", name.to_string_lossy()));
+                let _ = fs::remove_file(info);
+            }
+        }
+
+        verrouiller(db)?.execute("DELETE FROM quarantine WHERE id = ?1", params![id])?;
+        Ok(entry)
+    }
+
+    /// Restaure d'après l'emplacement de stockage (utilisé par l'annulation du journal)
+    pub fn restaurer_depuis(db: &Database, stored_path: &str) -> Result<(), AppError> {
+        let id = Self::lister(db)?
+            .into_iter()
+            .find(|e| e.stored_path == stored_path)
+            .map(|e| e.id)
+            .ok_or_else(|| AppError::Validation("Fichier absent de la quarantaine".into()))?;
+        Self::restaurer(db, id).map(|_| ())
+    }
+
+    /// Supprime définitivement les fichiers plus anciens que la durée de rétention.
+    /// Les fichiers envoyés à la corbeille sont laissés au bureau, seule l'entrée disparaît.
+    pub fn purger(db: &Database) -> Result<usize, AppError> {
+        let retention = Self::retention(db)?;
+        let conn = verrouiller(db)?;
+        let mut stmt = conn.prepare(
+            "SELECT id, stored_path, in_trash FROM quarantine
+             WHERE deleted_at < datetime('now', ?1)",
+        )?;
+        let expired = stmt
+            .query_map(params![format!("-{} days", retention)], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (id, stored_path, in_trash) in &expired {
+            if !in_trash {
+                let _ = fs::remove_file(stored_path);
+            }
+            conn.execute("DELETE FROM quarantine WHERE id = ?1", params![id])?;
+        }
+        Ok(expired.len())
+    }
+
+    fn retention(db: &Database) -> Result<u32, AppError> {
+        Ok(SettingsService::lire(db, SETTING_RETENTION_DAYS)?
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_RETENTION_DAYS))
+    }
+
+    fn deplacer_quarantaine(db: &Database, path: &Path) -> Result<PathBuf, AppError> {
+        let dir = PathBuf::from(SettingsService::lire_ou(
+            db,
+            SETTING_QUARANTINE_DIR,
+            QUARANTINE_DIR,
+        )?);
+        fs::create_dir_all(&dir)?;
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let target = dir.join(format!("{}_{}", stamp, name));
+
+        IOService::move_file(path, &target)?;
+        Ok(target)
+    }
+
+    /// Corbeille freedesktop : $XDG_DATA_HOME/Trash/{files,info}
+    #[cfg(target_os = "linux")]
+    fn envoyer_corbeille(db: &Database, path: &Path) -> Result<PathBuf, AppError> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+            .ok_or_else(|| AppError::Io("Corbeille introuvable".into()))?;
+        let files_dir = data_home.join("Trash").join("files");
+        let info_dir = data_home.join("Trash").join("info");
+        fs::create_dir_all(&files_dir)?;
+        fs::create_dir_all(&info_dir)?;
+
+        let absolute = fs::canonicalize(path)?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let mut candidate = name.to_string();
+        let mut n = 1;
+        while files_dir.join(&candidate).exists() {
+            n += 1;
+            candidate = format!("{}.{}", name, n);
+        }
+
+        let deletion_date: String = verrouiller(db)?.query_row(
+            "SELECT strftime('%Y-%m-%dT%H:%M:%S', 'now', 'localtime')",
+            [],
+            |row| row.get(0),
+        )?;
+        let encoded_path = absolute
+            .to_string_lossy()
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        // La fiche réserve le nom dans la corbeille ; elle est retirée si le déplacement échoue,
+        // sans quoi la corbeille listerait un fichier absent
+        let info = info_dir.join(format!("{}.trashinfo", candidate));
+        fs::write(
+            &info,
+            format!(
+                "[Trash Info]\nPath={}\nDeletionDate={}\n",
+                encoded_path, deletion_date
+            ),
+        )?;
+
+        let target = files_dir.join(&candidate);
+        if let Err(e) = IOService::move_file(path, &target) {
+            let _ = fs::remove_file(&info);
+            return Err(e);
+        }
+        Ok(target)
+    }
+}
+
+fn verrouiller(db: &Database) -> Result<std::sync::MutexGuard<'_, rusqlite::Connection>, AppError> {
+    db.conn
+        .lock()
+        .map_err(|_| AppError::DatabaseError("Lock error".into()))
+}
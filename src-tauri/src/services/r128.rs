@@ -0,0 +1,206 @@
+use std::f64::consts::PI;
+
+// Porte absolue et décalage relatif de la norme (BS.1770-4 / EBU R128)
+const PORTE_ABSOLUE_LUFS: f64 = -70.0;
+const PORTE_RELATIVE_LU: f64 = -10.0;
+// Sur-échantillonnage ×4 pour le true peak, filtre de 12 coefficients par phase
+const PHASES: usize = 4;
+const COEFFICIENTS: usize = 12;
+
+/// Filtre biquad en forme directe I, en f64 pour ne pas dériver sur une piste entière
+#[derive(Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn traiter(&mut self, entree: f64) -> f64 {
+        let sortie = self.b[0] * entree + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[1] * self.y[0]
+            - self.a[2] * self.y[1];
+        self.x = [entree, self.x[0]];
+        self.y = [sortie, self.y[0]];
+        sortie
+    }
+}
+
+/// Pondération K : plateau haut-parleur puis passe-haut RLB, recalculés pour la fréquence d'échantillonnage
+fn ponderation_k(sample_rate: u32) -> [Biquad; 2] {
+    let rate = f64::from(sample_rate);
+
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let plateau = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let passe_haut = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        x: [0.0; 2],
+        y: [0.0; 2],
+    };
+
+    [plateau, passe_haut]
+}
+
+/// Poids des canaux : surround arrière à +1.5 dB, LFE ignoré (disposition 5.1 standard)
+fn poids(canal: usize, channels: usize) -> f64 {
+    match (channels, canal) {
+        (6, 3) => 0.0,
+        (6, 4) | (6, 5) => 1.41,
+        _ => 1.0,
+    }
+}
+
+/// Mesure en continu : puissance des blocs de 400 ms (recouvrement 75 %) et true peak
+pub struct MesureR128 {
+    channels: usize,
+    filtres: Vec<[Biquad; 2]>,
+    poids: Vec<f64>,
+    trames_par_sous_bloc: usize,
+    trames: usize,
+    carres: Vec<f64>,
+    sous_blocs: Vec<f64>,
+    blocs: Vec<f64>,
+    historique: Vec<[f64; COEFFICIENTS]>,
+    interpolation: [[f64; COEFFICIENTS]; PHASES],
+    peak: f64,
+}
+
+impl MesureR128 {
+    pub fn new(channels: usize, sample_rate: u32) -> Self {
+        let channels = channels.max(1);
+
+        // Sinus cardinal fenêtré (Hann) aux positions fractionnaires p/4
+        let mut interpolation = [[0.0; COEFFICIENTS]; PHASES];
+        for (p, phase) in interpolation.iter_mut().enumerate() {
+            for (k, c) in phase.iter_mut().enumerate() {
+                let u = k as f64 - (COEFFICIENTS / 2) as f64 + p as f64 / PHASES as f64;
+                let sinc = if u == 0.0 {
+                    1.0
+                } else {
+                    (PI * u).sin() / (PI * u)
+                };
+                let fenetre = 0.5 * (1.0 + (PI * u / (COEFFICIENTS / 2) as f64).cos());
+                *c = sinc * fenetre;
+            }
+        }
+
+        Self {
+            channels,
+            filtres: vec![ponderation_k(sample_rate); channels],
+            poids: (0..channels).map(|c| poids(c, channels)).collect(),
+            trames_par_sous_bloc: (f64::from(sample_rate) / 10.0).round().max(1.0) as usize,
+            trames: 0,
+            carres: vec![0.0; channels],
+            sous_blocs: Vec::new(),
+            blocs: Vec::new(),
+            historique: vec![[0.0; COEFFICIENTS]; channels],
+            interpolation,
+            peak: 0.0,
+        }
+    }
+
+    /// Ajoute des échantillons entrelacés
+    pub fn ajouter(&mut self, samples: &[f32]) {
+        for trame in samples.chunks_exact(self.channels) {
+            for (c, &sample) in trame.iter().enumerate() {
+                let x = f64::from(sample);
+                self.suivre_peak(c, x);
+                let [plateau, passe_haut] = &mut self.filtres[c];
+                let y = passe_haut.traiter(plateau.traiter(x));
+                self.carres[c] += y * y;
+            }
+
+            self.trames += 1;
+            if self.trames == self.trames_par_sous_bloc {
+                self.clore_sous_bloc();
+            }
+        }
+    }
+
+    fn clore_sous_bloc(&mut self) {
+        let n = self.trames as f64;
+        let puissance: f64 = self
+            .carres
+            .iter()
+            .zip(&self.poids)
+            .map(|(carre, poids)| poids * carre / n)
+            .sum();
+        self.sous_blocs.push(puissance);
+        self.carres.iter_mut().for_each(|c| *c = 0.0);
+        self.trames = 0;
+
+        // Un bloc de 400 ms toutes les 100 ms
+        if self.sous_blocs.len() >= 4 {
+            let derniers = &self.sous_blocs[self.sous_blocs.len() - 4..];
+            self.blocs.push(derniers.iter().sum::<f64>() / 4.0);
+        }
+    }
+
+    fn suivre_peak(&mut self, canal: usize, x: f64) {
+        let historique = &mut self.historique[canal];
+        historique.copy_within(0..COEFFICIENTS - 1, 1);
+        historique[0] = x;
+        self.peak = self.peak.max(x.abs());
+        for phase in &self.interpolation[1..] {
+            let valeur: f64 = phase
+                .iter()
+                .zip(historique.iter())
+                .map(|(c, x)| c * x)
+                .sum();
+            self.peak = self.peak.max(valeur.abs());
+        }
+    }
+
+    /// Puissance pondérée de chaque bloc, à fusionner entre pistes pour le gain d'album
+    pub fn blocs(&self) -> &[f64] {
+        &self.blocs
+    }
+
+    pub fn true_peak(&self) -> f64 {
+        self.peak
+    }
+}
+
+fn lufs(puissance: f64) -> f64 {
+    -0.691 + 10.0 * puissance.log10()
+}
+
+/// Sonie intégrée avec double porte (absolue puis relative) ; -70 LUFS si tout est sous la porte
+pub fn sonie_integree(blocs: &[f64]) -> f64 {
+    let moyenne = |blocs: &[f64]| blocs.iter().sum::<f64>() / blocs.len() as f64;
+
+    let au_dessus: Vec<f64> = blocs
+        .iter()
+        .copied()
+        .filter(|&p| p > 0.0 && lufs(p) > PORTE_ABSOLUE_LUFS)
+        .collect();
+    if au_dessus.is_empty() {
+        return PORTE_ABSOLUE_LUFS;
+    }
+
+    let porte_relative = lufs(moyenne(&au_dessus)) + PORTE_RELATIVE_LU;
+    let retenus: Vec<f64> = au_dessus
+        .into_iter()
+        .filter(|&p| lufs(p) > porte_relative)
+        .collect();
+    lufs(moyenne(&retenus))
+}
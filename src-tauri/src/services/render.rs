@@ -0,0 +1,131 @@
+use super::equalizer::EqParams;
+use super::player_source;
+use super::player_transport::Transport;
+use crate::models::{AppError, EqSettings, RenderFormat, RenderResult, ReplayGainSettings};
+use rodio::Source;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+// Taille de l'en-tête RIFF/fmt/data écrit par `ecrire_entete`
+const TAILLE_ENTETE: u32 = 44;
+
+/// Rendu hors ligne d'une piste à travers la chaîne du lecteur (décodage → ReplayGain → EQ) vers un WAV
+pub struct RenderService;
+
+impl RenderService {
+    pub fn rendre(
+        source: &Path,
+        dest: &Path,
+        eq: EqSettings,
+        replaygain: &ReplayGainSettings,
+        album: bool,
+        format: RenderFormat,
+    ) -> Result<RenderResult, AppError> {
+        let mut params = EqParams::default();
+        params.appliquer(eq);
+        let (_, chaine) = player_source::charger(
+            &source.to_string_lossy(),
+            &Arc::new(Mutex::new(params)),
+            replaygain,
+            album,
+            None,
+            &Arc::new(Transport::new(1.0)),
+        )
+        .map_err(AppError::Audio)?;
+        let (channels, sample_rate) = (chaine.channels(), chaine.sample_rate());
+
+        let mut sortie = BufWriter::new(File::create(dest)?);
+        ecrire_entete(&mut sortie, format, channels, sample_rate, 0)?;
+        let (mut echantillons, mut peak, mut clipped_samples) = (0u64, 0f32, 0u64);
+        for x in chaine {
+            peak = peak.max(x.abs());
+            if format != RenderFormat::Float32 && x.abs() > 1.0 {
+                clipped_samples += 1;
+            }
+            match format {
+                RenderFormat::Pcm16 => {
+                    let v = (x.clamp(-1.0, 1.0) * 32767.0).round() as i16;
+                    sortie.write_all(&v.to_le_bytes())?;
+                }
+                RenderFormat::Pcm24 => {
+                    let v = (x.clamp(-1.0, 1.0) * 8_388_607.0).round() as i32;
+                    sortie.write_all(&v.to_le_bytes()[..3])?;
+                }
+                RenderFormat::Float32 => sortie.write_all(&x.to_le_bytes())?,
+            }
+            echantillons += 1;
+        }
+
+        // Tailles connues seulement à la fin : on réécrit l'en-tête
+        let octets = echantillons * u64::from(octets_par_echantillon(format));
+        let taille = u32::try_from(octets)
+            .ok()
+            .filter(|t| *t < u32::MAX - TAILLE_ENTETE)
+            .ok_or_else(|| AppError::Audio("Rendu trop long pour un fichier WAV".to_string()))?;
+        terminer_donnees(&mut sortie, taille)?;
+        sortie.seek(SeekFrom::Start(0))?;
+        ecrire_entete(&mut sortie, format, channels, sample_rate, taille)?;
+        sortie.flush()?;
+
+        Ok(RenderResult {
+            source: source.to_string_lossy().to_string(),
+            output: dest.to_string_lossy().to_string(),
+            frames: echantillons / u64::from(channels.max(1)),
+            sample_rate,
+            channels,
+            peak,
+            clipped_samples,
+        })
+    }
+}
+
+fn octets_par_echantillon(format: RenderFormat) -> u16 {
+    match format {
+        RenderFormat::Pcm16 => 2,
+        RenderFormat::Pcm24 => 3,
+        RenderFormat::Float32 => 4,
+    }
+}
+
+/// Un bloc RIFF de taille impaire est suivi d'un octet de bourrage (non compté dans sa taille)
+pub(super) fn terminer_donnees(
+    sortie: &mut impl Write,
+    taille_donnees: u32,
+) -> std::io::Result<()> {
+    if taille_donnees % 2 == 1 {
+        sortie.write_all(&[0])?;
+    }
+    Ok(())
+}
+
+pub(super) fn ecrire_entete(
+    sortie: &mut impl Write,
+    format: RenderFormat,
+    channels: u16,
+    sample_rate: u32,
+    taille_donnees: u32,
+) -> std::io::Result<()> {
+    let octets = octets_par_echantillon(format);
+    let bloc = channels * octets;
+    // 1 = PCM entier, 3 = IEEE flottant
+    let code = if format == RenderFormat::Float32 {
+        3u16
+    } else {
+        1
+    };
+    sortie.write_all(b"RIFF")?;
+    let bourrage = taille_donnees % 2;
+    sortie.write_all(&(TAILLE_ENTETE - 8 + taille_donnees + bourrage).to_le_bytes())?;
+    sortie.write_all(b"WAVEfmt ")?;
+    sortie.write_all(&16u32.to_le_bytes())?;
+    sortie.write_all(&code.to_le_bytes())?;
+    sortie.write_all(&channels.to_le_bytes())?;
+    sortie.write_all(&sample_rate.to_le_bytes())?;
+    sortie.write_all(&(sample_rate * u32::from(bloc)).to_le_bytes())?;
+    sortie.write_all(&bloc.to_le_bytes())?;
+    sortie.write_all(&(octets * 8).to_le_bytes())?;
+    sortie.write_all(b"data")?;
+    sortie.write_all(&taille_donnees.to_le_bytes())
+}
@@ -0,0 +1,122 @@
+use super::render::RenderService;
+use super::test_fixtures::{dossier_temp, ecrire_wav, pcm16};
+use crate::models::{
+    EqBand, EqFilterType, EqSettings, RenderFormat, ReplayGainMode, ReplayGainSettings,
+};
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// WAV PCM 16 bits mono 44,1 kHz : sinus 1 kHz
+fn ecrire_sinus(path: &Path, amplitude: f64) {
+    let taux = 44_100u32;
+    let echantillons: Vec<i32> = (0..taux / 2)
+        .map(|n| pcm16(amplitude * (2.0 * PI * 1000.0 * f64::from(n) / f64::from(taux)).sin()))
+        .collect();
+    ecrire_wav(path, taux, 1, 16, &echantillons);
+}
+
+fn echantillons_16(path: &Path) -> Vec<i16> {
+    fs::read(path).unwrap()[44..]
+        .chunks_exact(2)
+        .map(|c| i16::from_le_bytes([c[0], c[1]]))
+        .collect()
+}
+
+#[test]
+fn test_rendu_hors_ligne() {
+    let dir = dossier_temp("render");
+    let source = dir.join("sinus.wav");
+    ecrire_sinus(&source, 0.4);
+    let sans_gain = ReplayGainSettings {
+        mode: ReplayGainMode::Off,
+        ..ReplayGainSettings::default()
+    };
+
+    // Chaîne neutre : le rendu reproduit la source à un pas de quantification près
+    let neutre = dir.join("neutre.wav");
+    let resultat = RenderService::rendre(
+        &source,
+        &neutre,
+        EqSettings::default(),
+        &sans_gain,
+        false,
+        RenderFormat::Pcm16,
+    )
+    .unwrap();
+    assert_eq!((resultat.frames, resultat.channels), (22_050, 1));
+    let (avant, apres) = (echantillons_16(&source), echantillons_16(&neutre));
+    assert_eq!(avant.len(), apres.len());
+    assert!(avant.iter().zip(&apres).all(|(a, b)| (a - b).abs() <= 1));
+
+    // Fichier sans tag en mode piste : gain de repli choisi de -6 dB
+    let repli = RenderService::rendre(
+        &source,
+        &dir.join("repli.wav"),
+        EqSettings::default(),
+        &ReplayGainSettings {
+            mode: ReplayGainMode::Track,
+            fallback_db: -6.0,
+            ..ReplayGainSettings::default()
+        },
+        false,
+        RenderFormat::Float32,
+    )
+    .unwrap();
+    assert!((repli.peak - 0.4 * 10f32.powf(-6.0 / 20.0)).abs() < 0.01);
+
+    // +9 dB à 1 kHz sans anti-saturation : le pic dépasse 0 dBFS et le PCM 16 bits écrête
+    let eq = EqSettings {
+        auto_preamp: false,
+        bands: vec![EqBand::new(EqFilterType::Peaking, 1000.0, 9.0, 1.0)],
+        ..EqSettings::default()
+    };
+    let sature = RenderService::rendre(
+        &source,
+        &dir.join("sature.wav"),
+        eq.clone(),
+        &sans_gain,
+        false,
+        RenderFormat::Pcm16,
+    )
+    .unwrap();
+    assert!((sature.peak - 0.4 * 10f32.powf(9.0 / 20.0)).abs() < 0.05);
+    assert!(sature.clipped_samples > 0);
+    let corrige = RenderService::rendre(
+        &source,
+        &dir.join("corrige.wav"),
+        EqSettings {
+            auto_preamp: true,
+            ..eq
+        },
+        &sans_gain,
+        false,
+        RenderFormat::Pcm24,
+    )
+    .unwrap();
+    assert!((corrige.peak - 0.4).abs() < 0.02);
+    assert_eq!(corrige.clipped_samples, 0);
+
+    // 24 bits mono sur un nombre impair de trames : octet de bourrage RIFF compté dans la taille
+    let impaire = dir.join("impaire.wav");
+    ecrire_wav(&impaire, 44_100, 1, 16, &[1000; 101]);
+    let rendu = dir.join("impaire_24.wav");
+    RenderService::rendre(
+        &impaire,
+        &rendu,
+        EqSettings::default(),
+        &sans_gain,
+        false,
+        RenderFormat::Pcm24,
+    )
+    .unwrap();
+    let octets = fs::read(&rendu).unwrap();
+    assert_eq!(octets.len(), 44 + 303 + 1);
+    assert_eq!(
+        u32::from_le_bytes(octets[4..8].try_into().unwrap()) as usize,
+        octets.len() - 8
+    );
+    assert_eq!(hound::WavReader::open(&rendu).unwrap().duration(), 101);
+
+    fs::remove_dir_all(&dir).ok();
+}
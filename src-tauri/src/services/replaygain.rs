@@ -0,0 +1,54 @@
+use super::SettingsService;
+use crate::db::Database;
+use crate::models::{AppError, ReplayGainMode, ReplayGainSettings, ReplayGainTags};
+
+const SETTING_REPLAYGAIN: &str = "player.replaygain";
+
+/// Gain de lecture à partir des tags ReplayGain et des réglages utilisateur
+pub struct ReplayGainService;
+
+impl ReplayGainService {
+    pub fn charger(db: &Database) -> Result<ReplayGainSettings, AppError> {
+        Ok(SettingsService::lire(db, SETTING_REPLAYGAIN)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn enregistrer(db: &Database, reglages: &ReplayGainSettings) -> Result<(), AppError> {
+        let json = serde_json::to_string(reglages).map_err(|e| AppError::Unknown(e.to_string()))?;
+        SettingsService::ecrire(db, SETTING_REPLAYGAIN, &json)
+    }
+
+    /// Facteur linéaire à appliquer au signal décodé
+    pub fn facteur(
+        tags: &ReplayGainTags,
+        reglages: &ReplayGainSettings,
+        contexte_album: bool,
+    ) -> f32 {
+        let album = match reglages.mode {
+            ReplayGainMode::Off => return 1.0,
+            ReplayGainMode::Track => false,
+            ReplayGainMode::Album => true,
+            ReplayGainMode::Auto => contexte_album,
+        };
+
+        // Repli sur le gain de piste si l'album n'a pas été analysé, puis sur le gain par défaut
+        let piste = tags.track_gain_db.map(|g| (g, tags.track_peak));
+        let mesure = if album {
+            tags.album_gain_db.map(|g| (g, tags.album_peak)).or(piste)
+        } else {
+            piste
+        };
+        let (gain_db, peak) = match mesure {
+            Some((gain, peak)) => (gain + reglages.preamp_db, peak),
+            None => (reglages.fallback_db, None),
+        };
+
+        let facteur = 10f32.powf(gain_db / 20.0);
+        match peak {
+            // Le pic amplifié ne doit pas dépasser 0 dBFS
+            Some(peak) if reglages.prevent_clipping && peak > 0.0 => facteur.min(1.0 / peak),
+            _ => facteur,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::models::{Album, AppError, ScanResult};
-use crate::services::{AudioService, ValidatorService};
+use crate::services::{AudioService, CompilationService, ValidatorService};
 use std::collections::HashMap;
 use std::path::Path;
 use walkdir::WalkDir;
@@ -100,6 +100,11 @@ impl ScannerService {
                 album.year_max = years.iter().max().copied();
             }
 
+            // Compilation : l'artiste de l'album est le nom configuré pour "Various Artists"
+            if CompilationService::est_compilation(album) {
+                album.artist = CompilationService::nom_various_artists();
+            }
+
             ValidatorService::evaluate_album_status(album);
 
             // Trier les pistes par numéro
@@ -0,0 +1,42 @@
+use crate::db::Database;
+use crate::models::AppError;
+use rusqlite::{params, OptionalExtension};
+
+/// Réglages côté backend (clé/valeur), pour ce que le frontend ne peut pas garder en localStorage
+pub struct SettingsService;
+
+impl SettingsService {
+    pub fn lire(db: &Database, key: &str) -> Result<Option<String>, AppError> {
+        let conn = db
+            .conn
+            .lock()
+            .map_err(|_| AppError::DatabaseError("Lock error".into()))?;
+
+        let value = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    pub fn lire_ou(db: &Database, key: &str, default: &str) -> Result<String, AppError> {
+        Ok(Self::lire(db, key)?.unwrap_or_else(|| default.to_string()))
+    }
+
+    pub fn ecrire(db: &Database, key: &str, value: &str) -> Result<(), AppError> {
+        let conn = db
+            .conn
+            .lock()
+            .map_err(|_| AppError::DatabaseError("Lock error".into()))?;
+
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::models::{Album, AppError, SilenceReport, TrimProposal};
+use rodio::{Decoder, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+// -60 dBFS : en dessous, un échantillon compte comme silence (souffle de vinyle compris)
+const SEUIL_SILENCE: f32 = 0.001;
+// Pleine échelle, à un pas de quantification 16 bits près
+const SEUIL_ECRETAGE: f32 = 0.9999;
+const PLAGE_ECRETAGE_MIN: u64 = 3;
+// Silence au-delà duquel un rognage est proposé, et silence laissé en place
+pub const SILENCE_DEBUT_MAX_SEC: f64 = 2.0;
+pub const SILENCE_FIN_MAX_SEC: f64 = 5.0;
+const MARGE_DEBUT_SEC: f64 = 0.2;
+const MARGE_FIN_SEC: f64 = 1.0;
+const FORMATS_ROGNABLES: [&str; 2] = ["flac", "wav"];
+
+/// Mesure des silences de début / fin et de l'écrêtage, avec proposition de rognage sans perte
+pub struct SilenceService;
+
+impl SilenceService {
+    /// Rapports des pistes décodables, et une erreur par piste illisible (AAC, fichier abîmé...)
+    pub fn analyser_album(album: &Album, proposer: bool) -> (Vec<SilenceReport>, Vec<String>) {
+        let (mut rapports, mut errors) = (Vec::new(), Vec::new());
+        for track in &album.tracks {
+            match Self::analyser_fichier(Path::new(&track.path), proposer) {
+                Ok(rapport) => rapports.push(rapport),
+                Err(e) => errors.push(format!("{}: {}", track.filename, e)),
+            }
+        }
+        (rapports, errors)
+    }
+
+    pub fn analyser_fichier(path: &Path, proposer: bool) -> Result<SilenceReport, AppError> {
+        let file = File::open(path)?;
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| AppError::Audio(format!("{}: {}", path.display(), e)))?
+            .convert_samples::<f32>();
+        let channels = usize::from(source.channels().max(1));
+        let sample_rate = source.sample_rate();
+
+        let mut premiere: Option<u64> = None; // Première et dernière trame audibles
+        let mut derniere = 0u64;
+        let mut plages = vec![0u64; channels]; // Longueur de la suite écrêtée en cours, par canal
+        let (mut clipped_runs, mut clipped_samples) = (0u64, 0u64);
+        let mut n = 0u64;
+        for x in source {
+            let (trame, canal) = (n / channels as u64, (n % channels as u64) as usize);
+            n += 1;
+            if x.abs() >= SEUIL_SILENCE {
+                premiere.get_or_insert(trame);
+                derniere = trame;
+            }
+            if x.abs() >= SEUIL_ECRETAGE {
+                plages[canal] += 1;
+                if plages[canal] == PLAGE_ECRETAGE_MIN {
+                    clipped_runs += 1;
+                    clipped_samples += PLAGE_ECRETAGE_MIN;
+                } else if plages[canal] > PLAGE_ECRETAGE_MIN {
+                    clipped_samples += 1;
+                }
+            } else {
+                plages[canal] = 0;
+            }
+        }
+
+        let trames = n / channels as u64;
+        let secondes = |t: u64| t as f64 / f64::from(sample_rate.max(1));
+        let (debut, fin) = match premiere {
+            Some(p) => (p, derniere + 1),
+            None => (trames, trames), // Piste entièrement silencieuse
+        };
+        let mut rapport = SilenceReport {
+            path: path.to_string_lossy().to_string(),
+            duration_sec: secondes(trames),
+            leading_silence_sec: secondes(debut),
+            trailing_silence_sec: secondes(trames - fin),
+            clipped_runs,
+            clipped_samples,
+            trim: None,
+        };
+        if proposer && premiere.is_some() && Self::rognable(path) {
+            rapport.trim = Self::proposer(&rapport, sample_rate, debut, fin, trames);
+        }
+        Ok(rapport)
+    }
+
+    fn rognable(path: &Path) -> bool {
+        path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| FORMATS_ROGNABLES.contains(&e.as_str()))
+    }
+
+    /// Ne rogne que les silences trop longs, en laissant une courte marge autour de l'audio
+    fn proposer(
+        rapport: &SilenceReport,
+        sample_rate: u32,
+        debut: u64,
+        fin: u64,
+        trames: u64,
+    ) -> Option<TrimProposal> {
+        let marge = |s: f64| (s * f64::from(sample_rate)) as u64;
+        let start_frame = if rapport.leading_silence_sec > SILENCE_DEBUT_MAX_SEC {
+            debut.saturating_sub(marge(MARGE_DEBUT_SEC))
+        } else {
+            0
+        };
+        let end_frame = if rapport.trailing_silence_sec > SILENCE_FIN_MAX_SEC {
+            (fin + marge(MARGE_FIN_SEC)).min(trames)
+        } else {
+            trames
+        };
+        (start_frame > 0 || end_frame < trames).then(|| TrimProposal {
+            path: rapport.path.clone(),
+            sample_rate,
+            start_frame,
+            end_frame,
+        })
+    }
+}
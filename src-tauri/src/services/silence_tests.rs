@@ -0,0 +1,136 @@
+use super::converter::ConverterService;
+use super::pcm::Pcm;
+use super::silence::SilenceService;
+use super::test_fixtures::{dossier_temp, ecrire_wav, pcm16};
+use super::validator_silence::regles_silence;
+use super::{AudioService, ValidatorService};
+use crate::models::{Album, ConvertFormat, ConvertOptions, ConvertQuality, Track, TrimProposal};
+use lofty::{MimeType, Picture, PictureType};
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+const TAUX: u32 = 44_100;
+
+// WAV PCM 16 bits mono : silence, sinus 440 Hz écrêté (amplitude 1,3 ramenée à la pleine échelle), silence
+fn ecrire_piste(path: &Path, avant: u32, son: u32, apres: u32) {
+    let echantillons: Vec<i32> = (0..TAUX * (avant + son + apres))
+        .map(|n| {
+            if (TAUX * avant..TAUX * (avant + son)).contains(&n) {
+                pcm16((1.3 * (2.0 * PI * 440.0 * f64::from(n) / f64::from(TAUX)).sin()).clamp(-1.0, 1.0))
+            } else {
+                0
+            }
+        })
+        .collect();
+    ecrire_wav(path, TAUX, 1, 16, &echantillons);
+}
+
+#[test]
+fn test_silences_ecretage_et_rognage() {
+    let dir = dossier_temp("silence");
+    let piste = dir.join("01 - Face A.wav");
+    ecrire_piste(&piste, 3, 1, 7);
+
+    let rapport = SilenceService::analyser_fichier(&piste, true).unwrap();
+    assert!((rapport.duration_sec - 11.0).abs() < 1e-6);
+    assert!((rapport.leading_silence_sec - 3.0).abs() < 0.01);
+    assert!((rapport.trailing_silence_sec - 7.0).abs() < 0.01);
+    // Un plateau écrêté par demi-période de 440 Hz
+    assert!(rapport.clipped_runs > 800, "{:?}", rapport);
+
+    // Marges de 0,2 s avant et 1 s après le son
+    let trim = rapport.trim.clone().unwrap();
+    assert!((trim.start_frame as i64 - (2.8 * TAUX as f64) as i64).abs() < 50);
+    assert!((trim.end_frame as i64 - (5.0 * TAUX as f64) as i64).abs() < 50);
+
+    let rognee = dir.join("rognee.wav");
+    ConverterService::new()
+        .rogner(&trim, &rognee.to_string_lossy())
+        .unwrap();
+    let apres = SilenceService::analyser_fichier(&rognee, true).unwrap();
+    assert!((apres.duration_sec - 2.2).abs() < 0.01);
+    assert!((apres.leading_silence_sec - 0.2).abs() < 0.01);
+    assert!(apres.trim.is_none());
+
+    // FLAC : découpe native à la trame près, tag et pochette recopiés
+    let flac = dir.join("02 - Face B.flac");
+    let options = ConvertOptions {
+        format: ConvertFormat::Flac,
+        quality: ConvertQuality::Standard,
+        bitrate_kbps: None,
+    };
+    let service = ConverterService::new();
+    service
+        .convertir(&piste.to_string_lossy(), &flac.to_string_lossy(), &options)
+        .unwrap();
+    let audio = AudioService::new();
+    let mut tags = audio.lire_metadonnees(&flac.to_string_lossy()).unwrap();
+    tags.title = "Face B".into();
+    audio.ecrire_metadonnees(&tags).unwrap();
+    let pochette = Picture::new_unchecked(
+        PictureType::CoverFront,
+        MimeType::Png,
+        None,
+        vec![0x89, b'P', b'N', b'G', 1, 2, 3],
+    );
+    audio
+        .poser_cover(&flac.to_string_lossy(), pochette.clone())
+        .unwrap();
+    let trim_flac = SilenceService::analyser_fichier(&flac, true)
+        .unwrap()
+        .trim
+        .unwrap();
+    assert_eq!(
+        trim_flac,
+        TrimProposal {
+            path: flac.to_string_lossy().to_string(),
+            ..trim.clone()
+        }
+    );
+    let flac_rognee = dir.join("rognee.flac");
+    service
+        .rogner(&trim_flac, &flac_rognee.to_string_lossy())
+        .unwrap();
+    let mut pcm = Pcm::ouvrir(&flac_rognee).unwrap();
+    let (mut trames, mut tampon) = (0, Vec::new());
+    loop {
+        match pcm.lire(&mut tampon, 4096).unwrap() {
+            0 => break,
+            n => trames += n as u64,
+        }
+    }
+    assert_eq!(trames, trim.end_frame - trim.start_frame);
+    let rognee_tags = audio
+        .lire_metadonnees(&flac_rognee.to_string_lossy())
+        .unwrap();
+    assert_eq!(rognee_tags.title, "Face B");
+    let relue = audio.lire_cover(&flac_rognee.to_string_lossy()).unwrap();
+    assert_eq!(relue.unwrap().data(), pochette.data());
+
+    let mut album = Album::new(
+        dir.to_string_lossy().to_string(),
+        "Test".into(),
+        "Test".into(),
+    );
+    album.tracks.push(Track::new(
+        piste.to_string_lossy().to_string(),
+        "01 - Face A.wav".into(),
+    ));
+    // Une piste que rodio ne décode pas est signalée sans arrêter l'album
+    let aac = dir.join("03 - Bonus.m4a");
+    fs::write(&aac, b"pas de l'audio").unwrap();
+    album.tracks.push(Track::new(
+        aac.to_string_lossy().to_string(),
+        "03 - Bonus.m4a".into(),
+    ));
+    let (rapports, errors) = SilenceService::analyser_album(&album, false);
+    assert_eq!(rapports.len(), 1);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("03 - Bonus.m4a"));
+    ValidatorService::evaluer_avec(&mut album, regles_silence(&[rapport]));
+    assert!(album.validation.iter().any(|i| i.code == "LONG_SILENCE"));
+    assert!(album.validation.iter().any(|i| i.code == "CLIPPING"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,230 @@
+use super::spectrogram;
+use crate::models::{Album, AppError, SpectralReport};
+use rodio::{Decoder, Source};
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+const TAILLE_FENETRE: usize = 4096;
+// Nombre maximal de fenêtres analysées, réparties sur toute la piste
+const MAX_FENETRES: usize = 600;
+// Niveau sous la référence (1-6 kHz) en dessous duquel une bande est considérée vide
+const SEUIL_DB: f32 = 60.0;
+// Marge au-dessus du plancher de bruit pour qu'une bande compte comme occupée
+const MARGE_PLANCHER_DB: f32 = 10.0;
+// Chute minimale autour de la coupure pour parler d'un plateau d'encodeur et non d'un déclin naturel
+const CHUTE_MIN_DB: f32 = 20.0;
+// Au-delà, la coupure correspond à un encodage très haut débit ou à un vrai fichier sans perte
+const COUPURE_MAX_HZ: f32 = 20_500.0;
+
+const FORMATS_SANS_PERTE: [&str; 6] = ["flac", "wav", "aif", "aiff", "ape", "wv"];
+
+/// Spectre moyen d'une piste et, si demandé, les colonnes du spectrogramme (en dB)
+pub struct Spectre {
+    pub sample_rate: u32,
+    pub moyenne_db: Vec<f32>,
+    pub colonnes: Vec<Vec<f32>>,
+}
+
+/// Détection des faux fichiers sans perte (transcodés depuis un MP3/AAC)
+pub struct SpectralService;
+
+impl SpectralService {
+    pub fn est_sans_perte(path: &Path) -> bool {
+        path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| FORMATS_SANS_PERTE.contains(&e.as_str()))
+    }
+
+    /// Analyse les pistes sans perte de l'album ; les spectrogrammes sont écrits dans `dossier_png` si fourni
+    pub fn analyser_album(
+        album: &Album,
+        dossier_png: Option<&Path>,
+    ) -> Result<Vec<SpectralReport>, AppError> {
+        let mut rapports = Vec::new();
+        for track in &album.tracks {
+            let path = Path::new(&track.path);
+            if !Self::est_sans_perte(path) {
+                continue;
+            }
+            let png = dossier_png.map(|d| d.join(format!("{:x}.png", md5::compute(&track.path))));
+            rapports.push(Self::analyser_fichier(path, png.as_deref())?);
+        }
+        Ok(rapports)
+    }
+
+    pub fn analyser_fichier(path: &Path, png: Option<&Path>) -> Result<SpectralReport, AppError> {
+        let spectre = Self::spectre(path, png.is_some())?;
+        let (cutoff_hz, drop_db) = Self::estimer_coupure(&spectre.moyenne_db, spectre.sample_rate);
+
+        let nyquist = spectre.sample_rate as f32 / 2.0;
+        let lossy_suspect =
+            cutoff_hz < COUPURE_MAX_HZ && cutoff_hz < nyquist * 0.95 && drop_db >= CHUTE_MIN_DB;
+
+        let spectrogram_path = match png {
+            Some(png) => {
+                spectrogram::rendre(&spectre.colonnes, png)?;
+                Some(png.to_string_lossy().to_string())
+            }
+            None => None,
+        };
+
+        Ok(SpectralReport {
+            path: path.to_string_lossy().to_string(),
+            sample_rate: spectre.sample_rate,
+            cutoff_hz,
+            drop_db,
+            lossy_suspect,
+            suspected_source: lossy_suspect.then(|| Self::source_probable(cutoff_hz)),
+            spectrogram_path,
+        })
+    }
+
+    /// Plateaux typiques des encodeurs avec perte
+    fn source_probable(cutoff_hz: f32) -> String {
+        let source = if cutoff_hz <= 16_500.0 {
+            "MP3 128 kbps ou moins"
+        } else if cutoff_hz <= 19_500.0 {
+            "MP3/AAC 192-256 kbps"
+        } else {
+            "MP3 320 kbps"
+        };
+        format!("{} (coupure ~{:.1} kHz)", source, cutoff_hz / 1000.0)
+    }
+
+    /// Décode la piste en mono et calcule le spectre moyen sur des fenêtres de Blackman-Harris réparties.
+    /// Les fenêtres sont prises au fil du décodage : seule la dernière reste en mémoire.
+    pub fn spectre(path: &Path, avec_colonnes: bool) -> Result<Spectre, AppError> {
+        let file =
+            File::open(path).map_err(|e| AppError::Io(format!("Erreur lecture fichier: {}", e)))?;
+        let decoder = Decoder::new(BufReader::new(file))
+            .map_err(|e| AppError::Audio(format!("Format non décodable: {}", e)))?;
+        let sample_rate = decoder.sample_rate();
+        let channels = usize::from(decoder.channels().max(1));
+        // La durée annoncée suffit à répartir les fenêtres sans parcourir la piste deux fois
+        let trames = decoder
+            .total_duration()
+            .map(|d| (d.as_secs_f64() * f64::from(sample_rate)).round() as usize)
+            .ok_or_else(|| AppError::Audio("Durée de la piste inconnue".into()))?;
+        if trames < TAILLE_FENETRE {
+            return Err(AppError::Audio("Piste trop courte pour l'analyse".into()));
+        }
+
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(TAILLE_FENETRE);
+        // Blackman-Harris : lobes secondaires à -92 dB, pour ne pas étaler les aigus au-delà de la coupure
+        let fenetre: Vec<f32> = (0..TAILLE_FENETRE)
+            .map(|i| {
+                let x = 2.0 * PI * i as f32 / (TAILLE_FENETRE - 1) as f32;
+                0.35875 - 0.48829 * x.cos() + 0.14128 * (2.0 * x).cos() - 0.01168 * (3.0 * x).cos()
+            })
+            .collect();
+
+        let disponible = trames - TAILLE_FENETRE;
+        let fenetres = (disponible / TAILLE_FENETRE + 1).min(MAX_FENETRES);
+        let debut = |f: usize| {
+            if fenetres > 1 {
+                f * disponible / (fenetres - 1)
+            } else {
+                0
+            }
+        };
+        let bins = TAILLE_FENETRE / 2;
+        let mut puissance = vec![0f64; bins];
+        let mut colonnes = Vec::new();
+        let mut buffer = vec![Complex::new(0f32, 0f32); TAILLE_FENETRE];
+
+        // Tampon circulaire des TAILLE_FENETRE dernières trames mono
+        let mut anneau = vec![0f32; TAILLE_FENETRE];
+        let (mut position, mut analysees) = (0usize, 0usize);
+        let mut echantillons = decoder.convert_samples::<f32>();
+        let mut trame = Vec::with_capacity(channels);
+        while analysees < fenetres {
+            trame.clear();
+            trame.extend(echantillons.by_ref().take(channels));
+            if trame.len() < channels {
+                break; // Piste plus courte qu'annoncé : on garde les fenêtres déjà analysées
+            }
+            anneau[position % TAILLE_FENETRE] = trame.iter().sum::<f32>() / channels as f32;
+            position += 1;
+            if position < TAILLE_FENETRE || position - TAILLE_FENETRE != debut(analysees) {
+                continue;
+            }
+
+            // La trame la plus ancienne du tampon est celle qui sera écrasée ensuite
+            for (i, c) in buffer.iter_mut().enumerate() {
+                *c = Complex::new(anneau[(position + i) % TAILLE_FENETRE] * fenetre[i], 0.0);
+            }
+            fft.process(&mut buffer);
+            analysees += 1;
+
+            let colonne: Vec<f32> = buffer[..bins].iter().map(|c| c.norm_sqr()).collect();
+            for (acc, p) in puissance.iter_mut().zip(&colonne) {
+                *acc += f64::from(*p);
+            }
+            if avec_colonnes {
+                colonnes.push(colonne.into_iter().map(en_db).collect());
+            }
+        }
+        if analysees == 0 {
+            return Err(AppError::Audio("Piste trop courte pour l'analyse".into()));
+        }
+
+        let moyenne_db = puissance
+            .into_iter()
+            .map(|p| en_db((p / analysees as f64) as f32))
+            .collect();
+        Ok(Spectre {
+            sample_rate,
+            moyenne_db,
+            colonnes,
+        })
+    }
+
+    /// Plus haute fréquence encore au-dessus du seuil, et chute mesurée autour d'elle
+    pub fn estimer_coupure(moyenne_db: &[f32], sample_rate: u32) -> (f32, f32) {
+        let hz_par_bin = sample_rate as f32 / TAILLE_FENETRE as f32;
+        let bin = |hz: f32| ((hz / hz_par_bin) as usize).min(moyenne_db.len() - 1);
+
+        // Lissage en puissance sur ~200 Hz pour ignorer les raies isolées
+        let demi = (100.0 / hz_par_bin).ceil() as usize;
+        let lineaire: Vec<f32> = moyenne_db.iter().map(|db| 10f32.powf(db / 10.0)).collect();
+        let lisse: Vec<f32> = (0..lineaire.len())
+            .map(|i| {
+                let bande = &lineaire[i.saturating_sub(demi)..(i + demi + 1).min(lineaire.len())];
+                en_db(moyenne(bande))
+            })
+            .collect();
+
+        // Le bruit de quantification remplit la bande coupée : le seuil reste au-dessus de ce plancher
+        let reference = moyenne(&lisse[bin(1000.0)..=bin(6000.0)]);
+        let mut tries = lisse.clone();
+        tries.sort_by(|a, b| a.total_cmp(b));
+        let plancher = tries[tries.len() / 20];
+        // Sur un spectre plein, le percentile bas n'est pas du bruit : on le borne sous la référence
+        let seuil = (reference - SEUIL_DB)
+            .max((plancher + MARGE_PLANCHER_DB).min(reference - CHUTE_MIN_DB));
+        let coupure = (0..lisse.len())
+            .rev()
+            .find(|&i| lisse[i] > seuil)
+            .unwrap_or(0);
+        let cutoff_hz = coupure as f32 * hz_par_bin;
+
+        let dessous = &lisse[bin(cutoff_hz - 2000.0)..=bin(cutoff_hz - 1000.0)];
+        let dessus = &lisse[bin(cutoff_hz + 1000.0)..=bin(cutoff_hz + 2000.0)];
+        (cutoff_hz, moyenne(dessous) - moyenne(dessus))
+    }
+}
+
+fn en_db(puissance: f32) -> f32 {
+    10.0 * (puissance + 1e-12).log10()
+}
+
+fn moyenne(valeurs: &[f32]) -> f32 {
+    if valeurs.is_empty() {
+        return 0.0;
+    }
+    valeurs.iter().sum::<f32>() / valeurs.len() as f32
+}
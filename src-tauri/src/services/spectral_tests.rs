@@ -0,0 +1,66 @@
+use super::test_fixtures::{self, dossier_temp};
+use super::*;
+use crate::models::{Album, Track};
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+
+const TAUX: u32 = 44100;
+
+// WAV PCM 16 bits mono de 2 secondes : somme de sinus de phases pseudo-aléatoires jusqu'à `max_hz`
+fn ecrire_wav(path: &Path, max_hz: f32) {
+    let trames = TAUX * 2;
+    let frequences: Vec<(f32, f32)> = (1..)
+        .map(|k| k as f32 * 150.0)
+        .take_while(|f| *f <= max_hz)
+        .enumerate()
+        .map(|(i, f)| (f, (i * 7919 % 360) as f32 / 360.0 * 2.0 * PI))
+        .collect();
+    // Assez bas pour ne jamais écrêter : l'écrêtage remplirait tout le spectre
+    let amplitude = 0.15 / (frequences.len() as f32).sqrt();
+
+    let echantillons: Vec<i32> = (0..trames)
+        .map(|n| {
+            let t = n as f32 / TAUX as f32;
+            let v: f32 = frequences
+                .iter()
+                .map(|(f, phase)| (2.0 * PI * f * t + phase).sin())
+                .sum::<f32>()
+                * amplitude;
+            (v.clamp(-1.0, 1.0) * 32767.0) as i32
+        })
+        .collect();
+    test_fixtures::ecrire_wav(path, TAUX, 1, 16, &echantillons);
+}
+
+#[test]
+fn test_detection_faux_sans_perte() {
+    let dir = dossier_temp("spectral");
+    let transcode = dir.join("01 - Transcode.wav");
+    let pleine_bande = dir.join("02 - Pleine bande.wav");
+    ecrire_wav(&transcode, 16_000.0);
+    ecrire_wav(&pleine_bande, 21_900.0);
+
+    let mut album = Album::new(
+        dir.to_string_lossy().to_string(),
+        "Test".into(),
+        "Test".into(),
+    );
+    for path in [&transcode, &pleine_bande] {
+        let nom = path.file_name().unwrap().to_string_lossy().to_string();
+        album
+            .tracks
+            .push(Track::new(path.to_string_lossy().to_string(), nom));
+    }
+
+    let rapports = SpectralService::analyser_album(&album, Some(&dir.join("png"))).unwrap();
+    assert!(rapports[0].lossy_suspect, "{:?}", rapports[0]);
+    assert!((15_500.0..16_500.0).contains(&rapports[0].cutoff_hz));
+    assert!(!rapports[1].lossy_suspect, "{:?}", rapports[1]);
+    assert!(rapports[1].cutoff_hz > 21_000.0);
+    assert!(Path::new(rapports[0].spectrogram_path.as_ref().unwrap()).exists());
+
+    ValidatorService::evaluer_avec(&mut album, validator_spectral::regles_spectrales(&rapports));
+    assert!(album.validation.iter().any(|i| i.code == "LOSSY_SOURCE"));
+    fs::remove_dir_all(&dir).unwrap();
+}
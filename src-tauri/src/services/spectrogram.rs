@@ -0,0 +1,46 @@
+use crate::models::AppError;
+use image::{Rgb, RgbImage};
+use std::path::Path;
+
+const HAUTEUR: u32 = 256;
+// Dynamique affichée sous le maximum du spectrogramme
+const DYNAMIQUE_DB: f32 = 100.0;
+
+/// Écrit un spectrogramme PNG : temps en abscisse, fréquence linéaire en ordonnée (aigus en haut)
+pub fn rendre(colonnes: &[Vec<f32>], dest: &Path) -> Result<(), AppError> {
+    let bins = colonnes.first().map(|c| c.len()).unwrap_or(0);
+    if bins == 0 {
+        return Err(AppError::Audio("Spectre vide".into()));
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::Io(format!("Erreur création dossier: {}", e)))?;
+    }
+
+    let max = colonnes.iter().flatten().copied().fold(f32::MIN, f32::max);
+    let par_ligne = bins.div_ceil(HAUTEUR as usize);
+
+    let mut img = RgbImage::new(colonnes.len() as u32, HAUTEUR);
+    for (x, colonne) in colonnes.iter().enumerate() {
+        for y in 0..HAUTEUR {
+            let debut = (y as usize * par_ligne).min(bins - 1);
+            let fin = (debut + par_ligne).min(bins);
+            let niveau = colonne[debut..fin].iter().copied().fold(f32::MIN, f32::max);
+            let t = ((niveau - max + DYNAMIQUE_DB) / DYNAMIQUE_DB).clamp(0.0, 1.0);
+            img.put_pixel(x as u32, HAUTEUR - 1 - y, couleur(t));
+        }
+    }
+
+    img.save(dest)
+        .map_err(|e| AppError::Io(format!("Erreur écriture spectrogramme: {}", e)))
+}
+
+// Palette noir → violet → orange → jaune
+fn couleur(t: f32) -> Rgb<u8> {
+    let canal = |v: f32| (v.clamp(0.0, 1.0) * 255.0) as u8;
+    Rgb([
+        canal(t * 1.6),
+        canal((t - 0.4) * 1.7),
+        canal(if t < 0.5 { t * 1.2 } else { (1.0 - t) * 1.2 }),
+    ])
+}
@@ -0,0 +1,54 @@
+// Fichiers audio et dossiers temporaires partagés par les *_tests.rs
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Dossier temporaire propre au test et au processus
+pub fn dossier_temp(nom: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tagotomatik_{}_{}", nom, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Échantillon 16 bits d'une valeur de pleine échelle [-1, 1]
+pub fn pcm16(v: f64) -> i32 {
+    (v * 32767.0).round() as i32
+}
+
+/// WAV PCM entier, échantillons entrelacés sur 16, 24 ou 32 bits
+pub fn ecrire_wav(path: &Path, taux: u32, channels: u16, bits: u16, echantillons: &[i32]) {
+    ecrire_wav_annonce(path, taux, channels, bits, echantillons, echantillons.len());
+}
+
+/// Comme `ecrire_wav`, mais l'en-tête annonce `annonces` échantillons : plus que fournis = fichier tronqué
+pub fn ecrire_wav_annonce(
+    path: &Path,
+    taux: u32,
+    channels: u16,
+    bits: u16,
+    echantillons: &[i32],
+    annonces: usize,
+) {
+    let largeur = usize::from(bits / 8);
+    let taille = (annonces * largeur) as u32;
+    let bloc = channels * bits / 8;
+    let mut data = Vec::new();
+    data.extend_from_slice(b"RIFF");
+    data.extend_from_slice(&(36 + taille + taille % 2).to_le_bytes());
+    data.extend_from_slice(b"WAVEfmt ");
+    data.extend_from_slice(&16u32.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    data.extend_from_slice(&channels.to_le_bytes());
+    data.extend_from_slice(&taux.to_le_bytes());
+    data.extend_from_slice(&(taux * u32::from(bloc)).to_le_bytes());
+    data.extend_from_slice(&bloc.to_le_bytes());
+    data.extend_from_slice(&bits.to_le_bytes());
+    data.extend_from_slice(b"data");
+    data.extend_from_slice(&taille.to_le_bytes());
+    for x in echantillons {
+        data.extend_from_slice(&x.to_le_bytes()[..largeur]);
+    }
+    if echantillons.len() == annonces && taille % 2 == 1 {
+        data.push(0); // Octet de bourrage RIFF
+    }
+    fs::write(path, data).unwrap();
+}
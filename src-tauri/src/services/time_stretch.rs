@@ -0,0 +1,205 @@
+use super::player_transport::Transport;
+use rodio::source::SeekError;
+use rodio::Source;
+use std::f32::consts::PI;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Fenêtre d'analyse (~46 ms à 44,1 kHz), recouvrement de moitié
+const FENETRE: usize = 2048;
+const PAS: usize = FENETRE / 2;
+// Décalage maximal autour de la position idéale pour trouver la jonction la plus cohérente
+const TOLERANCE: usize = 256;
+// Sous-échantillonnage de la recherche : la corrélation n'a pas besoin de chaque trame
+const PAS_RECHERCHE: usize = 2;
+const PAS_CORRELATION: usize = 8;
+
+/// Changement de vitesse sans changement de hauteur (WSOLA) ; à vitesse 1 la source passe intacte
+pub struct Etirement<S> {
+    source: S,
+    transport: Arc<Transport>,
+    channels: usize,
+    hann: Vec<f32>,
+    actif: bool,
+    entree: Vec<f32>,
+    base: u64, // Trame de la source correspondant au début de `entree`
+    epuisee: bool,
+    analyse: f64,           // Position idéale du prochain segment
+    precedent: Option<u64>, // Début du dernier segment retenu
+    cumul: Vec<f32>,        // Somme des fenêtres en cours de recouvrement
+    sortie: Vec<f32>,
+    lu: usize,
+}
+
+impl<S: Source<Item = f32>> Etirement<S> {
+    pub fn new(source: S, transport: Arc<Transport>) -> Self {
+        let channels = usize::from(source.channels().max(1));
+        Self {
+            source,
+            transport,
+            channels,
+            // Hann périodique : deux fenêtres décalées d'un demi-pas somment exactement à 1
+            hann: (0..FENETRE)
+                .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / FENETRE as f32).cos())
+                .collect(),
+            actif: false,
+            entree: Vec::new(),
+            base: 0,
+            epuisee: false,
+            analyse: 0.0,
+            precedent: None,
+            cumul: vec![0.0; FENETRE * channels],
+            sortie: Vec::new(),
+            lu: 0,
+        }
+    }
+
+    fn reinitialiser(&mut self) {
+        self.actif = false;
+        self.entree.clear();
+        self.base = 0;
+        self.epuisee = false;
+        self.analyse = 0.0;
+        self.precedent = None;
+        self.cumul.iter_mut().for_each(|x| *x = 0.0);
+        self.sortie.clear();
+        self.lu = 0;
+    }
+
+    fn lues(&self) -> u64 {
+        self.base + (self.entree.len() / self.channels) as u64
+    }
+
+    fn remplir(&mut self, jusqua: u64) {
+        while !self.epuisee && self.lues() < jusqua {
+            for _ in 0..self.channels {
+                match self.source.next() {
+                    Some(x) => self.entree.push(x),
+                    None => {
+                        self.epuisee = true;
+                        // Une trame incomplète ne sert à rien
+                        let complet = self.entree.len() - self.entree.len() % self.channels;
+                        self.entree.truncate(complet);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Échantillon de la source (silence au-delà de la fin)
+    fn echantillon(&self, trame: u64, canal: usize) -> f32 {
+        let i = (trame - self.base) as usize * self.channels + canal;
+        self.entree.get(i).copied().unwrap_or(0.0)
+    }
+
+    fn mono(&self, trame: u64) -> f32 {
+        (0..self.channels).map(|c| self.echantillon(trame, c)).sum()
+    }
+
+    /// Segment le plus semblable à la continuation naturelle du précédent
+    fn choisir(&self, cible: u64) -> u64 {
+        let Some(naturel) = self.precedent.map(|p| p + PAS as u64) else {
+            return cible;
+        };
+        let debut = cible.saturating_sub(TOLERANCE as u64).max(self.base);
+        let mut meilleur = (cible, f32::MIN);
+        for k in (debut..=cible + TOLERANCE as u64).step_by(PAS_RECHERCHE) {
+            let score: f32 = (0..PAS)
+                .step_by(PAS_CORRELATION)
+                .map(|j| self.mono(naturel + j as u64) * self.mono(k + j as u64))
+                .sum();
+            if score > meilleur.1 {
+                meilleur = (k, score);
+            }
+        }
+        meilleur.0
+    }
+
+    /// Produit PAS trames de sortie ; faux quand la source est entièrement rendue
+    fn etape(&mut self) -> bool {
+        let cible = self.analyse.round() as u64;
+        self.remplir(cible + (TOLERANCE + FENETRE) as u64);
+        if self.epuisee && cible >= self.lues() {
+            // Dernière moitié de fenêtre encore en attente, puis fin
+            if self.cumul.iter().all(|x| *x == 0.0) {
+                return false;
+            }
+            self.sortie = self.cumul.drain(..PAS * self.channels).collect();
+            self.cumul.iter_mut().for_each(|x| *x = 0.0);
+            self.cumul.resize(FENETRE * self.channels, 0.0);
+            self.lu = 0;
+            return true;
+        }
+
+        let k = self.choisir(cible);
+        for j in 0..FENETRE {
+            let w = self.hann[j];
+            for c in 0..self.channels {
+                self.cumul[j * self.channels + c] += w * self.echantillon(k + j as u64, c);
+            }
+        }
+        self.sortie = self.cumul.drain(..PAS * self.channels).collect();
+        self.cumul.resize(FENETRE * self.channels, 0.0);
+        self.lu = 0;
+
+        self.precedent = Some(k);
+        self.analyse += PAS as f64 * f64::from(self.transport.vitesse());
+        // Oublie l'entrée qu'aucun segment futur ne pourra relire
+        let garder = (k + PAS as u64)
+            .min((self.analyse.round() as u64).saturating_sub(TOLERANCE as u64))
+            .max(self.base);
+        let oublier = ((garder - self.base) as usize * self.channels).min(self.entree.len());
+        self.entree.drain(..oublier);
+        self.base = garder;
+        true
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Etirement<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if !self.actif {
+            if self.transport.vitesse() == 1.0 {
+                return self.source.next();
+            }
+            // La suite de la source devient l'entrée de l'étirement, sans rien perdre
+            self.actif = true;
+        }
+        if self.lu >= self.sortie.len() && !self.etape() {
+            return None;
+        }
+        self.lu += 1;
+        self.sortie.get(self.lu - 1).copied()
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Etirement<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        if self.actif {
+            None
+        } else {
+            self.source.current_frame_len()
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let vitesse = self.transport.vitesse();
+        self.source.total_duration().map(|d| d.div_f32(vitesse))
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.source.try_seek(pos)?;
+        self.reinitialiser();
+        Ok(())
+    }
+}
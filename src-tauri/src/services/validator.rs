@@ -1,181 +1,212 @@
-use crate::models::{Album, AlbumStatus};
-use super::InspectorService;
+use super::validator_integrity::regles_integrite;
+use super::validator_silence::regles_silence;
+use super::validator_spectral::regles_spectrales;
+use super::validator_rules::{self, ValidationRule};
+use super::{InspectorService, SettingsService};
+use crate::db::Database;
+use crate::models::{
+    Album, AlbumStatus, AppError, Severity, ValidationIssue, ValidationRuleInfo,
+};
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::RwLock;
+
+const SETTING_PREFIX: &str = "validator.rule.";
+
+/// Réglage par code de règle : None = désactivée, Some(gravité) = gravité imposée.
+pub type ReglagesRegles = HashMap<String, Option<Severity>>;
+
+lazy_static! {
+    // Gardé en mémoire car le validateur est appelé par le scanner, sans accès à la base.
+    static ref CONFIG: RwLock<ReglagesRegles> = RwLock::new(HashMap::new());
+}
 
 pub struct ValidatorService;
 
 impl ValidatorService {
     pub fn evaluate_album_status(album: &mut Album) {
+        Self::evaluer_avec(album, Vec::new());
+    }
+
+    /// Réévalue l'album avec les règles d'une analyse à la demande (intégrité, spectre).
+    /// Les constats des autres analyses déjà présents sur l'album sont conservés.
+    pub fn evaluer_avec(album: &mut Album, analyses: Vec<Box<dyn ValidationRule>>) {
+        let config = CONFIG.read().map(|c| c.clone()).unwrap_or_default();
+        Self::evaluer_selon(album, analyses, &config);
+    }
+
+    /// Comme `evaluer_avec`, avec un réglage des règles fourni par l'appelant
+    pub fn evaluer_selon(
+        album: &mut Album,
+        analyses: Vec<Box<dyn ValidationRule>>,
+        config: &ReglagesRegles,
+    ) {
+        // Gravité par défaut des règles d'analyse, pour réappliquer le réglage courant aux constats conservés
+        let codes_analyse: HashMap<&str, Severity> = Self::regles_analyse()
+            .iter()
+            .map(|r| (r.code(), r.severite()))
+            .collect();
+        let reevalues: HashSet<&str> = analyses.iter().map(|r| r.code()).collect();
+        let conserves: Vec<ValidationIssue> = album
+            .validation
+            .drain(..)
+            .filter(|i| !reevalues.contains(i.code.as_str()))
+            .filter_map(|mut issue| {
+                let defaut = *codes_analyse.get(issue.code.as_str())?;
+                issue.severity = match config.get(&issue.code) {
+                    Some(None) => return None, // Règle désactivée depuis l'analyse
+                    Some(Some(severity)) => *severity,
+                    None => defaut,
+                };
+                Some(issue)
+            })
+            .collect();
         album.issues.clear();
 
-        Self::check_cover(album);
-        Self::check_playlist(album);
+        let mut regles = validator_rules::regles();
+        regles.extend(analyses);
 
-        if album.tracks.is_empty() {
-            album.status = AlbumStatus::Incomplete;
-            album.issues.push("Aucune piste audio trouvée".to_string());
-            return;
-        }
+        Self::refresh_cover(album);
+        album.has_playlist = InspectorService::has_playlist(Path::new(&album.path));
 
-        Self::check_tags(album);
-        Self::check_files(album);
+        for rule in regles {
+            let severity = match config.get(rule.code()) {
+                Some(None) => continue, // Règle désactivée
+                Some(Some(severity)) => *severity,
+                None => rule.severite(),
+            };
+            for constat in rule.verifier(album) {
+                album.validation.push(ValidationIssue {
+                    code: rule.code().to_string(),
+                    severity,
+                    message: constat.message,
+                    tracks: constat.tracks,
+                    fix: rule.correction(),
+                });
+            }
+        }
+        album.validation.extend(conserves);
 
-        if !album.issues.is_empty() {
-            album.status = AlbumStatus::Dirty;
+        if album.tracks.is_empty() {
+            album.validation.push(ValidationIssue {
+                code: "NO_TRACKS".to_string(),
+                severity: Severity::Error,
+                message: "Aucune piste audio trouvée".to_string(),
+                tracks: Vec::new(),
+                fix: None,
+            });
+        }
+
+        album.issues = album.validation.iter().map(|i| i.message.clone()).collect();
+
+        // Seules les erreurs bloquent : avertissements et infos laissent l'album "Clean"
+        album.status = if album.tracks.is_empty() {
+            AlbumStatus::Incomplete
+        } else if album.validation.iter().any(|i| i.severity == Severity::Error) {
+            AlbumStatus::Dirty
         } else {
-            album.status = AlbumStatus::Clean;
-        }
+            AlbumStatus::Clean
+        };
     }
 
-    fn check_cover(album: &mut Album) {
-        let mut cover_valid = false;
-        if let Some(path) = &album.cover_path {
-            if Path::new(path).exists() {
-                cover_valid = true;
-            }
-        }
+    fn refresh_cover(album: &mut Album) {
+        let cover_valid = album
+            .cover_path
+            .as_ref()
+            .is_some_and(|path| Path::new(path).exists());
 
         if !cover_valid {
             album.cover_path = InspectorService::find_cover_image(Path::new(&album.path));
         }
+    }
 
-        if album.cover_path.is_none() {
-            album.issues.push("Cover manquante".to_string());
-        }
+    // Règles qui ne relèvent rien sans les rapports d'une analyse lancée à la demande
+    fn regles_analyse() -> Vec<Box<dyn ValidationRule>> {
+        let mut regles = regles_integrite(&[]);
+        regles.extend(regles_spectrales(&[]));
+        regles.extend(regles_silence(&[]));
+        regles
     }
 
-    fn check_playlist(album: &mut Album) {
-        album.has_playlist = InspectorService::has_playlist(Path::new(&album.path));
-        if !album.has_playlist {
-            album.issues.push("Playlist manquante".to_string());
-        }
+    // Règles configurables, y compris celles des analyses à la demande
+    fn toutes_regles() -> Vec<Box<dyn ValidationRule>> {
+        let mut regles = validator_rules::regles();
+        regles.extend(Self::regles_analyse());
+        regles
     }
 
-    fn check_tags(album: &mut Album) {
-        // Année
-        if album.year.is_none() || album.year == Some(0) {
-            let has_missing_year = album
-                .tracks
-                .iter()
-                .any(|t| t.year.is_none() || t.year == Some(0));
-            if has_missing_year {
-                album.issues.push("Année manquante".to_string());
-            }
-        }
-
-        // Tags essentiels
-        let mut missing_titles = 0;
-        let mut missing_artists = 0;
-        let mut missing_albums = 0;
-        let mut missing_genres = 0;
-
-        for t in &album.tracks {
-            if t.title.trim().is_empty() {
-                missing_titles += 1;
-            }
-            if t.artist.trim().is_empty() {
-                missing_artists += 1;
-            }
-            if t.album.trim().is_empty() {
-                missing_albums += 1;
-            }
-            if t.genre.as_deref().unwrap_or("").trim().is_empty() {
-                missing_genres += 1;
+    /// Lit le réglage des règles enregistré en base
+    pub fn lire_config(db: &Database) -> Result<ReglagesRegles, AppError> {
+        let mut config = HashMap::new();
+        for rule in Self::toutes_regles() {
+            let key = format!("{}{}", SETTING_PREFIX, rule.code());
+            if let Some(value) = SettingsService::lire(db, &key)? {
+                config.insert(rule.code().to_string(), Severity::from_setting(&value));
             }
         }
+        Ok(config)
+    }
 
-        if missing_titles > 0 {
-            album
-                .issues
-                .push(format!("Titre manquant ({} pistes)", missing_titles));
-        }
-        if missing_artists > 0 {
-            album
-                .issues
-                .push(format!("Artiste manquant ({} pistes)", missing_artists));
-        }
-        if missing_albums > 0 {
-            album
-                .issues
-                .push(format!("Album manquant ({} pistes)", missing_albums));
-        }
-        if missing_genres > 0 {
-            album
-                .issues
-                .push(format!("Genre manquant ({} pistes)", missing_genres));
+    /// Charge le réglage des règles depuis la base (au démarrage et après modification)
+    pub fn charger_config(db: &Database) -> Result<(), AppError> {
+        let config = Self::lire_config(db)?;
+        if let Ok(mut current) = CONFIG.write() {
+            *current = config;
         }
+        Ok(())
+    }
 
-        // Numéros de piste
-        let has_missing_track_number = album
-            .tracks
+    pub fn lister_regles() -> Vec<ValidationRuleInfo> {
+        let config = CONFIG.read().map(|c| c.clone()).unwrap_or_default();
+        Self::toutes_regles()
             .iter()
-            .any(|t| t.track_number.is_none() || t.track_number == Some(0));
-        if has_missing_track_number {
-            album.issues.push("Numéros de piste manquants".to_string());
-        }
+            .map(|rule| {
+                let reglage = config.get(rule.code()).copied();
+                ValidationRuleInfo {
+                    code: rule.code().to_string(),
+                    description: rule.description().to_string(),
+                    default_severity: rule.severite(),
+                    enabled: reglage != Some(None),
+                    severity: reglage.flatten().unwrap_or_else(|| rule.severite()),
+                }
+            })
+            .collect()
+    }
+
+    /// Active/désactive une règle ou change sa gravité
+    pub fn configurer_regle(
+        db: &Database,
+        code: &str,
+        enabled: bool,
+        severity: Option<Severity>,
+    ) -> Result<ValidationRuleInfo, AppError> {
+        Self::enregistrer_regle(db, code, enabled, severity)?;
+        Self::charger_config(db)?;
+
+        Self::lister_regles()
+            .into_iter()
+            .find(|r| r.code == code)
+            .ok_or_else(|| AppError::Unknown("Règle introuvable après mise à jour".into()))
     }
 
-    fn check_files(album: &mut Album) {
-        // Fix for compilation year range (e.g. 1971-15) & Lifetime safety
-        let year_str = if let (Some(min), Some(max)) = (album.year_min, album.year_max) {
-             if min > 0 && max > 0 && min != max {
-                 let max_short = max % 100;
-                 format!("{}-{:02}", min, max_short)
-             } else {
-                 album.year.unwrap_or(0).to_string()
-             }
+    /// Enregistre le réglage d'une règle en base, sans toucher à la configuration en mémoire
+    pub fn enregistrer_regle(
+        db: &Database,
+        code: &str,
+        enabled: bool,
+        severity: Option<Severity>,
+    ) -> Result<(), AppError> {
+        let rule = Self::toutes_regles()
+            .into_iter()
+            .find(|r| r.code() == code)
+            .ok_or_else(|| AppError::Validation(format!("Règle inconnue: {}", code)))?;
+
+        let value = if enabled {
+            severity.unwrap_or_else(|| rule.severite()).as_str()
         } else {
-             album.year.unwrap_or(0).to_string()
+            "off"
         };
-
-        // Préparation du contexte pour la playlist
-        // NOTE: On passe toujours les infos même si vides pour permettre le debug dans detecter_fichiers_inutiles
-        let context_args = Some((album.artist.as_str(), year_str.as_str(), album.title.as_str()));
-
-        if let Ok(junk) = InspectorService::detecter_fichiers_inutiles(&album.path, context_args) {
-            if !junk.is_empty() {
-                album.issues.push(format!(
-                    "Fichiers inutiles détectés ({} fichiers)",
-                    junk.len()
-                ));
-            }
-        }
-
-        // Conformité noms et majuscules
-        let mut bad_filenames = 0;
-        let mut bad_casing = 0;
-
-        for t in &album.tracks {
-            let path = Path::new(&t.path);
-            if let Some(name) = path.file_name() {
-                let name_str = name.to_string_lossy();
-                if !name_str.chars().next().is_some_and(|c| c.is_ascii_digit()) {
-                    bad_filenames += 1;
-                }
-            }
-
-            if t.title.len() > 3
-                && (t.title == t.title.to_uppercase() || t.title == t.title.to_lowercase())
-            {
-                bad_casing += 1;
-            }
-        }
-
-        if bad_filenames > 0 {
-            album.issues.push(format!(
-                "Noms de fichiers non conformes ({} pistes)",
-                bad_filenames
-            ));
-        }
-        if bad_casing > 0 {
-            album.issues.push(format!(
-                "Problèmes de majuscules détectés ({} pistes)",
-                bad_casing
-            ));
-        }
+        SettingsService::ecrire(db, &format!("{}{}", SETTING_PREFIX, code), value)
     }
-
-
-
-
 }
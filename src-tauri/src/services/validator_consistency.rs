@@ -0,0 +1,97 @@
+use super::validator_rules::{Constat, ValidationRule};
+use super::{ConsistencyService, InspectorService};
+use crate::models::changeset::TagField;
+use crate::models::{Album, Severity, SuggestedFix};
+use std::path::Path;
+
+fn libelle_champ(field: TagField) -> &'static str {
+    match field {
+        TagField::Title => "Titre",
+        TagField::Artist => "Artiste",
+        TagField::AlbumArtist => "Artiste de l'album",
+        TagField::Album => "Album",
+        TagField::Year => "Année",
+        TagField::TrackNumber => "Numéro de piste",
+        TagField::TrackTotal => "Total de pistes",
+        TagField::DiscNumber => "Numéro de disque",
+        TagField::Genre => "Genre",
+        TagField::Compilation => "Compilation",
+    }
+}
+
+pub struct PlaylistNameRule;
+
+impl ValidationRule for PlaylistNameRule {
+    fn code(&self) -> &'static str {
+        "PLAYLIST_NAME"
+    }
+    fn description(&self) -> &'static str {
+        "Playlist mal nommée"
+    }
+    fn severite(&self) -> Severity {
+        Severity::Warning
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::WritePlaylist)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        let playlists = InspectorService::playlists(Path::new(&album.path));
+        if playlists.is_empty()
+            || playlists
+                .iter()
+                .any(|nom| InspectorService::playlist_conforme(album, nom))
+        {
+            return Vec::new();
+        }
+        vec![Constat {
+            message: format!("Playlist mal nommée ({})", playlists.join(", ")),
+            tracks: Vec::new(),
+        }]
+    }
+}
+
+pub struct TagConsistencyRule;
+
+impl ValidationRule for TagConsistencyRule {
+    fn code(&self) -> &'static str {
+        "TAG_INCONSISTENT"
+    }
+    fn description(&self) -> &'static str {
+        "Tags d'album différents selon les pistes"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::Unify)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        ConsistencyService::champs_incoherents(album)
+            .into_iter()
+            .map(|incoherence| {
+                let valeurs: Vec<String> = incoherence
+                    .values
+                    .iter()
+                    .map(|v| format!("\"{}\" ({})", v.value, v.tracks.len()))
+                    .collect();
+                let disque = incoherence
+                    .disc
+                    .filter(|_| album.tracks.iter().any(|t| t.disc_number.unwrap_or(1) > 1))
+                    .map(|d| format!(", disque {}", d))
+                    .unwrap_or_default();
+                Constat {
+                    message: format!(
+                        "{} incohérent{} : {}",
+                        libelle_champ(incoherence.field),
+                        disque,
+                        valeurs.join(", ")
+                    ),
+                    // Les pistes minoritaires sont celles à corriger
+                    tracks: incoherence
+                        .values
+                        .iter()
+                        .skip(1)
+                        .flat_map(|v| v.tracks.clone())
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
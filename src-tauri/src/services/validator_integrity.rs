@@ -0,0 +1,71 @@
+use super::validator_rules::{Constat, ValidationRule};
+use crate::models::{Album, IntegrityReport, Severity};
+
+// Codes produits par IntegrityService, avec leur description et gravité par défaut
+const CODES: [(&str, &str, Severity); 5] = [
+    ("DECODE_ERROR", "Erreur de décodage", Severity::Error),
+    ("TRUNCATED", "Fichier tronqué", Severity::Error),
+    (
+        "SAMPLE_COUNT_MISMATCH",
+        "Nombre d'échantillons incohérent",
+        Severity::Warning,
+    ),
+    ("FLAC_MD5_MISMATCH", "MD5 FLAC invalide", Severity::Error),
+    ("NOT_VERIFIABLE", "Format non vérifiable", Severity::Info),
+];
+
+/// Règle alimentée par les rapports de verify_album : elle ne relève rien sans vérification préalable
+pub struct IntegrityRule {
+    code: &'static str,
+    description: &'static str,
+    severite: Severity,
+    rapports: Vec<IntegrityReport>,
+}
+
+impl ValidationRule for IntegrityRule {
+    fn code(&self) -> &'static str {
+        self.code
+    }
+    fn description(&self) -> &'static str {
+        self.description
+    }
+    fn severite(&self) -> Severity {
+        self.severite
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        self.rapports
+            .iter()
+            .filter(|r| album.tracks.iter().any(|t| t.path == r.path))
+            .flat_map(|r| {
+                r.problems
+                    .iter()
+                    .filter(|p| p.code == self.code)
+                    .map(|p| Constat {
+                        message: format!("{} : {}", nom_fichier(&r.path), p.message),
+                        tracks: vec![r.path.clone()],
+                    })
+            })
+            .collect()
+    }
+}
+
+fn nom_fichier(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+pub fn regles_integrite(rapports: &[IntegrityReport]) -> Vec<Box<dyn ValidationRule>> {
+    CODES
+        .iter()
+        .map(|(code, description, severite)| {
+            Box::new(IntegrityRule {
+                code,
+                description,
+                severite: *severite,
+                rapports: rapports.to_vec(),
+            }) as Box<dyn ValidationRule>
+        })
+        .collect()
+}
@@ -0,0 +1,166 @@
+use super::validator_rules::{Constat, ValidationRule};
+use super::NumberingService;
+use crate::models::{Album, Severity, SuggestedFix};
+use std::collections::BTreeMap;
+
+// Règles de cohérence de la numérotation des pistes (trous, doublons, totaux, préfixes)
+
+/// Libellé du disque, seulement pour les albums multi-disques
+fn libelle_disque(album: &Album, disque: u32) -> String {
+    if NumberingService::par_disque(&album.tracks).len() > 1 {
+        format!("disque {} : ", disque)
+    } else {
+        String::new()
+    }
+}
+
+pub struct TrackGapRule;
+
+impl ValidationRule for TrackGapRule {
+    fn code(&self) -> &'static str {
+        "TRACK_GAP"
+    }
+    fn description(&self) -> &'static str {
+        "Trous dans la numérotation"
+    }
+    fn severite(&self) -> Severity {
+        Severity::Warning
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::Renumber)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        let mut constats = Vec::new();
+        for (disque, tracks) in NumberingService::par_disque(&album.tracks) {
+            let numeros: Vec<u32> = tracks
+                .iter()
+                .filter_map(|t| t.track_number)
+                .filter(|&n| n > 0)
+                .collect();
+            let Some(&max) = numeros.iter().max() else {
+                continue;
+            };
+            let manquants: Vec<String> = (1..=max)
+                .filter(|n| !numeros.contains(n))
+                .map(|n| n.to_string())
+                .collect();
+            if !manquants.is_empty() {
+                constats.push(Constat {
+                    message: format!(
+                        "Pistes absentes de la numérotation ({}{})",
+                        libelle_disque(album, disque),
+                        manquants.join(", ")
+                    ),
+                    tracks: Vec::new(),
+                });
+            }
+        }
+        constats
+    }
+}
+
+pub struct TrackDuplicateRule;
+
+impl ValidationRule for TrackDuplicateRule {
+    fn code(&self) -> &'static str {
+        "TRACK_DUPLICATE"
+    }
+    fn description(&self) -> &'static str {
+        "Numéros de piste en double"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::Renumber)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        let mut constats = Vec::new();
+        for (disque, tracks) in NumberingService::par_disque(&album.tracks) {
+            let mut par_numero: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+            for track in tracks {
+                if let Some(n) = track.track_number.filter(|&n| n > 0) {
+                    par_numero.entry(n).or_default().push(track.path.clone());
+                }
+            }
+            for (numero, chemins) in par_numero {
+                if chemins.len() > 1 {
+                    constats.push(Constat {
+                        message: format!(
+                            "Numéro de piste en double ({}n° {}, {} pistes)",
+                            libelle_disque(album, disque),
+                            numero,
+                            chemins.len()
+                        ),
+                        tracks: chemins,
+                    });
+                }
+            }
+        }
+        constats
+    }
+}
+
+pub struct TrackTotalRule;
+
+impl ValidationRule for TrackTotalRule {
+    fn code(&self) -> &'static str {
+        "TRACK_OVER_TOTAL"
+    }
+    fn description(&self) -> &'static str {
+        "Numéro de piste supérieur au total"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::Renumber)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        let tracks: Vec<String> = album
+            .tracks
+            .iter()
+            .filter(|t| matches!((t.track_number, t.track_total), (Some(n), Some(total)) if total > 0 && n > total))
+            .map(|t| t.path.clone())
+            .collect();
+        if tracks.is_empty() {
+            return Vec::new();
+        }
+        vec![Constat {
+            message: format!(
+                "Numéro de piste supérieur au total ({} pistes)",
+                tracks.len()
+            ),
+            tracks,
+        }]
+    }
+}
+
+pub struct TrackPrefixRule;
+
+impl ValidationRule for TrackPrefixRule {
+    fn code(&self) -> &'static str {
+        "TRACK_PREFIX_MISMATCH"
+    }
+    fn description(&self) -> &'static str {
+        "Préfixe du fichier différent du numéro de piste"
+    }
+    fn severite(&self) -> Severity {
+        Severity::Warning
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::Renumber)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        let tracks: Vec<String> = album
+            .tracks
+            .iter()
+            .filter(|t| !NumberingService::prefixe_correspond(t))
+            .map(|t| t.path.clone())
+            .collect();
+        if tracks.is_empty() {
+            return Vec::new();
+        }
+        vec![Constat {
+            message: format!(
+                "Préfixe du fichier différent du numéro de piste ({} pistes)",
+                tracks.len()
+            ),
+            tracks,
+        }]
+    }
+}
@@ -0,0 +1,268 @@
+use super::validator_consistency::{PlaylistNameRule, TagConsistencyRule};
+use super::validator_numbering::{
+    TrackDuplicateRule, TrackGapRule, TrackPrefixRule, TrackTotalRule,
+};
+use super::InspectorService;
+use crate::models::{Album, Severity, SuggestedFix, Track};
+use std::path::Path;
+
+/// Problème relevé par une règle, avant application de la gravité configurée
+pub struct Constat {
+    pub message: String,
+    pub tracks: Vec<String>,
+}
+
+/// Règle du validateur. Le code est stable : il sert de clé de réglage et au frontend.
+pub trait ValidationRule: Send + Sync {
+    fn code(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn severite(&self) -> Severity {
+        Severity::Error
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        None
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat>;
+}
+
+/// Toutes les règles connues, dans l'ordre d'affichage
+pub fn regles() -> Vec<Box<dyn ValidationRule>> {
+    vec![
+        Box::new(CoverRule),
+        Box::new(PlaylistRule),
+        Box::new(PlaylistNameRule),
+        Box::new(YearRule),
+        Box::new(ChampManquantRule {
+            code: "TITLE_MISSING",
+            description: "Titre manquant",
+            manquant: |t| t.title.trim().is_empty(),
+        }),
+        Box::new(ChampManquantRule {
+            code: "ARTIST_MISSING",
+            description: "Artiste manquant",
+            manquant: |t| t.artist.trim().is_empty(),
+        }),
+        Box::new(ChampManquantRule {
+            code: "ALBUM_MISSING",
+            description: "Album manquant",
+            manquant: |t| t.album.trim().is_empty(),
+        }),
+        Box::new(ChampManquantRule {
+            code: "GENRE_MISSING",
+            description: "Genre manquant",
+            manquant: |t| t.genre.as_deref().unwrap_or("").trim().is_empty(),
+        }),
+        Box::new(TrackNumberRule),
+        Box::new(TrackGapRule),
+        Box::new(TrackDuplicateRule),
+        Box::new(TrackTotalRule),
+        Box::new(TrackPrefixRule),
+        Box::new(TagConsistencyRule),
+        Box::new(JunkRule),
+        Box::new(FilenameRule),
+        Box::new(CasingRule),
+    ]
+}
+
+/// Un seul constat pour l'album, avec les pistes concernées
+fn constat_pistes(album: &Album, message: &str, concernee: impl Fn(&Track) -> bool) -> Vec<Constat> {
+    let tracks: Vec<String> = album
+        .tracks
+        .iter()
+        .filter(|t| concernee(t))
+        .map(|t| t.path.clone())
+        .collect();
+    if tracks.is_empty() {
+        return Vec::new();
+    }
+    vec![Constat {
+        message: format!("{} ({} pistes)", message, tracks.len()),
+        tracks,
+    }]
+}
+
+struct CoverRule;
+
+impl ValidationRule for CoverRule {
+    fn code(&self) -> &'static str {
+        "COVER_MISSING"
+    }
+    fn description(&self) -> &'static str {
+        "Cover manquante"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::SearchCover)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        if album.cover_path.is_some() {
+            return Vec::new();
+        }
+        vec![Constat {
+            message: "Cover manquante".to_string(),
+            tracks: Vec::new(),
+        }]
+    }
+}
+
+struct PlaylistRule;
+
+impl ValidationRule for PlaylistRule {
+    fn code(&self) -> &'static str {
+        "PLAYLIST_MISSING"
+    }
+    fn description(&self) -> &'static str {
+        "Playlist manquante"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::WritePlaylist)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        if album.has_playlist {
+            return Vec::new();
+        }
+        vec![Constat {
+            message: "Playlist manquante".to_string(),
+            tracks: Vec::new(),
+        }]
+    }
+}
+
+struct YearRule;
+
+impl ValidationRule for YearRule {
+    fn code(&self) -> &'static str {
+        "YEAR_MISSING"
+    }
+    fn description(&self) -> &'static str {
+        "Année manquante"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::EditTags)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        if album.year.is_some_and(|y| y > 0) {
+            return Vec::new();
+        }
+        let tracks: Vec<String> = album
+            .tracks
+            .iter()
+            .filter(|t| t.year.is_none() || t.year == Some(0))
+            .map(|t| t.path.clone())
+            .collect();
+        if tracks.is_empty() {
+            return Vec::new();
+        }
+        vec![Constat {
+            message: "Année manquante".to_string(),
+            tracks,
+        }]
+    }
+}
+
+struct ChampManquantRule {
+    code: &'static str,
+    description: &'static str,
+    manquant: fn(&Track) -> bool,
+}
+
+impl ValidationRule for ChampManquantRule {
+    fn code(&self) -> &'static str {
+        self.code
+    }
+    fn description(&self) -> &'static str {
+        self.description
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::EditTags)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        constat_pistes(album, self.description, self.manquant)
+    }
+}
+
+struct TrackNumberRule;
+
+impl ValidationRule for TrackNumberRule {
+    fn code(&self) -> &'static str {
+        "TRACK_NUMBER_MISSING"
+    }
+    fn description(&self) -> &'static str {
+        "Numéros de piste manquants"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::Renumber)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        constat_pistes(album, "Numéros de piste manquants", |t| {
+            t.track_number.is_none() || t.track_number == Some(0)
+        })
+    }
+}
+
+struct JunkRule;
+
+impl ValidationRule for JunkRule {
+    fn code(&self) -> &'static str {
+        "JUNK_FILES"
+    }
+    fn description(&self) -> &'static str {
+        "Fichiers inutiles détectés"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::AutoCorrect)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        match InspectorService::detecter_fichiers_inutiles(album) {
+            Ok(junk) if !junk.is_empty() => vec![Constat {
+                message: format!("Fichiers inutiles détectés ({} fichiers)", junk.len()),
+                tracks: Vec::new(),
+            }],
+            _ => Vec::new(),
+        }
+    }
+}
+
+struct FilenameRule;
+
+impl ValidationRule for FilenameRule {
+    fn code(&self) -> &'static str {
+        "FILENAME_NONCONFORM"
+    }
+    fn description(&self) -> &'static str {
+        "Noms de fichiers non conformes"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::AutoCorrect)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        constat_pistes(album, "Noms de fichiers non conformes", |t| {
+            Path::new(&t.path).file_name().is_some_and(|name| {
+                !name
+                    .to_string_lossy()
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_digit())
+            })
+        })
+    }
+}
+
+struct CasingRule;
+
+impl ValidationRule for CasingRule {
+    fn code(&self) -> &'static str {
+        "TITLE_CASING"
+    }
+    fn description(&self) -> &'static str {
+        "Problèmes de majuscules détectés"
+    }
+    fn correction(&self) -> Option<SuggestedFix> {
+        Some(SuggestedFix::AutoCorrect)
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        constat_pistes(album, "Problèmes de majuscules détectés", |t| {
+            t.title.len() > 3
+                && (t.title == t.title.to_uppercase() || t.title == t.title.to_lowercase())
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use super::silence::{SILENCE_DEBUT_MAX_SEC, SILENCE_FIN_MAX_SEC};
+use super::validator_rules::{Constat, ValidationRule};
+use crate::models::{Album, Severity, SilenceReport};
+use std::path::Path;
+
+// Plages écrêtées tolérées avant de signaler la piste (un master « fort » en contient quelques-unes)
+const PLAGES_ECRETEES_MAX: u64 = 10;
+
+/// Silence trop long en début ou fin de piste (alimentée par analyze_silence)
+pub struct LongSilenceRule {
+    rapports: Vec<SilenceReport>,
+}
+
+impl ValidationRule for LongSilenceRule {
+    fn code(&self) -> &'static str {
+        "LONG_SILENCE"
+    }
+    fn description(&self) -> &'static str {
+        "Silence trop long en début ou fin de piste"
+    }
+    fn severite(&self) -> Severity {
+        Severity::Warning
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        rapports_de(&self.rapports, album)
+            .filter_map(|r| {
+                let mut constats = Vec::new();
+                if r.leading_silence_sec > SILENCE_DEBUT_MAX_SEC {
+                    constats.push(format!("{:.1} s au début", r.leading_silence_sec));
+                }
+                if r.trailing_silence_sec > SILENCE_FIN_MAX_SEC {
+                    constats.push(format!("{:.1} s à la fin", r.trailing_silence_sec));
+                }
+                (!constats.is_empty()).then(|| Constat {
+                    message: format!(
+                        "{} : silence de {}",
+                        nom_fichier(&r.path),
+                        constats.join(" et ")
+                    ),
+                    tracks: vec![r.path.clone()],
+                })
+            })
+            .collect()
+    }
+}
+
+/// Écrêtage franc : suites d'échantillons bloqués à pleine échelle
+pub struct ClippingRule {
+    rapports: Vec<SilenceReport>,
+}
+
+impl ValidationRule for ClippingRule {
+    fn code(&self) -> &'static str {
+        "CLIPPING"
+    }
+    fn description(&self) -> &'static str {
+        "Écrêtage"
+    }
+    fn severite(&self) -> Severity {
+        Severity::Warning
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        rapports_de(&self.rapports, album)
+            .filter(|r| r.clipped_runs > PLAGES_ECRETEES_MAX)
+            .map(|r| Constat {
+                message: format!(
+                    "{} : {} plages écrêtées ({} échantillons)",
+                    nom_fichier(&r.path),
+                    r.clipped_runs,
+                    r.clipped_samples
+                ),
+                tracks: vec![r.path.clone()],
+            })
+            .collect()
+    }
+}
+
+fn rapports_de<'a>(
+    rapports: &'a [SilenceReport],
+    album: &'a Album,
+) -> impl Iterator<Item = &'a SilenceReport> {
+    rapports
+        .iter()
+        .filter(move |r| album.tracks.iter().any(|t| t.path == r.path))
+}
+
+fn nom_fichier(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+pub fn regles_silence(rapports: &[SilenceReport]) -> Vec<Box<dyn ValidationRule>> {
+    vec![
+        Box::new(LongSilenceRule {
+            rapports: rapports.to_vec(),
+        }),
+        Box::new(ClippingRule {
+            rapports: rapports.to_vec(),
+        }),
+    ]
+}
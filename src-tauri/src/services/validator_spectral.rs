@@ -0,0 +1,43 @@
+use super::validator_rules::{Constat, ValidationRule};
+use crate::models::{Album, Severity, SpectralReport};
+use std::path::Path;
+
+/// Piste sans perte dont le spectre trahit une source avec perte (alimentée par analyze_spectrum)
+pub struct LossySourceRule {
+    rapports: Vec<SpectralReport>,
+}
+
+impl ValidationRule for LossySourceRule {
+    fn code(&self) -> &'static str {
+        "LOSSY_SOURCE"
+    }
+    fn description(&self) -> &'static str {
+        "Faux sans perte (transcodé)"
+    }
+    fn severite(&self) -> Severity {
+        Severity::Warning
+    }
+    fn verifier(&self, album: &Album) -> Vec<Constat> {
+        self.rapports
+            .iter()
+            .filter(|r| r.lossy_suspect && album.tracks.iter().any(|t| t.path == r.path))
+            .map(|r| Constat {
+                message: format!(
+                    "{} : source probable {}",
+                    Path::new(&r.path)
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                    r.suspected_source.as_deref().unwrap_or("avec perte")
+                ),
+                tracks: vec![r.path.clone()],
+            })
+            .collect()
+    }
+}
+
+pub fn regles_spectrales(rapports: &[SpectralReport]) -> Vec<Box<dyn ValidationRule>> {
+    vec![Box::new(LossySourceRule {
+        rapports: rapports.to_vec(),
+    })]
+}
@@ -0,0 +1,188 @@
+use super::*;
+use crate::db::Database;
+use crate::models::changeset::TagField;
+use crate::models::{Album, AlbumStatus, RenumberMode, Severity, Track};
+
+fn piste(numero: u32, titre: &str) -> Track {
+    let filename = format!("{:02} - {}.flac", numero, titre);
+    let mut track = Track::new(format!("/introuvable/album/{}", filename), filename);
+    track.title = titre.to_string();
+    track.artist = "Artist".to_string();
+    track.album = "Album".to_string();
+    track.year = Some(2001);
+    track.genre = Some("Rock".to_string());
+    track.track_number = Some(numero);
+    track
+}
+
+#[test]
+fn test_regles_configurables() {
+    let db = Database::open(":memory:").unwrap();
+    let mut album = Album::new(
+        "/introuvable/album".to_string(),
+        "Album".to_string(),
+        "Artist".to_string(),
+    );
+    album.tracks = vec![piste(1, "Intro"), piste(2, "SHOUT")];
+
+    ValidatorService::evaluate_album_status(&mut album);
+    let codes: Vec<&str> = album.validation.iter().map(|i| i.code.as_str()).collect();
+    assert_eq!(
+        codes,
+        vec!["COVER_MISSING", "PLAYLIST_MISSING", "TITLE_CASING"]
+    );
+    assert_eq!(
+        album.validation[2].tracks,
+        vec![album.tracks[1].path.clone()]
+    );
+    assert_eq!(album.status, AlbumStatus::Dirty);
+
+    // Cover ignorée, playlist et majuscules en simple avertissement : l'album passe "Clean".
+    // Le réglage est injecté : la configuration partagée par le processus reste intacte.
+    ValidatorService::enregistrer_regle(&db, "COVER_MISSING", false, None).unwrap();
+    ValidatorService::enregistrer_regle(&db, "PLAYLIST_MISSING", true, Some(Severity::Warning))
+        .unwrap();
+    ValidatorService::enregistrer_regle(&db, "TITLE_CASING", true, Some(Severity::Info)).unwrap();
+    let config = ValidatorService::lire_config(&db).unwrap();
+    ValidatorService::evaluer_selon(&mut album, Vec::new(), &config);
+    assert_eq!(album.validation.len(), 2);
+    assert_eq!(album.validation[0].severity, Severity::Warning);
+    assert_eq!(album.status, AlbumStatus::Clean);
+
+    assert!(ValidatorService::enregistrer_regle(&db, "INCONNUE", true, None).is_err());
+}
+
+#[test]
+fn test_numerotation_et_renumerotation() {
+    let mut album = Album::new(
+        "/introuvable/album".to_string(),
+        "Album".to_string(),
+        "Artist".to_string(),
+    );
+    let mut quatre = piste(4, "Four");
+    quatre.track_total = Some(3);
+    let mut doublon = piste(2, "Again");
+    doublon.filename = "05 - Again.flac".to_string();
+    album.tracks = vec![piste(1, "One"), piste(2, "Two"), doublon, quatre];
+
+    ValidatorService::evaluate_album_status(&mut album);
+    let codes: Vec<&str> = album.validation.iter().map(|i| i.code.as_str()).collect();
+    assert!(codes.contains(&"TRACK_GAP"));
+    assert!(codes.contains(&"TRACK_DUPLICATE"));
+    assert!(codes.contains(&"TRACK_OVER_TOTAL"));
+    assert!(codes.contains(&"TRACK_PREFIX_MISMATCH"));
+    let doublons = album
+        .validation
+        .iter()
+        .find(|i| i.code == "TRACK_DUPLICATE")
+        .unwrap();
+    assert_eq!(doublons.tracks.len(), 2);
+
+    NumberingService::renumeroter(&mut album, RenumberMode::DiscTrackOrder);
+    let resultat: Vec<(u32, &str)> = album
+        .tracks
+        .iter()
+        .map(|t| (t.track_number.unwrap(), t.filename.as_str()))
+        .collect();
+    assert_eq!(
+        resultat,
+        vec![
+            (1, "01 - One.flac"),
+            (2, "02 - Two.flac"),
+            (3, "03 - Again.flac"),
+            (4, "04 - Four.flac"),
+        ]
+    );
+    assert!(album.tracks.iter().all(|t| t.track_total == Some(4)));
+    assert!(album.tracks[0].is_modified);
+
+    // Ordre des fichiers : "2 - x" passe avant "10 - x"
+    let mut fichiers: Vec<Track> = (1..=10).rev().map(|n| piste(n, "Song")).collect();
+    for track in &mut fichiers {
+        track.filename = format!("{} - Song.flac", track.track_number.unwrap());
+        track.track_number = None;
+    }
+    album.tracks = fichiers;
+    NumberingService::renumeroter(&mut album, RenumberMode::FilenameOrder);
+    let dixieme = album.tracks.iter().find(|t| t.track_number == Some(10)).unwrap();
+    assert_eq!(dixieme.filename, "10 - Song.flac");
+    assert_eq!(album.tracks[8].filename, "02 - Song.flac");
+}
+
+#[test]
+fn test_coherence_et_unification() {
+    let mut album = Album::new(
+        "/introuvable/album".to_string(),
+        "Album".to_string(),
+        "Artist".to_string(),
+    );
+    let mut autre = piste(3, "Three");
+    autre.album = "Album (Live)".to_string();
+    autre.genre = Some("Pop".to_string());
+    album.tracks = vec![piste(1, "One"), piste(2, "Two"), autre];
+
+    let incoherences = ConsistencyService::champs_incoherents(&album);
+    let champs: Vec<TagField> = incoherences.iter().map(|i| i.field).collect();
+    assert_eq!(champs, vec![TagField::Album, TagField::Genre]);
+    assert_eq!(incoherences[0].values[0].value, "Album");
+    assert_eq!(
+        incoherences[0].values[1].tracks,
+        vec![album.tracks[2].path.clone()]
+    );
+
+    ConsistencyService::unifier(&mut album, Some(TagField::Genre), Some("Jazz".to_string()));
+    assert!(album
+        .tracks
+        .iter()
+        .all(|t| t.genre.as_deref() == Some("Jazz")));
+    ConsistencyService::unifier(&mut album, None, None);
+    assert!(album.tracks.iter().all(|t| t.album == "Album"));
+    assert!(ConsistencyService::champs_incoherents(&album).is_empty());
+
+    // Compilation : artistes différents, années libres, album artist "Various Artists"
+    for (i, track) in album.tracks.iter_mut().enumerate() {
+        track.artist = format!("Artist {}", i);
+        track.album_artist = String::new();
+        track.year = Some(1990 + i as u32);
+    }
+    assert!(CompilationService::est_compilation(&album));
+    assert!(ConsistencyService::champs_incoherents(&album).is_empty());
+    ConsistencyService::unifier(&mut album, Some(TagField::AlbumArtist), None);
+    assert!(album
+        .tracks
+        .iter()
+        .all(|t| t.album_artist == "Various Artists"));
+}
+
+#[test]
+fn test_compilation_et_nom_de_playlist() {
+    let mut album = Album::new(
+        "/introuvable/(1971-85) Hits".to_string(),
+        "(1971-85) Hits".to_string(),
+        "Artist 0".to_string(),
+    );
+    album.tracks = (0..4)
+        .map(|i| {
+            let mut track = piste(i + 1, "Song");
+            track.artist = format!("Artist {}", i);
+            track.album_artist = track.artist.clone();
+            track
+        })
+        .collect();
+    // "feat." ne suffit pas à faire une compilation
+    let mut solo = album.clone();
+    for track in &mut solo.tracks {
+        track.artist = format!("Artist feat. Guest {}", track.path.len());
+        track.album_artist = String::new();
+    }
+    assert!(!CompilationService::est_compilation(&solo));
+
+    assert!(CompilationService::est_compilation(&album));
+    assert_eq!(CompilationService::artiste_album(&album), "Various Artists");
+
+    album.year_min = Some(1971);
+    album.year_max = Some(1985);
+    assert!(InspectorService::playlist_conforme(&album, "Various Artists - (1971-85) Hits"));
+    assert!(InspectorService::playlist_conforme(&album, "hits"));
+    assert!(!InspectorService::playlist_conforme(&album, "Artist 0 - (1971-85) Hits"));
+}
@@ -0,0 +1,76 @@
+use crate::models::AppError;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Copie un WAV en ne gardant que les trames [debut, fin[ : les autres blocs (fmt, LIST...) sont recopiés tels quels
+pub fn rogner_wav(source: &Path, dest: &Path, debut: u64, fin: u64) -> Result<(), AppError> {
+    let mut entree = File::open(source)?;
+    let longueur = entree.metadata()?.len();
+    let mut entete = [0u8; 12];
+    entree.read_exact(&mut entete)?;
+    if &entete[..4] != b"RIFF" || &entete[8..] != b"WAVE" {
+        return Err(AppError::Validation(format!(
+            "{} n'est pas un fichier WAV",
+            source.display()
+        )));
+    }
+
+    // Inventaire des blocs : (identifiant, position des données, taille)
+    let mut blocs = Vec::new();
+    let mut pos = 12u64;
+    let mut bloc = [0u8; 8];
+    while pos + 8 <= longueur {
+        entree.seek(SeekFrom::Start(pos))?;
+        entree.read_exact(&mut bloc)?;
+        let taille = u64::from(u32::from_le_bytes([bloc[4], bloc[5], bloc[6], bloc[7]]));
+        blocs.push(([bloc[0], bloc[1], bloc[2], bloc[3]], pos + 8, taille));
+        pos += 8 + taille + taille % 2;
+    }
+    let alignement = blocs
+        .iter()
+        .find(|b| &b.0 == b"fmt ")
+        .filter(|b| b.2 >= 16)
+        .map(|b| -> io::Result<u64> {
+            let mut fmt = [0u8; 16];
+            entree.seek(SeekFrom::Start(b.1))?;
+            entree.read_exact(&mut fmt)?;
+            Ok(u64::from(u16::from_le_bytes([fmt[12], fmt[13]])))
+        })
+        .transpose()?
+        .filter(|a| *a > 0)
+        .ok_or_else(|| AppError::Validation("Bloc fmt absent ou invalide".to_string()))?;
+
+    let mut sortie = BufWriter::new(File::create(dest)?);
+    sortie.write_all(b"RIFF\0\0\0\0WAVE")?;
+    let mut ecrits = 4u64;
+    for (id, position, taille) in blocs {
+        let (depart, taille) = if &id == b"data" {
+            let trames = taille / alignement;
+            let (debut, fin) = (debut.min(trames), fin.min(trames).max(debut.min(trames)));
+            (position + debut * alignement, (fin - debut) * alignement)
+        } else {
+            (position, taille)
+        };
+        let taille_32 = u32::try_from(taille)
+            .map_err(|_| AppError::Validation("Bloc WAV trop grand".to_string()))?;
+        sortie.write_all(&id)?;
+        sortie.write_all(&taille_32.to_le_bytes())?;
+        entree.seek(SeekFrom::Start(depart))?;
+        let copies = io::copy(&mut (&mut entree).take(taille), &mut sortie)?;
+        if copies < taille {
+            return Err(AppError::Io(format!("{} est tronqué", source.display())));
+        }
+        if taille % 2 == 1 {
+            sortie.write_all(&[0])?;
+        }
+        ecrits += 8 + taille + taille % 2;
+    }
+
+    let riff = u32::try_from(ecrits)
+        .map_err(|_| AppError::Validation("Fichier WAV trop grand".to_string()))?;
+    sortie.seek(SeekFrom::Start(4))?;
+    sortie.write_all(&riff.to_le_bytes())?;
+    sortie.flush()?;
+    Ok(())
+}
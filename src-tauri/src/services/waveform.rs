@@ -0,0 +1,153 @@
+use super::IOService;
+use crate::db::Database;
+use crate::models::{AppError, Waveform, WaveformLevel};
+use rodio::{Decoder, Source};
+use rusqlite::{params, OptionalExtension};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+// Trames par crête de base, avant regroupement aux résolutions demandées
+const TRAMES_BASE: usize = 64;
+// Nombre de points des résolutions produites (moins si la piste est trop courte)
+const RESOLUTIONS: [usize; 3] = [512, 2048, 8192];
+// Entrées gardées en cache (quelques centaines de Ko chacune), les moins récemment lues sont évincées
+const MAX_ENTREES: usize = 200;
+
+/// Crêtes min/max d'une piste à plusieurs résolutions, calculées en un décodage
+pub struct WaveformService;
+
+impl WaveformService {
+    /// Forme d'onde depuis le cache si le contenu du fichier n'a pas changé, sinon décodée puis mise en cache
+    pub fn obtenir(db: &Database, path: &Path) -> Result<Waveform, AppError> {
+        let hash = IOService::empreinte_fichier(path)?;
+        if let Some(mut waveform) = Self::lire_cache(db, &hash)? {
+            // Même contenu à un autre emplacement : on ne garde que le chemin courant
+            waveform.path = path.to_string_lossy().to_string();
+            return Ok(waveform);
+        }
+        let mut waveform = Self::calculer(path)?;
+        waveform.hash = hash;
+        Self::ecrire_cache(db, &waveform)?;
+        Ok(waveform)
+    }
+
+    /// Décode la piste ; `hash` est laissé vide
+    pub fn calculer(path: &Path) -> Result<Waveform, AppError> {
+        let file = File::open(path)?;
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| AppError::Audio(format!("{}: {}", path.display(), e)))?
+            .convert_samples::<f32>();
+        let (channels, sample_rate) = (source.channels(), source.sample_rate());
+        let par_crete = TRAMES_BASE * usize::from(channels.max(1));
+
+        let (mut min, mut max) = (Vec::new(), Vec::new());
+        let (mut bas, mut haut, mut n, mut echantillons) = (f32::MAX, f32::MIN, 0, 0u64);
+        for x in source {
+            bas = bas.min(x);
+            haut = haut.max(x);
+            n += 1;
+            echantillons += 1;
+            if n == par_crete {
+                min.push(bas);
+                max.push(haut);
+                (bas, haut, n) = (f32::MAX, f32::MIN, 0);
+            }
+        }
+        if n > 0 {
+            min.push(bas);
+            max.push(haut);
+        }
+
+        let frames = echantillons / u64::from(channels.max(1));
+        let mut levels: Vec<WaveformLevel> = RESOLUTIONS
+            .iter()
+            .map(|&points| regrouper(&min, &max, points, frames))
+            .collect();
+        // Piste courte : les résolutions fines se confondent
+        levels.dedup_by_key(|l| l.max.len());
+        Ok(Waveform {
+            path: path.to_string_lossy().to_string(),
+            hash: String::new(),
+            sample_rate,
+            channels,
+            frames,
+            levels,
+        })
+    }
+
+    fn lire_cache(db: &Database, hash: &str) -> Result<Option<Waveform>, AppError> {
+        let conn = db
+            .conn
+            .lock()
+            .map_err(|_| AppError::DatabaseError("Lock error".into()))?;
+
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT waveform FROM waveform_cache WHERE hash = ?1",
+                params![hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if json.is_some() {
+            conn.execute(
+                "UPDATE waveform_cache SET last_used = (SELECT MAX(last_used) + 1 FROM waveform_cache)
+                 WHERE hash = ?1",
+                params![hash],
+            )?;
+        }
+        // Une entrée illisible (ancien format) est simplement recalculée
+        Ok(json.and_then(|j| serde_json::from_str(&j).ok()))
+    }
+
+    fn ecrire_cache(db: &Database, waveform: &Waveform) -> Result<(), AppError> {
+        let json = serde_json::to_string(waveform).map_err(|e| AppError::Unknown(e.to_string()))?;
+        let conn = db
+            .conn
+            .lock()
+            .map_err(|_| AppError::DatabaseError("Lock error".into()))?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO waveform_cache (hash, waveform, last_used)
+             VALUES (?1, ?2, (SELECT COALESCE(MAX(last_used), 0) + 1 FROM waveform_cache))",
+            params![waveform.hash, json],
+        )?;
+        drop(conn);
+        Self::elaguer(db, MAX_ENTREES)
+    }
+
+    /// Ne garde que les `garder` formes d'onde lues ou calculées le plus récemment
+    pub(super) fn elaguer(db: &Database, garder: usize) -> Result<(), AppError> {
+        let conn = db
+            .conn
+            .lock()
+            .map_err(|_| AppError::DatabaseError("Lock error".into()))?;
+
+        conn.execute(
+            "DELETE FROM waveform_cache WHERE hash NOT IN
+             (SELECT hash FROM waveform_cache ORDER BY last_used DESC LIMIT ?1)",
+            params![garder as i64],
+        )?;
+        Ok(())
+    }
+}
+
+/// Ramène les crêtes de base à `points` crêtes (au plus une par crête de base)
+fn regrouper(min: &[f32], max: &[f32], points: usize, frames: u64) -> WaveformLevel {
+    let points = points.min(min.len());
+    let (mut bas, mut haut) = (Vec::with_capacity(points), Vec::with_capacity(points));
+    for i in 0..points {
+        let (debut, fin) = (i * min.len() / points, (i + 1) * min.len() / points);
+        bas.push(min[debut..fin].iter().copied().fold(f32::MAX, f32::min));
+        haut.push(max[debut..fin].iter().copied().fold(f32::MIN, f32::max));
+    }
+    WaveformLevel {
+        frames_per_point: if points == 0 {
+            0.0
+        } else {
+            frames as f64 / points as f64
+        },
+        min: bas,
+        max: haut,
+    }
+}
@@ -0,0 +1,90 @@
+use super::test_fixtures::{dossier_temp, ecrire_wav, pcm16};
+use super::waveform::WaveformService;
+use crate::db::Database;
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// WAV PCM 16 bits stéréo 44,1 kHz : une seconde de silence puis une seconde de sinus 440 Hz
+fn ecrire_silence_puis_sinus(path: &Path, amplitude: f64) {
+    let taux = 44_100u32;
+    let echantillons: Vec<i32> = (0..taux * 2)
+        .flat_map(|n| {
+            let v = if n < taux {
+                0.0
+            } else {
+                amplitude * (2.0 * PI * 440.0 * f64::from(n) / f64::from(taux)).sin()
+            };
+            [pcm16(v); 2]
+        })
+        .collect();
+    ecrire_wav(path, taux, 2, 16, &echantillons);
+}
+
+#[test]
+fn test_forme_d_onde_et_cache() {
+    let dir = dossier_temp("waveform");
+    let piste = dir.join("piste.wav");
+    ecrire_silence_puis_sinus(&piste, 0.5);
+
+    let waveform = WaveformService::calculer(&piste).unwrap();
+    assert_eq!((waveform.frames, waveform.channels), (88_200, 2));
+    let tailles: Vec<usize> = waveform.levels.iter().map(|l| l.max.len()).collect();
+    assert_eq!(tailles, vec![512, 1379]); // Au plus une crête par 64 trames
+
+    let grossier = &waveform.levels[0];
+    assert!((grossier.frames_per_point - 88_200.0 / 512.0).abs() < 1e-9);
+    assert!(grossier.max[..250].iter().all(|&m| m == 0.0));
+    assert!(grossier.max[260..].iter().all(|&m| (m - 0.5).abs() < 0.01));
+    assert!(grossier.min[260..].iter().all(|&m| (m + 0.5).abs() < 0.01));
+
+    // Second appel : servi par le cache, y compris pour une copie du fichier
+    let db = Database::open(":memory:").unwrap();
+    let premiere = WaveformService::obtenir(&db, &piste).unwrap();
+    assert_eq!(premiere.hash.len(), 32);
+    let copie = dir.join("copie.wav");
+    fs::copy(&piste, &copie).unwrap();
+    fs::write(&piste, b"plus un fichier audio").unwrap();
+    let depuis_cache = WaveformService::obtenir(&db, &copie).unwrap();
+    assert_eq!(depuis_cache.levels, premiere.levels);
+    assert_eq!(depuis_cache.path, copie.to_string_lossy());
+    assert!(WaveformService::obtenir(&db, &piste).is_err());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_cache_borne_aux_plus_recentes() {
+    let dir = dossier_temp("waveform_lru");
+    let db = Database::open(":memory:").unwrap();
+    let pistes: Vec<_> = [0.2, 0.4, 0.6]
+        .iter()
+        .enumerate()
+        .map(|(i, &amplitude)| {
+            let piste = dir.join(format!("{}.wav", i));
+            ecrire_silence_puis_sinus(&piste, amplitude);
+            piste
+        })
+        .collect();
+    let a = WaveformService::obtenir(&db, &pistes[0]).unwrap();
+    let b = WaveformService::obtenir(&db, &pistes[1]).unwrap();
+    // Relire A le rend plus récent que B, qui sera évincé
+    WaveformService::obtenir(&db, &pistes[0]).unwrap();
+    let c = WaveformService::obtenir(&db, &pistes[2]).unwrap();
+    WaveformService::elaguer(&db, 2).unwrap();
+
+    let conn = db.conn.lock().unwrap();
+    let mut stmt = conn.prepare("SELECT hash FROM waveform_cache").unwrap();
+    let mut restants: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .map(|h| h.unwrap())
+        .collect();
+    restants.sort();
+    let mut attendus = vec![a.hash, c.hash];
+    attendus.sort();
+    assert_eq!(restants, attendus);
+    assert!(!restants.contains(&b.hash));
+
+    fs::remove_dir_all(&dir).ok();
+}
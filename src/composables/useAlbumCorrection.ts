@@ -1,6 +1,6 @@
 import { ref, type Ref } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
-import type { Album } from '../types';
+import type { Album, ChangeSet } from '../types';
 import { useToastStore } from '../stores/toast';
 
 function handleError(e: unknown, toast: ReturnType<typeof useToastStore>, context: string): string {
@@ -35,6 +35,8 @@ export function useAlbumCorrection(
     onUpdate?: () => void
 ) {
     const originalAlbums = ref<Map<string, Album>>(new Map());
+    // Plan calculé sur l'aperçu, seul contenu que apply_auto_correct exécutera
+    const pendingPlans = ref<Map<string, ChangeSet>>(new Map());
     const toast = useToastStore();
     
     function notifyUpdate() {
@@ -67,25 +69,36 @@ export function useAlbumCorrection(
     async function autoCorrectAlbum(albumId: string) {
         await handleAlbumOperation(
             albumId,
-            (album) => {
+            async (album) => {
                 if (!originalAlbums.value.has(albumId)) {
                     originalAlbums.value.set(albumId, JSON.parse(JSON.stringify(album)));
                 }
-                return invoke<Album>('preview_auto_correct', { album });
+                const corrected = await invoke<Album>('preview_auto_correct', { album });
+                const plan = await invoke<ChangeSet>('plan_auto_correct', { album: corrected });
+                return { corrected, plan };
+            },
+            ({ corrected, plan }, index) => {
+                albums.value[index] = corrected;
+                pendingPlans.value.set(albumId, plan);
             },
-            (corrected, index) => albums.value[index] = corrected,
             'Prévisualisation de l\'auto-correction.',
             'Erreur auto-correction'
         );
     }
 
     async function applyAutoCorrect(albumId: string) {
+        const plan = pendingPlans.value.get(albumId);
+        if (!plan) {
+            toast.error('Aucun plan à appliquer : relancez l\'auto-correction.');
+            return;
+        }
         await handleAlbumOperation(
             albumId,
-            (album) => invoke<Album>('apply_auto_correct', { album }),
+            (album) => invoke<Album>('apply_auto_correct', { album, plan }),
             (final, index) => {
                 albums.value[index] = final;
                 originalAlbums.value.delete(albumId);
+                pendingPlans.value.delete(albumId);
             },
             'Corrections appliquées.',
             'Erreur application'
@@ -94,6 +107,7 @@ export function useAlbumCorrection(
 
     function cancelAutoCorrect(albumId: string) {
         const original = originalAlbums.value.get(albumId);
+        pendingPlans.value.delete(albumId);
         if (original) {
             const index = albums.value.findIndex(a => a.id === albumId);
             if (index !== -1) {
@@ -105,6 +119,10 @@ export function useAlbumCorrection(
         }
     }
 
+    function getPendingPlan(albumId: string): ChangeSet | undefined {
+        return pendingPlans.value.get(albumId);
+    }
+
     function hasPendingCorrection(albumId: string): boolean {
         return originalAlbums.value.has(albumId);
     }
@@ -158,6 +176,7 @@ export function useAlbumCorrection(
         applyAutoCorrect,
         cancelAutoCorrect,
         hasPendingCorrection,
+        getPendingPlan,
         saveAlbum,
         applyMetadata
     };
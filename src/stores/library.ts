@@ -26,7 +26,7 @@ export const useLibraryStore = defineStore('library', () => {
     // Pass saveState as callback to persist changes made during correction
     const { 
         autoCorrectAlbum, applyAutoCorrect, cancelAutoCorrect, 
-        hasPendingCorrection, saveAlbum, applyMetadata
+        hasPendingCorrection, getPendingPlan, saveAlbum, applyMetadata
     } = useAlbumCorrection(albums, isLoading, error, saveState);
 
     async function scanDirectory(path: string, isAutoLoad = false) {
@@ -193,7 +193,7 @@ export const useLibraryStore = defineStore('library', () => {
     return {
         albums, currentPath, isLoading, error,
         scanDirectory, getAlbumById, autoCorrectAlbum, applyAutoCorrect,
-        cancelAutoCorrect, hasPendingCorrection, saveAlbum, removeAlbum,
+        cancelAutoCorrect, hasPendingCorrection, getPendingPlan, saveAlbum, removeAlbum,
         updateAlbumTracksField, refreshAlbum, loadLibrary, applyMetadata
     };
 });
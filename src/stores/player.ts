@@ -1,6 +1,7 @@
 import { defineStore } from 'pinia'
 import { ref } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import type { Track } from '../types'
 import { useToastStore } from './toast'
 
@@ -23,28 +24,19 @@ export const usePlayerStore = defineStore('player', () => {
 
   const toast = useToastStore()
   
-  let timer: number | null = null
+  // Position, état et erreurs publiés par le thread audio
+  listen<{ position_sec: number; duration_sec: number | null }>('player-position', (event) => {
+    currentTime.value = Math.floor(event.payload.position_sec)
+    if (event.payload.duration_sec) duration.value = Math.round(event.payload.duration_sec)
+  })
 
-  function startTimer() {
-    if (timer) clearInterval(timer)
-    timer = setInterval(() => {
-      if (currentTime.value < duration.value) {
-        currentTime.value += 1
-      } else {
-        // Fin de piste : essayer de passer à la suivante
-        if (currentIndex.value < queue.value.length - 1) {
-          next()
-        } else {
-          stop()
-        }
-      }
-    }, 1000) as unknown as number
-  }
+  listen<{ status: string }>('player-state', (event) => {
+    isPlaying.value = event.payload.status === 'Playing'
+  })
 
-  function stopTimer() {
-    if (timer) clearInterval(timer)
-    timer = null
-  }
+  listen<{ path: string; message: string }>('player-error', (event) => {
+    toast.error(`Erreur lecture: ${event.payload.message}`)
+  })
 
   async function play(track: Track, albumCoverPath?: string | null, newQueue?: Track[]) {
     try {
@@ -70,8 +62,8 @@ export const usePlayerStore = defineStore('player', () => {
         return
       }
 
-      // Nouvelle lecture
-      await invoke('play_track', { path: track.path })
+      // Nouvelle lecture : la file est confiée au backend qui gère l'enchaînement
+      await invoke('play_album', { tracks: queue.value, startIndex: currentIndex.value })
       currentTrack.value = track
       duration.value = track.duration_sec || 0
       currentTime.value = 0
@@ -79,30 +71,36 @@ export const usePlayerStore = defineStore('player', () => {
         currentCoverPath.value = albumCoverPath
       }
       isPlaying.value = true
-      startTimer()
     } catch (error) {
       toast.error(`Erreur lecture: ${error}`)
     }
   }
 
+  // Synchronisation avec la file du backend (suivant/précédent et enchaînement automatique)
+  listen<{ index: number; entry: { path: string } }>('player-track-changed', (event) => {
+    const index = queue.value.findIndex(t => t.path === event.payload.entry.path)
+    if (index === -1) return
+    currentIndex.value = index
+    currentTrack.value = queue.value[index]
+    duration.value = queue.value[index].duration_sec || 0
+    currentTime.value = 0
+  })
+
   async function next() {
     if (queue.value.length > 0 && currentIndex.value < queue.value.length - 1) {
-      const nextTrack = queue.value[currentIndex.value + 1]
-      await play(nextTrack, currentCoverPath.value) // Garde la cover actuelle
+      await invoke('next_track')
     }
   }
 
   async function previous() {
     // Si on est à > 3 secondes, on restart la piste, sinon précédente
     if (currentTime.value > 3) {
-      await invoke('play_track', { path: currentTrack.value?.path })
-      currentTime.value = 0
+      await seek(0)
       return
     }
 
     if (queue.value.length > 0 && currentIndex.value > 0) {
-      const prevTrack = queue.value[currentIndex.value - 1]
-      await play(prevTrack, currentCoverPath.value)
+      await invoke('previous_track')
     }
   }
 
@@ -110,7 +108,6 @@ export const usePlayerStore = defineStore('player', () => {
     try {
       await invoke('pause_track')
       isPlaying.value = false
-      stopTimer()
     } catch (error) {
       toast.error(`Erreur pause: ${error}`)
     }
@@ -120,7 +117,6 @@ export const usePlayerStore = defineStore('player', () => {
     try {
       await invoke('resume_track')
       isPlaying.value = true
-      startTimer()
     } catch (error) {
       toast.error(`Erreur resume: ${error}`)
     }
@@ -132,7 +128,6 @@ export const usePlayerStore = defineStore('player', () => {
       currentTrack.value = null
       isPlaying.value = false
       currentTime.value = 0
-      stopTimer()
     } catch (error) {
       toast.error(`Erreur stop: ${error}`)
     }
@@ -7,6 +7,9 @@ export interface Track {
     album: string;
     year?: number;
     track_number?: number;
+    track_total?: number;
+    disc_number?: number;
+    compilation?: boolean;
     genre?: string;
     duration_sec: number;
     format: string;
@@ -35,6 +38,17 @@ export interface Album {
     tracks: Track[];
     status: AlbumStatus;
     issues?: string[];
+    validation?: ValidationIssue[];
+}
+
+export type Severity = 'error' | 'warning' | 'info';
+
+export interface ValidationIssue {
+    code: string;
+    severity: Severity;
+    message: string;
+    tracks: string[];
+    fix?: 'auto_correct' | 'search_cover' | 'write_playlist' | 'edit_tags' | 'renumber' | 'unify';
 }
 
 export interface MusicBrainzRelease {
@@ -45,7 +59,54 @@ export interface MusicBrainzRelease {
     country?: string;
 }
 
+export interface JunkEntry {
+    path: string;
+    size: number;
+    category: 'cover' | 'image' | 'text' | 'system' | 'other';
+    action: 'delete' | 'rename';
+    target?: string;
+    reason: string;
+    rule: string;
+}
+
+// Résultat de convert_file : champs de la source qui ne se relisent pas dans la sortie
+export interface ConvertResult {
+    source: string;
+    output: string;
+    format: 'mp3' | 'flac' | 'opus' | 'wav' | 'aac';
+    encoder: string;
+    lost_fields: string[];
+    tag_error?: string | null;
+}
+
+// Original gardé tant que l'utilisateur n'a pas accepté la perte de champs
+export interface PendingDeletion {
+    path: string;
+    title: string;
+    lost: string[];
+}
+
 export interface ScanResult {
     albums: Album[];
     errors: string[];
 }
+
+export type TagField = 'Title' | 'Artist' | 'AlbumArtist' | 'Album' | 'Year' | 'TrackNumber'
+    | 'TrackTotal' | 'DiscNumber' | 'Genre' | 'Compilation';
+
+export interface FieldChange {
+    field: TagField;
+    old?: string | null;
+    new?: string | null;
+}
+
+// Plan d'auto-correction calculé par plan_auto_correct, renvoyé tel quel à apply_auto_correct
+export interface ChangeSet {
+    album_path: string;
+    fingerprint: string;
+    folder_rename?: { from: string; to: string; reason: string } | null;
+    file_renames: { from: string; to: string; reason: string }[];
+    tag_diffs: { file: string; changes: FieldChange[]; reason: string }[];
+    cover_moves: { from: string; to: string; reason: string; rule: string }[];
+    deletions: { path: string; size: number; reason: string; rule: string }[];
+}